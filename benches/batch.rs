@@ -0,0 +1,31 @@
+use std::time::{Duration, Instant};
+
+use rusterators::generators::{BoostedGenerator, GeneratorChannel};
+
+const ELEMENTS: u64 = 1_000_000;
+const BATCH_SIZE: u64 = 64;
+
+/// Iterates [gen] to its end and reports the duration together with the number of context switches it took
+fn measure(name: &str, switches: u64, gen: impl Iterator<Item=u64>) -> Duration {
+    let start = Instant::now();
+    let sum: u64 = gen.sum();
+    let elapsed = start.elapsed();
+    assert_eq!(sum, ELEMENTS * (ELEMENTS - 1) / 2);
+    println!("{:<8} {:>8} switches {:?} ({:?} per element)", name, switches, elapsed, elapsed / ELEMENTS as u32);
+    elapsed
+}
+
+fn main() {
+    let single = measure("single", ELEMENTS, BoostedGenerator::new(|chan| {
+        chan.yield_all(0..ELEMENTS);
+    }));
+    let batched = measure("batched", ELEMENTS / BATCH_SIZE, BoostedGenerator::new(|chan| {
+        let mut start = 0;
+        while start < ELEMENTS {
+            let end = (start + BATCH_SIZE).min(ELEMENTS);
+            chan.yield_batch((start..end).collect());
+            start = end;
+        }
+    }));
+    println!("batched/single: {:.3}", batched.as_secs_f64() / single.as_secs_f64());
+}
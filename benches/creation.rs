@@ -0,0 +1,40 @@
+use std::time::{Duration, Instant};
+
+use std::cell::RefCell;
+
+use rusterators::coroutines::{Coroutine, StackFactory, StackPool};
+
+const ITERATIONS: u32 = 10_000;
+const STACK_SIZE: usize = 16 * 1024;
+
+/// Runs [create_and_run] ITERATIONS times and reports the average duration of a single run
+fn measure(name: &str, create_and_run: impl Fn()) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        create_and_run();
+    }
+    let per_run = start.elapsed() / ITERATIONS;
+    println!("{:<10} {:?} per coroutine", name, per_run);
+    per_run
+}
+
+fn main() {
+    let boxed = measure("boxed", || {
+        let mut co = Coroutine::<(), (), ()>::new_with_factory(StackFactory::of_size(STACK_SIZE), |_, _| ());
+        co.resume(());
+    });
+    let unboxed = measure("unboxed", || {
+        let mut co = Coroutine::<(), (), ()>::new_unboxed_with_factory(StackFactory::of_size(STACK_SIZE), |_, _| ());
+        co.resume(());
+    });
+    println!("unboxed/boxed: {:.3}", unboxed.as_secs_f64() / boxed.as_secs_f64());
+
+    let pool = RefCell::new(StackPool::new(STACK_SIZE));
+    let pooled = measure("pooled", || {
+        let factory = pool.borrow_mut().factory();
+        let mut co = Coroutine::<(), (), ()>::new_with_factory(factory, |_, _| ()).keep_stack(true);
+        co.resume(());
+        co.recycle_into(&mut pool.borrow_mut());
+    });
+    println!("pooled/boxed: {:.3}, fresh stacks mapped by the pool: {}", pooled.as_secs_f64() / boxed.as_secs_f64(), pool.borrow().misses());
+}
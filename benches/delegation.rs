@@ -0,0 +1,41 @@
+use std::time::{Duration, Instant};
+
+use rusterators::generators::{BoostedGenerator, GeneratorChannel};
+
+const ELEMENTS: u64 = 10_000;
+const DEPTHS: [u32; 4] = [1, 10, 100, 1000];
+
+/// Yields ELEMENTS numbers from within [depth] generators each handing on the values of the one below
+/// [relay] makes each level yield the values on its own instead of delegating them by yield_from
+fn nested(depth: u32, relay: bool) -> BoostedGenerator<'static, u64, (), ()> {
+    BoostedGenerator::new(move |chan| {
+        if depth == 0 {
+            chan.yield_all(0..ELEMENTS);
+        } else if relay {
+            for val in nested(depth - 1, relay) {
+                chan.yield_val(val);
+            }
+        } else {
+            chan.yield_from(nested(depth - 1, relay)).unwrap();
+        }
+    })
+}
+
+/// Iterates the nested generators to their end and reports the duration per element
+fn measure(name: &str, depth: u32, relay: bool) -> Duration {
+    let gen = nested(depth, relay);
+    let start = Instant::now();
+    let sum: u64 = gen.sum();
+    let elapsed = start.elapsed() / ELEMENTS as u32;
+    assert_eq!(sum, ELEMENTS * (ELEMENTS - 1) / 2);
+    println!("{:<10} depth {:>5} {:?} per element", name, depth, elapsed);
+    elapsed
+}
+
+fn main() {
+    for depth in DEPTHS {
+        let delegated = measure("yield_from", depth, false);
+        let relayed = measure("relay", depth, true);
+        println!("yield_from/relay: {:.3}", delegated.as_secs_f64() / relayed.as_secs_f64());
+    }
+}
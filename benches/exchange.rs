@@ -0,0 +1,16 @@
+use std::time::Instant;
+
+use rusterators::generators::{BoringGenerator, GeneratorChannel};
+
+const ELEMENTS: u32 = 10_000_000;
+
+/// Measures the round trip of a single yield of a word sized value resumed by a zero sized one
+/// Both values are moved through the exchange containers, so this is the baseline for any fast path skipping them
+fn main() {
+    let gen = BoringGenerator::new(|chan| chan.yield_all(0..ELEMENTS));
+    let start = Instant::now();
+    let sum: u64 = gen.map(u64::from).sum();
+    let elapsed = start.elapsed();
+    assert_eq!(sum, u64::from(ELEMENTS) * u64::from(ELEMENTS - 1) / 2);
+    println!("{} yields {:?} ({:?} per yield)", ELEMENTS, elapsed, elapsed / ELEMENTS);
+}
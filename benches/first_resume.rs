@@ -0,0 +1,39 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use rusterators::coroutines::{Coroutine, StackFactory};
+
+const COROUTINES: usize = 2_000;
+const STACK_SIZE: usize = 256 * 1024;
+/// Bytes of stack the routine uses on its first resume, which faults in that many fresh pages unless the stack has been touched before
+const DEPTH: usize = 192 * 1024;
+
+/// Uses roughly [remaining] bytes of stack by recursing with frames holding a page sized buffer each
+#[inline(never)]
+fn descend(remaining: usize) -> usize {
+    let mut frame = [0u8; 4096];
+    black_box(&mut frame);
+    if remaining > frame.len() { descend(remaining - frame.len()) + frame[0] as usize } else { frame[0] as usize }
+}
+
+/// Measures the first resume of COROUTINES coroutines whose stacks are built by [factory] up front and reports the 99th percentile
+fn measure(name: &str, factory: impl Fn() -> StackFactory) -> Duration {
+    let mut samples: Vec<_> = (0..COROUTINES).map(|_| {
+        let mut co = Coroutine::<(), usize, ()>::new_unboxed_with_factory(factory(), |_, _| descend(DEPTH));
+        let start = Instant::now();
+        let result = co.resume(()).unwrap_return();
+        let elapsed = start.elapsed();
+        assert_eq!(result, 0);
+        elapsed
+    }).collect();
+    samples.sort();
+    let p99 = samples[samples.len() * 99 / 100];
+    println!("{:<12} p50 {:?} p99 {:?}", name, samples[samples.len() / 2], p99);
+    p99
+}
+
+fn main() {
+    let lazy = measure("lazy", || StackFactory::of_size(STACK_SIZE));
+    let touched = measure("pre-touched", || StackFactory::of_size_populated(STACK_SIZE));
+    println!("pre-touched/lazy p99: {:.3}", touched.as_secs_f64() / lazy.as_secs_f64());
+}
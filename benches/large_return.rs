@@ -0,0 +1,31 @@
+use std::time::{Duration, Instant};
+
+use rusterators::coroutines::{Coroutine, ResumeResult};
+
+const ELEMENTS: u32 = 5_000_000;
+
+/// Measures the average yield of a u32 by a coroutine returning a value of type [R]
+/// The return value is moved once only, so the duration per yield shouldn't depend on its size
+fn measure<R: 'static>(name: &str, ret: impl FnOnce() -> R + 'static) -> Duration {
+    let mut co = Coroutine::<u32, R, ()>::new(move |chan, _| {
+        for val in 0..ELEMENTS {
+            chan.suspend(val);
+        }
+        ret()
+    });
+    let start = Instant::now();
+    let mut sum = 0u64;
+    while let ResumeResult::Yield(val) = co.resume(()) {
+        sum += u64::from(val);
+    }
+    let per_yield = start.elapsed() / ELEMENTS;
+    assert_eq!(sum, u64::from(ELEMENTS) * u64::from(ELEMENTS - 1) / 2);
+    println!("{:<12} {:?} per yield", name, per_yield);
+    per_yield
+}
+
+fn main() {
+    let unit = measure("unit return", || ());
+    let large = measure("256B return", || [7u8; 256]);
+    println!("256B/unit: {:.3}", large.as_secs_f64() / unit.as_secs_f64());
+}
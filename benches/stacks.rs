@@ -0,0 +1,27 @@
+use std::time::{Duration, Instant};
+
+use rusterators::coroutines::StackFactory;
+use rusterators::generators::{BoostedGenerator, GeneratorChannel};
+
+const GENERATORS: usize = 10_000;
+const STACK_SIZE: usize = 16 * 1024;
+
+/// Creates GENERATORS generators building their stacks by [factory], keeps all of them alive at once and reports the average duration per generator
+fn measure(name: &str, factory: impl Fn() -> StackFactory) -> Duration {
+    let start = Instant::now();
+    let mut generators: Vec<_> = (0..GENERATORS)
+        .map(|i| BoostedGenerator::new_with_factory(factory(), move |chan| chan.yield_val(i)))
+        .collect();
+    let sum: usize = generators.iter_mut().filter_map(Iterator::next).sum();
+    drop(generators);
+    let per_generator = start.elapsed() / GENERATORS as u32;
+    assert_eq!(sum, (0..GENERATORS).sum());
+    println!("{:<12} {:?} per generator", name, per_generator);
+    per_generator
+}
+
+fn main() {
+    let protected = measure("protected", || StackFactory::of_size(STACK_SIZE));
+    let unprotected = measure("unprotected", || StackFactory::unprotected(STACK_SIZE));
+    println!("unprotected/protected: {:.3}", unprotected.as_secs_f64() / protected.as_secs_f64());
+}
@@ -1,15 +1,19 @@
-use std::panic::{catch_unwind, AssertUnwindSafe};
 use rusterators::generators::{BoostedGenerator, GeneratorChannel, ResultingGenerator};
 fn main() {
     let mut g=BoostedGenerator::new(|g| {
         g.yield_val(0);
-        g.yield_from(BoostedGenerator::new(|c| unimplemented!()))
-    });
-    let catch_result=catch_unwind(AssertUnwindSafe (|| {
-        for i in &mut g {
-            println!("value {}", i)
+        // the failure of the sub generator ends up as its result instead of tearing down this generator
+        match g.yield_from(BoostedGenerator::new(|c| -> u32 {
+            c.yield_val(1);
+            unimplemented!()
+        })) {
+            Ok(count) => format!("sub generator returned {}", count),
+            Err(e) => format!("sub generator failed: {}", e),
         }
-    }));
+    });
+    for i in &mut g {
+        println!("value {}", i)
+    }
 
-    println!("result:  {:?} {:?}",catch_result,g.result());
-}
\ No newline at end of file
+    println!("result: {:?}", g.result());
+}
@@ -1,8 +1,10 @@
 extern crate rusterators;
+use rusterators::coroutines::{Coroutine, ResumeResult};
 use rusterators::generators::{BoringGenerator, GeneratorChannel};
 
 fn main() {
-    for f in BoringGenerator::new(|g| {
+    // the generator hardly uses any stack, so a small one is plenty
+    for f in BoringGenerator::new_with_stack(16 * 1024, |g| {
         let mut current=(0,1);
         loop {
             g.yield_val(current.0);
@@ -12,4 +14,18 @@ fn main() {
       println!("{}",f)
     }
 
-}
\ No newline at end of file
+    let mut below_thousand = Coroutine::new(|chan, _| {
+        let mut current = (0, 1);
+        let mut count = 0;
+        while current.0 < 1000 {
+            chan.suspend(current.0);
+            current = (current.1, current.0 + current.1);
+            count += 1;
+        }
+        count
+    });
+    while let ResumeResult::Yield(f) = below_thousand.resume_unit() {
+        println!("{}", f)
+    }
+    println!("completed: {}", below_thousand.is_completed());
+}
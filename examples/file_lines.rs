@@ -1,24 +1,26 @@
+use std::error::Error;
+use std::io::{self, BufRead, Cursor};
+
+use rusterators::coroutines::{Coroutine, ResumeResult};
+use rusterators::generator;
 use rusterators::generators::{BoostedGenerator, GeneratorChannel, Generator, ResultingGenerator};
 
 
-fn create_line_generator<'a>(file_content: Result<String, String>) -> BoostedGenerator<'a, String, Result<(), String>, ()> {
-    BoostedGenerator::new(move |g| {
-        match &file_content {
-            Ok(content) => {
-                g.yield_all(content.lines().map(|s| String::from(s.trim())));
-                Ok(())
-            }
-            Err(_) => {
-                Err(String::from("failed to read lines"))
-            }
+/// Yields the trimmed lines of [reader], the first read error ends the generator and is returned
+fn create_line_generator<'a>(reader: impl BufRead + 'a) -> BoostedGenerator<'a, String, io::Result<()>, ()> {
+    generator! {
+        for line in reader.lines() {
+            yield_!(String::from(line?.trim()));
         }
-    })
+        ret_!(Ok(()))
+    }
 }
 
 struct RefStr<'a>(&'a str);
 
-fn main() {
-    let mut gt = BoostedGenerator::new_receiving(|gc, mut i: RefStr| {
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut words = "a b c".split_whitespace();
+    let mut gt = BoostedGenerator::new_receiving_with_initial(RefStr(words.next().unwrap()), |gc, mut i: RefStr| {
         let mut v = Vec::<&str>::new();
         for _ in 0..2 {
             v.push(i.0);
@@ -26,27 +28,48 @@ fn main() {
         }
         v.iter().map(|s| s.len()).fold(0, |a, b| a + b)
     });
-    for s in "a b c".split_whitespace() {
+    // the generator starts with the word passed on construction, so each resume passes the next one
+    for s in words {
         gt.resume(RefStr(s));
     }
     println!("{:?}", gt.result());
 
 
-    let mut g = create_line_generator(Ok(String::from(r#"1 line
+    let mut g = create_line_generator(Cursor::new(r#"1 line
     2 line
     3 line
-    4 line"#)));
+    4 line"#));
 
     for s in &mut g {
         println!("{}", s)
     }
 
-    println!("result: {:?}", g.result());
+    match g.result() {
+        Ok(lines) => println!("result: {:?}", lines),
+        Err(e) => println!("no result: {}", e),
+    }
 
 
-    let mut g = create_line_generator(Err("".into()));
-    for s in &mut g {
-        println!("never read: {}", s);
+    let mut line_lengths = Coroutine::new(|chan, _| {
+        let content = include_str!("test_text_file.txt");
+        for line in content.lines() {
+            chan.suspend(line.trim().len());
+        }
+        content.lines().count()
+    });
+    loop {
+        match line_lengths.resume_unit() {
+            ResumeResult::Yield(length) => println!("line length: {}", length),
+            ResumeResult::Return(count) => {
+                println!("lines: {}", count);
+                break;
+            }
+        }
     }
-    println!("result: {:?}", g.result());
+
+
+    // the second line is no valid UTF-8
+    let g = create_line_generator(Cursor::new(b"1 line\n\xff line\n3 line".to_vec()));
+    g.drain_with(|s| println!("read before failure: {}", s))??;
+    Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,32 @@
+use std::error::Error;
+use std::io::{BufReader, Cursor};
+
+use rusterators::generators::{GeneratorExt, ResultingGenerator};
+use rusterators::generators::io::{lines_of, split_of};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut g = lines_of(BufReader::new(include_str!("test_text_file.txt").as_bytes()));
+
+    for s in &mut g {
+        println!("{}", s.trim())
+    }
+
+    match g.result() {
+        Ok(lines) => println!("result: {:?}", lines),
+        Err(e) => println!("no result: {}", e),
+    }
+
+
+    let words = split_of(Cursor::new("1 line;2 line;3 line"), b';').map_yield(|word| String::from_utf8_lossy(&word).into_owned());
+    let (count, result) = words.fold_yields(0, |count, word| {
+        println!("word: {}", word);
+        count + 1
+    });
+    println!("words: {} {:?}", count, result);
+
+
+    // the second line is no valid UTF-8
+    let g = lines_of(Cursor::new(b"1 line\n\xff line\n3 line".to_vec()));
+    g.drain_with(|s| println!("read before failure: {}", s))??;
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use rusterators::coroutines::{LendingCoroutine, ResumeResult};
+
+struct Entity {
+    name: &'static str,
+    position: i32,
+}
+
+fn main() {
+    let mut world = vec![Entity { name: "goblin", position: 0 }, Entity { name: "orc", position: 10 }];
+
+    // the ai moves every entity towards the others and reports the remaining distance each frame
+    let mut ai = LendingCoroutine::<i32, i32, Vec<Entity>>::new(|chan| {
+        let mut entities = chan.current();
+        loop {
+            let (left, right) = (entities[0].position, entities[1].position);
+            entities[0].position += (right - left).signum();
+            entities[1].position -= (right - left).signum();
+            let distance = (entities[1].position - entities[0].position).abs();
+            if distance <= 1 {
+                return distance;
+            }
+            entities = chan.suspend(distance);
+        }
+    });
+
+    for frame in 0.. {
+        // the world is borrowed for the ai tick only, so it can be changed in between
+        match ai.resume(&mut world) {
+            ResumeResult::Yield(distance) => println!("frame {}: distance {}", frame, distance),
+            ResumeResult::Return(distance) => {
+                println!("frame {}: met at distance {}", frame, distance);
+                break;
+            }
+        }
+        world.push(Entity { name: "bystander", position: frame });
+    }
+    for entity in &world {
+        println!("{} at {}", entity.name, entity.position);
+    }
+}
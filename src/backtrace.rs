@@ -0,0 +1,85 @@
+use std::backtrace::Backtrace;
+#[cfg(feature = "backtrace")]
+use std::cell::{Cell, RefCell};
+#[cfg(feature = "backtrace")]
+use std::sync::Once;
+
+#[cfg(feature = "backtrace")]
+thread_local! {
+    /// Backtrace of the last panic raised on this thread, captured by the hook while the panicking stack is still intact
+    static PANIC_SITE: RefCell<Option<Backtrace>> = const { RefCell::new(None) };
+    /// Number of coroutine contexts running on this thread, which is 0 unless a panic is raised by a routine
+    static ROUTINE_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+#[cfg(feature = "backtrace")]
+static INSTALL_HOOK: Once = Once::new();
+
+/// Marks the current thread as running a coroutine context as long as it lives, entered by the invocation context around each switch to the coroutine context
+/// Also forgets the backtrace of a panic seen before, so it can't be mistaken for the one of a panic the routine resumes by [std::panic::resume_unwind]
+#[cfg(feature = "backtrace")]
+pub(crate) struct RoutineScope;
+
+/// Prepares a coroutine context for capturing the backtrace of a panic raised by its routine
+/// Installs a panic hook (once) which captures the backtrace before handing over to the previously installed hook,
+/// since catching the panic at the bottom of the coroutine stack is too late to see the frames it has been raised in
+/// The hook only captures panics raised while a coroutine context runs (see [RoutineScope]), other panics of the program don't pay for it
+#[cfg(feature = "backtrace")]
+pub(crate) fn prepare() {
+    INSTALL_HOOK.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if ROUTINE_DEPTH.try_with(Cell::get).unwrap_or(0) > 0 {
+                let _ = PANIC_SITE.try_with(|site| site.replace(Some(Backtrace::capture())));
+            }
+            previous(info)
+        }));
+    });
+}
+
+#[cfg(feature = "backtrace")]
+impl RoutineScope {
+    #[inline]
+    pub(crate) fn enter() -> Self {
+        ROUTINE_DEPTH.with(|depth| depth.set(depth.get() + 1));
+        PANIC_SITE.with(|site| site.take());
+        RoutineScope
+    }
+}
+
+#[cfg(feature = "backtrace")]
+impl Drop for RoutineScope {
+    #[inline]
+    fn drop(&mut self) {
+        ROUTINE_DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+}
+
+/// Takes the backtrace captured for the panic the current coroutine context has unwinded with
+/// Captures the backtrace right away if the hook did not see the panic, e.g. as it has been replaced or the panic has been resumed by [std::panic::resume_unwind]
+#[cfg(feature = "backtrace")]
+pub(crate) fn take() -> Option<Box<Backtrace>> {
+    Some(Box::new(PANIC_SITE.with(|site| site.take()).unwrap_or_else(Backtrace::capture)))
+}
+
+/// Stands in for the scope marking running coroutine contexts if the backtrace feature is disabled
+#[cfg(not(feature = "backtrace"))]
+pub(crate) struct RoutineScope;
+
+#[cfg(not(feature = "backtrace"))]
+impl RoutineScope {
+    #[inline]
+    pub(crate) fn enter() -> Self {
+        RoutineScope
+    }
+}
+
+#[cfg(not(feature = "backtrace"))]
+#[inline]
+pub(crate) fn prepare() {}
+
+#[cfg(not(feature = "backtrace"))]
+#[inline]
+pub(crate) fn take() -> Option<Box<Backtrace>> {
+    None
+}
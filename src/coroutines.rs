@@ -1,10 +1,33 @@
 use std::any::Any;
-use std::panic::{AssertUnwindSafe, catch_unwind, resume_unwind};
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::borrow::Cow;
+use std::cell::OnceCell;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::marker::PhantomData;
+use std::ops::{ControlFlow, Deref, DerefMut, Range};
+use std::mem::{replace, transmute};
+use std::process::abort;
+use std::panic::{AssertUnwindSafe, catch_unwind, resume_unwind, RefUnwindSafe, UnwindSafe};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 
 use context::{Transfer};
-use context::stack::{ProtectedFixedSizeStack};
 
-use crate::transfer::{ExchangingTransfer, StackFactory};
+use crate::raw::{RawChannel, RawCoroutine};
+use crate::stack::stack_high_water_mark;
+use crate::transfer::RawExchangeState;
+pub use crate::stack::{DefaultStackAllocator, OwnedStack, StackAllocator, StackFactory, StackPool};
+pub use context::stack::{Stack, StackError};
+use crate::trace::{self, SwitchCounter, SwitchScope};
+use crate::backtrace;
+use crate::generators::{BoostedGenerator, BoostedGeneratorChannel};
+#[cfg(feature = "nightly")]
+pub use crate::nightly::StdCoroutine;
+#[cfg(all(unix, feature = "stack-overflow-recovery"))]
+use crate::overflow::{self, ResumeScope};
 
 /// Type alias for the data a panic is carrying
 type PanicData = Box<dyn Any + Send + 'static>;
@@ -12,39 +35,64 @@ type PanicData = Box<dyn Any + Send + 'static>;
 /// Encodes the reason the execution flow of a coroutine context has been resumed(or started) from an invoking context
 /// Normally resume happens because the invoking context has passed a value (e.g. by channel.resume() in order to invoke or resume coroutines normal execution
 /// Otherwise the invoking context is about to drop the controlling coroutine struct which requires the coroutine context to unwind its callstack
+/// Cancelled passes a value like Yield but additionally tells the coroutine that its [CancellationToken] has been cancelled
+/// Delegated resumes a context whose delegate (see [SuspenseType::Delegate]) has completed, the flag tells whether the coroutine has been cancelled meanwhile
 #[derive(Debug)]
 pub enum ResumeType<Receive> {
     Yield(Receive),
+    Cancelled(Receive),
+    Delegated(bool),
     Drop(),
 }
 
 /// The reason a coroutine execution got suspended encoded to be communicated between invocation contexts.
 /// The coroutine either got suspended in the middle of execution to yield a value(e.g.) by channel.suspend() call and is ready to resume execution after Yield variant has been send
 /// Or the coroutine has completed execution - either by returning a value or by unwinding callstack for some reason - and may not be resumed after Complete variant has been send
+/// YieldBatch transfers several yielded values at once, which are handed out one by one on invocation side before the coroutine is resumed again
+/// Delegate hands over a [DelegateTarget], which the invocation side calls for the values to yield until it returns None and then resumes the coroutine by [ResumeType::Delegated]
 #[derive(Debug)]
 pub enum SuspenseType<Yield, Return> {
     Yield(Yield),
+    YieldBatch(Vec<Yield>),
+    Delegate(DelegateTarget<Yield>),
     Complete(CompleteType<Return>),
 }
 
+/// Address of the closure a suspended coroutine context delegates its yields to, e.g. the resumes of a generator it yields from
+/// The closure lives on the callstack of the context, so the address is only valid until the context is resumed or unwinded
+#[derive(Debug)]
+pub struct DelegateTarget<Yield>(*mut (dyn FnMut(&mut DelegateStack<Yield>) -> Option<Yield> + 'static));
+
+/// Delegates of nested coroutine contexts, the innermost one last, which are called by the outermost coroutine directly
+/// A coroutine resumed by a delegate pushes the delegate of its own context instead of calling it itself, see [crate::generators::Generator::resume_delegated]
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct DelegateStack<Yield>(Vec<DelegateTarget<Yield>>);
+
 /// Encodes the variant of coroutines execution completion.
 /// Either routines function has returned - in which case Return carries the returned value -
 /// or the coroutine callstack has been unwinded - then Unwind carries the reason for unwinding
+/// The returned value is boxed, so a large Return type doesn't inflate every [SuspenseType] moved by a yield
 #[derive(Debug)]
 pub enum CompleteType<Return> {
-    Return(Return),
+    Return(Box<Return>),
     Unwind(UnwindReason),
 }
 
 /// Encodes the reason a coroutine context has unwinded its callstack for
 /// Either as panic occured while executing routine:
 /// In this case panic data is transferred between context borders by Panic variant and is expected to be "rethrown" in invoking context
+/// along with the backtrace of the panic site, which is only captured with the backtrace feature enabled
 ///
 /// Otherwise invoking context instructed coroutine context to unwind its stack and Drop variant acknowledges successfull unwind
+///
+/// With stack overflow recovery enabled, StackOverflow reports a context which hit the guard page of its stack
+/// Such a context did not actually unwind: it is abandoned and destructors of values living on its stack never run
 #[derive(Debug)]
 pub enum UnwindReason {
-    Panic(PanicData),
+    Panic(PanicData, Option<Box<Backtrace>>),
     Drop,
+    StackOverflow,
 }
 
 /// CoroutineFactory holds the closure and offer a method needed to construct an invocable coroutine
@@ -55,13 +103,143 @@ pub enum UnwindReason {
 /// Represents the actual execution of a coroutine on invocation context side
 /// It encapsulates a state enum being either in Running state holding context/stack or in Completed state holding completion type
 /// It's methods offer the main public interface for invocation interaction
-pub struct Coroutine<'a, Yield: 'static, Return: 'static, Receive: 'a>(InvocationState<'a, Yield, Return, Receive>);
+/// Besides the state it tracks the usage of its callstack, buffers values of a yielded batch not handed out yet as well as the last yielded value (see [resume_buffered]),
+/// keeps the delegates it calls on behalf of its context and whether the context waits for its delegate to complete (see [SuspenseType::Delegate]),
+/// counts interactions and carries an optional name identifying it in panic messages and tracing output
+/// A coroutine asked to leak on drop (see [Coroutine::leak_on_drop]) is dropped like by [Coroutine::forget]
+/// A coroutine asked to keep its stack (see [Coroutine::keep_stack]) holds on to the callstack after completion instead of releasing it right away
+/// While it runs the routine on behalf of a resume it is flagged as resuming, so a routine reaching its own handle can't resume it again
+/// It is not [UnwindSafe] since the routine may capture mutable references, so catching its panic needs [AssertUnwindSafe] unless it is wrapped by [UnwindSafeCoroutine]
+/// A panic of the routine completes the coroutine before it is rethrown though, so the coroutine can still be queried afterwards
+pub struct Coroutine<'a, Yield: 'static, Return: 'static, Receive: 'a> {
+    state: InvocationState<'a, Yield, Return, Receive>,
+    batch: VecDeque<Yield>,
+    delegates: DelegateStack<Yield>,
+    delegating: bool,
+    last_yield: Option<Yield>,
+    cancellation: OnceCell<CancellationToken>,
+    stats: Stats,
+    panic: Option<PanicData>,
+    backtrace: Option<Box<Backtrace>>,
+    watermark: StackWatermark,
+    name: Option<Cow<'static, str>>,
+    switches: SwitchCounter,
+    leak_on_drop: bool,
+    keep_stack: bool,
+    completion: Option<CompletionKind>,
+    watcher: OnceCell<CompletionWatcher>,
+    resuming: bool,
+}
+
+/// Coroutine built from a closure that can be invoked more than once, which allows to start the routine again after (or while) it ran
+/// Holds the shared closure, the coroutine of the current run and the number of runs that have been started so far
+pub struct RestartableCoroutine<'a, Yield: 'static, Return: 'static, Receive: 'a>(Rc<DynRestartFn<'a, Yield, Return, Receive>>, Coroutine<'a, Yield, Return, Receive>, usize);
+
+/// Coroutine whose handle encodes the alternation of sending and receiving values in its type
+/// Every step consumes the handle: [start] hands back a [Suspended] which either carries a yielded value together with a [NeedsInput] handle to resume with
+/// or the returned value only. Thereby misuse like resuming a completed coroutine or resuming twice after a single yield doesn't compile:
+///
+/// ```compile_fail,E0382
+/// use rusterators::coroutines::TypedCoroutine;
+/// let co = TypedCoroutine::<i32, (), i32>::new(|chan, i| { chan.suspend(i); });
+/// co.start(1);
+/// co.start(2); // a coroutine is started once
+/// ```
+///
+/// ```compile_fail,E0382
+/// use rusterators::coroutines::{Suspended, TypedCoroutine};
+/// let co = TypedCoroutine::<i32, (), i32>::new(|chan, i| { chan.suspend(i); });
+/// if let Suspended::Yielded(_, next) = co.start(1) {
+///     next.resume(2);
+///     next.resume(3); // each yield is answered by exactly one resume
+/// }
+/// ```
+///
+/// ```compile_fail,E0599
+/// use rusterators::coroutines::TypedCoroutine;
+/// let co = TypedCoroutine::<i32, (), i32>::new(|chan, i| { chan.suspend(i); });
+/// let (_, next) = co.start(1).yielded().unwrap();
+/// let finished = next.resume(2).finished().unwrap();
+/// finished.resume(3); // a finished coroutine only hands out its returned value, there is no handle to resume it with
+/// ```
+pub struct TypedCoroutine<'a, Yield: 'static, Return: 'static, Receive: 'a>(Coroutine<'a, Yield, Return, Receive>);
+
+/// Outcome of starting or resuming a [TypedCoroutine]
+// boxing the handle would cost an allocation per step, while Finished is produced once only
+#[allow(clippy::large_enum_variant)]
+pub enum Suspended<'a, Yield: 'static, Return: 'static, Receive: 'a> {
+    Yielded(Yield, NeedsInput<'a, Yield, Return, Receive>),
+    Finished(Return),
+}
+
+/// Handle of a [TypedCoroutine] which has yielded a value and waits to be resumed
+pub struct NeedsInput<'a, Yield: 'static, Return: 'static, Receive: 'a>(Coroutine<'a, Yield, Return, Receive>);
+
+/// Coroutine which is resumed with a mutable reference that is lent to the routine for a single resume only
+/// Unlike a Coroutine receiving &'a mut T, the borrow passed to [LendingCoroutine::resume] ends when resume returns, so the referenced value can be used and borrowed anew in between
+/// (e.g. a world passed to an AI routine once per frame)
+pub struct LendingCoroutine<'a, Yield: 'static, Return: 'static, T: ?Sized + 'a>(Coroutine<'a, Yield, Return, *mut T>);
+
+/// Interface of a [LendingCoroutine] on coroutine context side
+/// The lent reference is only reachable through a [Lent] guard borrowing the channel, which needs to be dropped before the routine can suspend again:
+///
+/// ```compile_fail,E0499
+/// use rusterators::coroutines::LendingCoroutine;
+/// let mut co = LendingCoroutine::<(), (), Vec<i32>>::new(|chan| {
+///     let first = chan.current();
+///     chan.suspend(());
+///     first.push(1); // the borrow of the first resume is not valid anymore
+/// });
+/// ```
+pub struct LendingChannel<'c, 'a, Yield: 'static, Return: 'static, T: ?Sized + 'a> {
+    channel: &'c mut CoroutineChannel<'a, Yield, Return, *mut T>,
+    current: *mut T,
+}
+
+/// Guard giving access to the reference lent by the current resume of a [LendingCoroutine]
+pub struct Lent<'l, T: ?Sized>(&'l mut T);
+
+/// Coroutine which may be moved to other threads and resumed there, whereby only one thread at a time can resume it
+/// Resuming on another thread works since the coroutine context runs on its own callstack and both contexts exchange the addresses of their message containers on every switch
+/// The routine however executes on the thread resuming it, which is why it has to be constructed unsafely (see [SendCoroutine::new])
+/// The wrapped coroutine is only reachable by shared reference, so it can't be swapped for one whose routine isn't Send:
+///
+/// ```compile_fail,E0594
+/// use std::rc::Rc;
+/// use rusterators::coroutines::{Coroutine, SendCoroutine};
+/// let mut co = unsafe { SendCoroutine::<(), (), ()>::new(|_, _| ()) };
+/// let shared = Rc::new(());
+/// *co = Coroutine::new(move |_, _| drop(shared));
+/// ```
+pub struct SendCoroutine<'a, Yield: 'static, Return: 'static, Receive: 'a>(Coroutine<'a, Yield, Return, Receive>);
+
+/// Coroutine whose routine is [UnwindSafe], so a panic of it can be caught without [AssertUnwindSafe] (see [UnwindSafeCoroutine::new])
+/// The wrapped coroutine is only reachable by shared reference like the one of a [SendCoroutine], so it can't be swapped for one whose routine isn't unwind safe
+pub struct UnwindSafeCoroutine<'a, Yield: 'static, Return: 'static, Receive: 'a>(Coroutine<'a, Yield, Return, Receive>);
+
+/// Scope handed out by [scope] to create coroutines and generators borrowing data of the surrounding environment (lifetime 'env)
+/// Everything created by it is bound to lifetime 'scope and therefore completed or dropped before [scope] returns
+pub struct Scope<'scope, 'env: 'scope> {
+    scope: PhantomData<&'scope mut &'scope ()>,
+    env: PhantomData<&'env mut &'env ()>,
+}
+
+/// Outcome of [connect] telling which coroutine returned first along with its return value
+/// The other coroutine is handed back suspended, so it can be finished or dropped
+// boxing the coroutine handed back would cost an allocation for a value produced once only
+#[allow(clippy::large_enum_variant)]
+pub enum DuplexOutcome<'a, Req: 'static, Resp: 'static, Ra: 'static, Rb: 'static> {
+    /// The requesting coroutine returned, the responding one waits for the next request
+    AReturned(Ra, Coroutine<'a, Resp, Rb, Req>),
+    /// The responding coroutine returned, the requesting one waits for the response to its last request
+    BReturned(Rb, Coroutine<'a, Req, Ra, Resp>),
+}
 
 /// Represents the return of a coroutine invocation/resume
 /// While ResumeType/SuspenseType encode controlflow informations between the contexts, this type encode the user-side information
 /// i.e. whether the routine has yielded a value ready to resume or returned a value and therefore completed. Panics however will be rethrown at a lower level and won't return at all
 /// It will be returned by methods invoking the coroutine from the invocation context side (channel.resume()).
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ResumeResult<Yield, Return> {
     Yield(Yield),
     Return(Return),
@@ -72,33 +250,131 @@ pub enum ResumeResult<Yield, Return> {
 /// This is because if variant is Return, channel.resume has already returned ResumeType::Return containing the return value
 /// In case of a unwind, the Coroutine struct either dropped (in which case the variant can never be queried) or invocation paniced.
 /// In later case panic has been rethrown on invocation side and therefore - if variant is queried - has been catched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CompleteVariant {
     Return,
     Unwind,
 }
 
+/// Failures of coroutine interaction reported by the *_checked methods, which the panicking methods turn into a panic message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CoroutineError {
+    /// The coroutine has already completed and can't be resumed anymore
+    Completed,
+    /// The routine panicked, carries the panic message if the panic data has been a string
+    Panicked(Option<String>),
+    /// The coroutine context has been unwinded without the invocation context requesting it
+    Dropped,
+    /// The coroutine context overflowed its stack (see stack-overflow-recovery feature)
+    StackOverflow,
+    /// The contexts exchanged messages violating the communication protocol
+    Protocol(&'static str),
+}
+
+/// Counts the interactions with a coroutine
+/// resumes counts the resume calls the coroutine did not refuse because of being completed, yields the values handed out by them
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub resumes: u64,
+    pub yields: u64,
+    pub returned: bool,
+}
+
+/// Describes how a completed coroutine finished, see [Coroutine::completion_info]
+#[derive(Debug)]
+pub struct CompletionInfo<'c> {
+    pub variant: CompleteVariant,
+    /// Backtrace of the site the routine panicked at, only captured with the backtrace feature enabled
+    /// Whether it actually holds frames depends on RUST_BACKTRACE like for [Backtrace::capture]
+    pub backtrace: Option<&'c Backtrace>,
+}
+
+/// Panic of a routine created by [Coroutine::new_try], returned to the invocation context instead of being rethrown
+#[derive(Debug)]
+pub struct CoroutinePanic {
+    /// Message of the panic in case its data has been a string
+    pub message: Option<String>,
+    /// The original panic data, e.g. to rethrow it by [std::panic::resume_unwind]
+    pub payload: PanicData,
+}
+
+/// Reasons [Coroutine::abort] failed to tear down the coroutine context cleanly
+#[derive(Debug)]
+pub enum AbortError {
+    /// Cleanup code of the coroutine panicked while its context has been unwinded, carries the panic data
+    CleanupPanicked(PanicData),
+    /// The coroutine caught the unwind and went on instead of completing, a context still suspended afterwards is leaked like by [Coroutine::forget]
+    Intercepted,
+}
+
 /// Represents the current state of a coroutine execution.
 /// If coroutine callstack and context have already been created(even if actual routine closure has not been invoked initially),
-/// Running variant holds associated context structures and communication channel(meaning that all context including stack will be dropped as soon as state changes and such resources are freed as soon as possible)
+/// Running variant holds associated context structures and communication channel(meaning that all context will be dropped as soon as state changes and such resources are freed as soon as possible)
 /// Completed variant is used in case coroutine context has been dropped (either due to return or unwind) and controlling struct on invocation side still exists
+/// It keeps the callstack the context ran on if the coroutine has been asked to by [Coroutine::keep_stack], so it can be reused by [Coroutine::into_stack]
 enum InvocationState<'a, Yield: 'static, Return: 'static, Receive: 'a> {
-    Init(Option<Box<DynFn<'a, Yield, Return, Receive>>>),
-    Running(InvocationChannel<'a, Yield, Return, Receive>, ProtectedFixedSizeStack),
-    Completed(CompleteVariant),
+    Init(Option<PendingRoutine<'a, Yield, Return, Receive>>),
+    Running(InvocationChannel<'a, Yield, Return, Receive>),
+    Completed(CompleteVariant, Option<OwnedStack>),
+}
+
+/// Routine of a coroutine not started yet along with the factory building its callstack and the initial value bundled on construction if any
+struct PendingRoutine<'a, Yield: 'static, Return: 'static, Receive: 'a> {
+    routine: Box<DynFn<'a, Yield, Return, Receive>>,
+    stack_factory: StackFactory,
+    initial: Option<Receive>,
 }
 
+/// Tracks the high-water mark of a coroutines callstack
+/// Stacks are only measured if they have been painted by a measuring [StackFactory], in which case the mark is determined on completion
+enum StackWatermark {
+    Unmeasured,
+    Painted,
+    Measured(usize),
+}
 
 /// Offers communication interface between contexts on coroutine context sides
-/// Also holds information whether a caught panic is "real" or caused intentionally for controlled stack unwinding(second field is true in later case)
-/// TODO: maybe this can be done in a better way
+/// Also holds whether the invocation context has cancelled the coroutine (second field is true in that case)
 ///
 /// Provides possibility to suspend current execution by yielding a given value to invocation context and receiving a value sended by invocation context on return
-pub struct CoroutineChannel<'a, Yield: 'static, Return: 'static, Receive: 'a>(ExchangingTransfer<'a, SuspenseType<Yield, Return>, ResumeType<Receive>>, bool);
+pub struct CoroutineChannel<'a, Yield: 'static, Return: 'static, Receive: 'a>(RawChannel<'a, SuspenseType<Yield, Return>, ResumeType<Receive>>, bool);
+
+/// Handle to ask a coroutine to stop early, obtained by [Coroutine::cancellation_token]
+/// The coroutine observes the cancellation when it is resumed the next time (see [CoroutineChannel::is_cancelled] and [CoroutineChannel::suspend_checked])
+/// and may finish its work to return regularly. The token may be cancelled from any thread
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+/// Handle to observe whether a coroutine is still live from elsewhere, e.g. another thread, obtained by [Coroutine::watcher]
+/// The coroutine updates all its watchers when it completes or is dropped
+#[derive(Debug, Clone)]
+pub struct CompletionWatcher(Arc<AtomicU8>);
+
+/// The way a coroutine stopped being live as reported by [CompletionWatcher]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionKind {
+    /// The routine has returned
+    Returned,
+    /// The routine panicked
+    Panicked,
+    /// The coroutine context overflowed its stack (see stack-overflow-recovery feature)
+    StackOverflow,
+    /// The coroutine has been dropped (or aborted) before it completed
+    Dropped,
+}
+
+/// Signals a coroutine that its [CancellationToken] has been cancelled, see [CoroutineChannel::suspend_checked]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+/// Panic payload of the controlled stack unwind caused by a Drop request of the invocation context
+/// Lets the coroutine context tell this unwind apart from "real" panics, which includes panics raised by cleanup code after the unwind has been caught
+struct DropUnwind;
 
 /// Offers communication interface between contexts on invocation context side
 /// Provides possibility to resume coroutine execution which kinds of equals CoroutineChannels suspend capability
 /// However this is decorated by coroutine and not accessible outside
-struct InvocationChannel<'a, Yield: 'static, Return: 'static, Receive: 'a>(ExchangingTransfer<'a, ResumeType<Receive>, SuspenseType<Yield, Return>>);
+struct InvocationChannel<'a, Yield: 'static, Return: 'static, Receive: 'a>(RawCoroutine<'a, ResumeType<Receive>, SuspenseType<Yield, Return>>);
 
 // impl<'a, Yield: 'static, Return: 'static, Receive> CoroutineFactory<'a, Yield, Return, Receive>
 // //where
@@ -119,9 +395,17 @@ struct InvocationChannel<'a, Yield: 'static, Return: 'static, Receive: 'a>(Excha
 impl<'a, Yield: 'static, Return: 'static, Receive: 'a> Drop for Coroutine<'a, Yield, Return, Receive> {
     /// Causes coroutine context to unwind in case it is still running
     fn drop(&mut self) {
-        match &mut self.0 {
-            InvocationState::Running(channel, _) => {
-                channel.unwind();
+        self.notify_completion(CompletionKind::Dropped);
+        if self.leak_on_drop {
+            return self.leak();
+        }
+        match &mut self.state {
+            InvocationState::Running(channel) => {
+                let _scope = ResumeScope::enter(channel.stack());
+                let _switch = SwitchScope::enter(&mut self.switches, self.name.as_deref(), "drop");
+                if let Err(e) = channel.unwind_checked() {
+                    panic!("{}", NamedError(&e, self.name.as_deref()))
+                }
             }
             _ => {}
         }
@@ -131,115 +415,1216 @@ impl<'a, Yield: 'static, Return: 'static, Receive: 'a> Drop for Coroutine<'a, Yi
 impl<'a, Yield: 'static, Return: 'static, Receive: 'a> Coroutine<'a, Yield, Return, Receive> {
     /// Constructs a new coroutine by given closure
     pub fn new(handler: impl FnOnce(&mut CoroutineChannel<Yield, Return, Receive>, Receive) -> Return + 'a) -> Self where Receive: 'a {
-        Self(InvocationState::Init(Some(Box::new(handler))))
+        Self::new_with_factory(StackFactory::default_stack(), handler)
+    }
+    /// Constructs a new coroutine by given closure which will build its callstack by [stack_factory] on first resume
+    pub fn new_with_factory(stack_factory: StackFactory, handler: impl FnOnce(&mut CoroutineChannel<Yield, Return, Receive>, Receive) -> Return + 'a) -> Self {
+        Self::from_state(InvocationState::Init(Some(PendingRoutine { routine: Box::new(handler), stack_factory, initial: None })), StackWatermark::Unmeasured)
+    }
+    /// Constructs a new coroutine by given closure which is started with [initial] as if it had already been resumed with it
+    /// The first resume starts the routine with [initial] and passes its value on as answer to the first suspend, so every resume delivers its value to the routine
+    /// The value the routine yields in response to [initial] is dropped, the value sent by the first resume only if the routine returns before suspending
+    pub fn new_with_initial(initial: Receive, handler: impl FnOnce(&mut CoroutineChannel<Yield, Return, Receive>, Receive) -> Return + 'a) -> Self {
+        Self::from_state(InvocationState::Init(Some(PendingRoutine { routine: Box::new(handler), stack_factory: StackFactory::default_stack(), initial: Some(initial) })), StackWatermark::Unmeasured)
+    }
+    /// Constructs a new coroutine by given closure which runs on [stack], e.g. one handed out by [into_stack]
+    /// Owning the stack guarantees that it is not used by another context anymore
+    pub fn new_in(stack: impl Into<OwnedStack>, handler: impl FnOnce(&mut CoroutineChannel<Yield, Return, Receive>, Receive) -> Return + 'a) -> Self {
+        Self::new_with_factory(StackFactory::of_stack(stack), handler)
+    }
+    /// Constructs a new coroutine by given closure and resumes it with [initial] right away
+    /// Returns the coroutine together with the result of that first resume, which already is the return value if the routine did not suspend
+    pub fn invoke(handler: impl FnOnce(&mut CoroutineChannel<Yield, Return, Receive>, Receive) -> Return + 'a, initial: Receive) -> (Self, ResumeResult<Yield, Return>) {
+        let mut co = Self::new(handler);
+        let first = co.resume(initial);
+        (co, first)
+    }
+    /// Constructs a new coroutine by given closure without boxing it
+    /// Instead the callstack is built right away and the closure is moved onto it directly, so the closure type does not need to be erased
+    /// The first resume then starts the routine like for coroutines created by [new]
+    pub fn new_unboxed<F>(handler: F) -> Self where F: FnOnce(&mut CoroutineChannel<Yield, Return, Receive>, Receive) -> Return + 'a {
+        Self::new_unboxed_with_factory(StackFactory::default_stack(), handler)
+    }
+    /// Like [new_unboxed] but building the callstack by [stack_factory]
+    pub fn new_unboxed_with_factory<F>(stack_factory: StackFactory, handler: F) -> Self where F: FnOnce(&mut CoroutineChannel<Yield, Return, Receive>, Receive) -> Return + 'a {
+        let (state, watermark) = Self::spawn(stack_factory, handler);
+        Self::from_state(state, watermark)
+    }
+    /// Wraps a given state into an unnamed coroutine
+    fn from_state(state: InvocationState<'a, Yield, Return, Receive>, watermark: StackWatermark) -> Self {
+        Self { state, batch: VecDeque::new(), delegates: DelegateStack(Vec::new()), delegating: false, last_yield: None, cancellation: OnceCell::new(), stats: Stats::default(), panic: None, backtrace: None, watermark, name: None, switches: SwitchCounter::new(), leak_on_drop: false, keep_stack: false, completion: None, watcher: OnceCell::new(), resuming: false }
+    }
+    /// Names the coroutine, e.g. to tell it apart from others in tracing output
+    /// The name is kept without the tracing feature as well, since panic messages and errors of the coroutine name it (see [resume])
+    pub fn with_name(mut self, name: impl Into<Cow<'static, str>>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+    /// Returns the name given by [with_name] if any
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+    /// Hands out the data of the panic the routine has unwinded with, e.g. to rethrow the original panic by [std::panic::resume_unwind]
+    /// Returns None if the routine did not panic or the data has already been taken
+    pub fn take_panic(&mut self) -> Option<PanicData> {
+        self.panic.take()
+    }
+    /// Returns the message of the panic the routine has unwinded with, if its data is a string that has not been taken yet
+    pub(crate) fn panic_message(&self) -> Option<String> {
+        self.panic.as_ref().and_then(panic_message)
+    }
+    /// Describes how the coroutine completed, or returns None while it is not completed
+    pub fn completion_info(&self) -> Option<CompletionInfo<'_>> {
+        match self.state {
+            InvocationState::Completed(variant, _) => Some(CompletionInfo { variant, backtrace: self.backtrace.as_deref() }),
+            _ => None
+        }
+    }
+    /// Returns the token to cancel this coroutine with, all tokens of a coroutine share the same state
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation.get_or_init(CancellationToken::default).clone()
+    }
+    /// Returns a handle observing the completion of this coroutine, all watchers of a coroutine share the same state
+    pub fn watcher(&self) -> CompletionWatcher {
+        self.watcher.get_or_init(|| CompletionWatcher::new(self.completion)).clone()
+    }
+    /// Remembers the way the coroutine stopped being live and passes it on to its watchers, only the first call has an effect
+    fn notify_completion(&mut self, kind: CompletionKind) {
+        if self.completion.is_none() {
+            self.completion = Some(kind);
+            if let Some(watcher) = self.watcher.get() {
+                watcher.set(kind);
+            }
+        }
+    }
+    /// Returns how often the coroutine has been resumed and has yielded so far and whether it has returned
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+    /// Builds the callstack and context for [routine] and transfers the routine to it
+    /// Returns the resulting running state which awaits the initial value, together with the watermark state of the stack
+    fn spawn<F>(stack_factory: StackFactory, routine: F) -> (InvocationState<'a, Yield, Return, Receive>, StackWatermark) where F: FnOnce(&mut CoroutineChannel<Yield, Return, Receive>, Receive) -> Return + 'a {
+        let watermark = if stack_factory.is_measured() { StackWatermark::Painted } else { StackWatermark::Unmeasured };
+        let raw = RawCoroutine::spawn_context(stack_factory, run_co_context::<Yield, Return, Receive, F>, routine);
+        (InvocationState::Running(InvocationChannel(raw)), watermark)
     }
     /// Sends a given value to the coroutine context and yields execution control to it
     /// Returns either a Yield or a Return ResumeResult after coroutine execution has been suspended
     /// Panics in case coroutine execution did panic or in case coroutine execution already has completed it
+    ///
+    /// If the coroutine has yielded a batch (see [CoroutineChannel::suspend_batch]) the buffered values are returned first without resuming the coroutine,
+    /// [send] is dropped in that case
+    /// The panic message names the coroutine (see [with_name]) and includes the message of a panic raised by the routine, whose data is kept for [take_panic]
+    /// With the backtrace feature enabled and RUST_BACKTRACE set, the panic message also contains the backtrace of the panic site within the coroutine
     pub fn resume(&mut self, send: Receive) -> ResumeResult<Yield, Return> {
-        let (rec, next_state) = match &mut self.0 {
-            InvocationState::Init(co_fn) => {
-                let (exchanging_transfer, stack) =
-                    ExchangingTransfer::<ResumeType<Receive>, SuspenseType<Yield, Return>>
-                    ::init_context_sending(StackFactory::default_stack(),
-                                           run_co_context::<Yield, Return, Receive>, co_fn.take().unwrap());
-                let mut channel = InvocationChannel::<Yield, Return, Receive>(exchanging_transfer);
-                let rec=channel.suspend(send);
-                (rec, Some(InvocationState::Running(channel, stack)))
-            }
-            InvocationState::Running(channel, _) => (channel.suspend(send), None),
-            _ => panic!("tried to send to non-running context")
-        };
-        let (res,other_next_state)=self.receive(rec);
-        if let Some(state)=other_next_state.or(next_state)  { self.0 = state; }
-        res
+        let resumed = self.resume_checked(send);
+        self.rethrow(resumed)
+    }
+    /// Like [resume] on behalf of a coroutine context yielding from this one, see [resume_delegated_checked]
+    pub(crate) fn resume_delegated(&mut self, send: Receive, outer: &mut DelegateStack<Yield>) -> ResumeResult<Yield, Return> {
+        let resumed = self.resume_delegated_checked(send, outer);
+        self.rethrow(resumed)
+    }
+    /// Turns a failed resume into a panic, see [resume]
+    fn rethrow(&self, resumed: Result<ResumeResult<Yield, Return>, CoroutineError>) -> ResumeResult<Yield, Return> {
+        match resumed {
+            Ok(result) => result,
+            Err(e @ CoroutineError::Panicked(_)) if self.backtrace.as_ref().is_some_and(|b| b.status() == BacktraceStatus::Captured) =>
+                panic!("{}\ncoroutine backtrace:\n{}", NamedError(&e, self.name()), self.backtrace.as_ref().unwrap()),
+            Err(e) => panic!("{}", NamedError(&e, self.name()))
+        }
+    }
+    /// Like [resume] but returns None instead of panicking if the coroutine has already completed, so drivers don't need to check [is_completed] first
+    /// Panics of the routine are still rethrown as for [resume]
+    pub fn resume_or_finished(&mut self, send: Receive) -> Option<ResumeResult<Yield, Return>> {
+        if self.is_completed() {
+            None
+        } else {
+            Some(self.resume(send))
+        }
+    }
+    /// Like [resume] but keeps a yielded value in the coroutine instead of handing it out, where it can be looked at by [last_yield] until the next resume or [take_yield]
+    /// This allows consumers to compare the heads of several coroutines while only advancing one of them
+    pub fn resume_buffered(&mut self, send: Receive) -> ResumeResult<&Yield, Return> {
+        match self.resume(send) {
+            ResumeResult::Yield(y) => ResumeResult::Yield(self.last_yield.insert(y)),
+            ResumeResult::Return(r) => ResumeResult::Return(r),
+        }
+    }
+    /// Returns the value buffered by the last [resume_buffered] if it has not been taken yet
+    pub fn last_yield(&self) -> Option<&Yield> {
+        self.last_yield.as_ref()
+    }
+    /// Takes the value buffered by the last [resume_buffered] out of the coroutine
+    pub fn take_yield(&mut self) -> Option<Yield> {
+        self.last_yield.take()
+    }
+    /// Like [resume] but reports failures as [CoroutineError] instead of panicking
+    /// Still panics if the coroutine is resumed by its own routine, which would corrupt the exchange between the contexts
+    pub fn resume_checked(&mut self, send: Receive) -> Result<ResumeResult<Yield, Return>, CoroutineError> {
+        self.resume_with(send, None)
+    }
+    /// Like [resume_checked] on behalf of a coroutine context yielding from this one, whose coroutine calls [outer]
+    /// The delegate of this context is pushed to [outer] rather than being called by this coroutine, so the values of nested coroutines pass a single one only
+    pub(crate) fn resume_delegated_checked(&mut self, send: Receive, outer: &mut DelegateStack<Yield>) -> Result<ResumeResult<Yield, Return>, CoroutineError> {
+        // delegates left by direct resumes are still called by this coroutine
+        if self.delegates.0.is_empty() {
+            self.resume_with(send, Some(outer))
+        } else {
+            self.resume_with(send, None)
+        }
+    }
+    /// Resumes the coroutine calling the delegates of its context on [outer] or on its own delegate stack if there is none
+    fn resume_with(&mut self, send: Receive, mut outer: Option<&mut DelegateStack<Yield>>) -> Result<ResumeResult<Yield, Return>, CoroutineError> {
+        if self.resuming {
+            match self.name() {
+                Some(name) => panic!("re-entrant resume of coroutine '{}'", name),
+                None => panic!("re-entrant resume of coroutine"),
+            }
+        }
+        self.last_yield = None;
+        if let Some(y) = self.batch.pop_front() {
+            self.stats.resumes += 1;
+            self.stats.yields += 1;
+            return Ok(ResumeResult::Yield(y));
+        }
+        // the values of delegates are handed out without resuming the context as well, [send] is dropped then
+        let mut send = Some(send);
+        if self.delegating {
+            // a delegate pushed to an outer stack is done once this coroutine is called again, since only the innermost delegate is called
+            if outer.is_none() {
+                if let Some(y) = self.delegates.next_above(0) {
+                    self.stats.resumes += 1;
+                    self.stats.yields += 1;
+                    return Ok(ResumeResult::Yield(y));
+                }
+            }
+            send = None;
+        }
+        if let InvocationState::Init(init) = &mut self.state {
+            let PendingRoutine { routine, stack_factory, initial } = init.take().unwrap();
+            let (state, watermark) = Self::spawn(stack_factory, routine);
+            self.state = state;
+            self.watermark = watermark;
+            if let Some(initial) = initial {
+                // starting with the bundled value is part of this resume rather than one of its own
+                let stats = self.stats;
+                let started = self.resume_with(initial, None);
+                let returned = self.stats.returned;
+                self.stats = stats;
+                match started {
+                    Ok(ResumeResult::Yield(_)) => {}
+                    finished => {
+                        self.stats.resumes += 1;
+                        self.stats.returned = returned;
+                        return finished;
+                    }
+                }
+            }
+        }
+        loop {
+            let rec = match &mut self.state {
+                InvocationState::Running(channel) => {
+                    self.stats.resumes += 1;
+                    let _scope = ResumeScope::enter(channel.stack());
+                    let _switch = SwitchScope::enter(&mut self.switches, self.name.as_deref(), "resume");
+                    let cancelled = self.cancellation.get().is_some_and(CancellationToken::is_cancelled);
+                    self.resuming = true;
+                    let rec = match send.take() {
+                        Some(send) => channel.suspend(send, cancelled),
+                        None => {
+                            self.delegating = false;
+                            channel.end_delegation(cancelled)
+                        }
+                    };
+                    self.resuming = false;
+                    rec
+                }
+                _ => return Err(CoroutineError::Completed)
+            };
+            match rec {
+                SuspenseType::Delegate(target) => {
+                    self.delegating = true;
+                    let delegates = match &mut outer {
+                        Some(outer) => &mut **outer,
+                        None => &mut self.delegates,
+                    };
+                    let base = delegates.0.len();
+                    delegates.0.push(target);
+                    if let Some(y) = delegates.next_above(base) {
+                        self.stats.yields += 1;
+                        return Ok(ResumeResult::Yield(y));
+                    }
+                }
+                rec => return self.receive(rec),
+            }
+        }
     }
 
     /// queries whether coroutine has completed execution
     pub fn is_completed(&self) -> bool {
-        match self.0 {
-            InvocationState::Completed(_) => true,
+        match self.state {
+            InvocationState::Completed(..) => true,
             _ => false
         }
     }
+    /// Unwinds the coroutine context in case it is still running and hands back its callstack which is unused afterwards
+    /// Leaves the coroutine in completed state
+    fn unwind_into_stack(&mut self) -> Option<OwnedStack> {
+        self.measure_stack();
+        match replace(&mut self.state, InvocationState::Completed(CompleteVariant::Unwind, None)) {
+            InvocationState::Running(mut channel) => {
+                {
+                    let _scope = ResumeScope::enter(channel.stack());
+                    let _switch = SwitchScope::enter(&mut self.switches, self.name.as_deref(), "drop");
+                    channel.unwind();
+                }
+                channel.0.into_stack()
+            }
+            InvocationState::Completed(variant, stack) => {
+                self.state = InvocationState::Completed(variant, None);
+                stack
+            }
+            other => {
+                self.state = other;
+                None
+            }
+        }
+    }
+    /// Tears down the coroutine like dropping it does, but reports the outcome of the unwind instead of panicking
+    /// Returns [AbortError::CleanupPanicked] if the coroutine panicked while its context has been unwinded, e.g. a destructor panicked after the unwind has been caught
+    /// Note that a destructor panicking while the stack is still unwinding aborts the process like any panic during unwinding does
+    pub fn abort(mut self) -> Result<(), AbortError> {
+        self.measure_stack();
+        match replace(&mut self.state, InvocationState::Completed(CompleteVariant::Unwind, None)) {
+            InvocationState::Running(mut channel) => {
+                let answer = {
+                    let _scope = ResumeScope::enter(channel.stack());
+                    let _switch = SwitchScope::enter(&mut self.switches, self.name.as_deref(), "drop");
+                    channel.request_unwind()
+                };
+                match answer {
+                    SuspenseType::Complete(CompleteType::Unwind(UnwindReason::Panic(p, _))) => Err(AbortError::CleanupPanicked(p)),
+                    SuspenseType::Complete(CompleteType::Unwind(_)) => Ok(()),
+                    SuspenseType::Complete(CompleteType::Return(_)) => Err(AbortError::Intercepted),
+                    _ => {
+                        // the context would intercept the unwind requested on drop again, so it is leaked like by [forget]
+                        std::mem::forget(channel);
+                        Err(AbortError::Intercepted)
+                    }
+                }
+            }
+            _ => Ok(())
+        }
+    }
+    /// Drops the coroutine without unwinding its context, which intentionally LEAKS the callstack including everything living on it
+    /// Destructors of values on the coroutine stack never run and the coroutine context is never resumed again,
+    /// which is what coroutines running foreign frames (e.g. of FFI callbacks) need, as unwinding through those is undefined behavior
+    /// A coroutine which has not been started yet just drops its routine
+    pub fn forget(mut self) {
+        self.leak();
+    }
+    /// Makes dropping the coroutine behave like [forget], i.e. leak the callstack instead of unwinding it
+    pub fn leak_on_drop(mut self, leak: bool) -> Self {
+        self.leak_on_drop = leak;
+        self
+    }
+    /// Makes the coroutine keep its callstack once it completed, so it can be handed out by [into_stack] rather than being released to its allocator right away
+    pub fn keep_stack(mut self, keep: bool) -> Self {
+        self.keep_stack = keep;
+        self
+    }
+    /// Moves the coroutine to completed state leaking the callstack of a running context
+    fn leak(&mut self) {
+        if let InvocationState::Running(channel) = replace(&mut self.state, InvocationState::Completed(CompleteVariant::Unwind, None)) {
+            std::mem::forget(channel);
+        }
+    }
+    /// Returns the size of the coroutines callstack in bytes (not including its guard page)
+    /// Returns None unless the coroutine is running, i.e. before the first resume and after completion
+    pub fn stack_size(&self) -> Option<usize> {
+        self.stack_range().map(|range| range.len())
+    }
+    /// Returns the address range of the coroutines callstack (not including its guard page), e.g. to tell whether a pointer refers to a value living on it
+    /// Returns None unless the coroutine is running like [stack_size]
+    pub fn stack_range(&self) -> Option<Range<usize>> {
+        match &self.state {
+            InvocationState::Running(channel) => Some(channel.stack().bottom() as usize..channel.stack().top() as usize),
+            _ => None
+        }
+    }
+    /// Returns the address range of the inaccessible guard region right below the coroutines callstack, which is empty for unprotected stacks
+    /// Returns None unless the coroutine is running like [stack_size]
+    pub fn guard_range(&self) -> Option<Range<usize>> {
+        match &self.state {
+            InvocationState::Running(channel) => {
+                let bottom = channel.stack().bottom() as usize;
+                Some(bottom - channel.stack().guard_size()..bottom)
+            }
+            _ => None
+        }
+    }
+    /// Returns the maximum number of bytes the coroutines callstack has been used with
+    /// Only available if the coroutine has been created with a measuring stack factory (see [StackFactory::of_size_measured]) and has been started
+    /// While the coroutine is running the stack is scanned on each call, after completion the mark measured on completion is returned
+    pub fn stack_high_water_mark(&self) -> Option<usize> {
+        match (&self.state, &self.watermark) {
+            (InvocationState::Running(channel), StackWatermark::Painted) => Some(stack_high_water_mark(channel.stack())),
+            (_, StackWatermark::Measured(mark)) => Some(*mark),
+            _ => None
+        }
+    }
+    /// Moves the coroutine to completed state which frees the context, the callstack is only kept for [into_stack] if asked to by [keep_stack]
+    /// In case the stack has been painted for measurement, the high-water mark is determined before the stack may be reused
+    fn complete(&mut self, variant: CompleteVariant) {
+        self.measure_stack();
+        let stack = match replace(&mut self.state, InvocationState::Completed(variant, None)) {
+            InvocationState::Running(channel) => channel.0.into_stack(),
+            _ => None
+        };
+        // the context is gone, so nothing may refer to values which lived on its stack anymore
+        #[cfg(feature = "debug-stack-poison")]
+        if let Some(stack) = &stack {
+            crate::stack::poison_stack(stack);
+        }
+        let stack = stack.filter(|_| self.keep_stack);
+        self.state = InvocationState::Completed(variant, stack);
+    }
+    /// Hands out the callstack of a completed coroutine to run another coroutine on it (see [new_in]), which saves allocating a fresh one
+    /// A coroutine still running is unwound first like on drop, one which has not been started, has leaked its stack or completed without keeping it (see [keep_stack]) returns None
+    pub fn into_stack(mut self) -> Option<OwnedStack> {
+        self.unwind_into_stack()
+    }
+    /// Hands the callstack over to [pool] like [into_stack] does, so the pool can hand it out to the next coroutine
+    pub fn recycle_into(self, pool: &mut StackPool) {
+        if let Some(stack) = self.into_stack() {
+            pool.push(stack);
+        }
+    }
+    /// Takes a suspended coroutine apart into the raw transfer of its context, the state needed to rebuild the exchange and its callstack,
+    /// e.g. to park the context in the run queue of another scheduler until it is rebuilt by [from_raw_parts]
+    /// Hands the coroutine back unless it is running with no yielded values buffered, i.e. before it has been started, after completion, in the middle of a batch or while its context delegates
+    /// Everything but the context is dropped along with the handle (e.g. name, stats and the last yielded value), watchers keep reporting the coroutine as live
+    // the coroutine is handed back on failure like by Rc::try_unwrap, boxing it would cost an allocation for nothing
+    #[allow(clippy::result_large_err)]
+    pub fn into_raw_parts(mut self) -> Result<(Transfer, RawExchangeState, OwnedStack), Self> {
+        if !matches!(self.state, InvocationState::Running(_)) || !self.batch.is_empty() || self.delegating {
+            return Err(self);
+        }
+        self.watcher.take();
+        match replace(&mut self.state, InvocationState::Completed(CompleteVariant::Unwind, None)) {
+            InvocationState::Running(channel) => {
+                let (transfer, state, stack) = channel.0.into_raw_parts();
+                Ok((transfer, state, stack.expect("running coroutine without stack")))
+            }
+            _ => unreachable!()
+        }
+    }
+    /// Rebuilds a coroutine taken apart by [into_raw_parts], which can be resumed as before
+    ///
+    /// # Safety
+    /// The parts must have been handed out together by [into_raw_parts] of a coroutine of the same types and lifetime,
+    /// and the context must not have been resumed in between (see [ExchangingTransfer::from_raw](crate::transfer::ExchangingTransfer::from_raw))
+    pub unsafe fn from_raw_parts(transfer: Transfer, state: RawExchangeState, stack: OwnedStack) -> Self {
+        Self::from_state(InvocationState::Running(InvocationChannel(RawCoroutine::from_raw_parts(transfer, state, stack))), StackWatermark::Unmeasured)
+    }
+    /// Stores the current high-water mark of a running coroutines painted stack
+    fn measure_stack(&mut self) {
+        if let (InvocationState::Running(channel), StackWatermark::Painted) = (&self.state, &self.watermark) {
+            self.watermark = StackWatermark::Measured(stack_high_water_mark(channel.stack()));
+        }
+    }
     /// Internally handles value passed by coroutine execution
-    fn receive(&mut self, rec: SuspenseType<Yield, Return>) -> (ResumeResult<Yield, Return>, Option<InvocationState<'a, Yield, Return, Receive>>) {
+    fn receive(&mut self, rec: SuspenseType<Yield, Return>) -> Result<ResumeResult<Yield, Return>, CoroutineError> {
         match rec {
-            SuspenseType::Yield(y) => (ResumeResult::Yield(y), None),
-            SuspenseType::Complete(CompleteType::Return(r)) => (ResumeResult::Return(r), Some(InvocationState::Completed(CompleteVariant::Return))),
+            SuspenseType::Yield(y) => {
+                self.stats.yields += 1;
+                Ok(ResumeResult::Yield(y))
+            }
+            SuspenseType::YieldBatch(batch) => {
+                self.stats.yields += 1;
+                self.batch = batch.into();
+                self.batch.pop_front()
+                    .map(ResumeResult::Yield)
+                    .ok_or(CoroutineError::Protocol("coroutine yielded an empty batch"))
+            }
+            SuspenseType::Delegate(_) => Err(CoroutineError::Protocol("coroutine delegated outside of a resume")),
+            SuspenseType::Complete(CompleteType::Return(r)) => {
+                self.stats.returned = true;
+                self.complete(CompleteVariant::Return);
+                self.notify_completion(CompletionKind::Returned);
+                Ok(ResumeResult::Return(*r))
+            }
             SuspenseType::Complete(CompleteType::Unwind(u)) => {
-                self.0 = InvocationState::Completed(CompleteVariant::Unwind);
-                // TODO maybe pass some data referencing/containing original ponic but also being formatted
-                panic!(if let UnwindReason::Panic(_) = u { "Coroutine panicked" } else { "coroutine context dropped outside of coroutine destructor" })
+                self.complete(CompleteVariant::Unwind);
+                let (kind, error) = match u {
+                    UnwindReason::Panic(p, backtrace) => {
+                        let message = panic_message(&p);
+                        self.panic = Some(p);
+                        self.backtrace = backtrace;
+                        (CompletionKind::Panicked, CoroutineError::Panicked(message))
+                    }
+                    UnwindReason::StackOverflow => (CompletionKind::StackOverflow, CoroutineError::StackOverflow),
+                    UnwindReason::Drop => (CompletionKind::Dropped, CoroutineError::Dropped),
+                };
+                self.notify_completion(kind);
+                Err(error)
             }
         }
     }
 }
 
+impl<'a, Yield: 'static, Return: 'static, Receive: 'a> Coroutine<'a, Yield, Result<Return, CoroutinePanic>, Receive> {
+    /// Constructs a new coroutine whose routine may not panic across the context border
+    /// A panic of [handler] is caught within the coroutine context and returned as Err, so resume returns ResumeResult::Return(Err(..)) rather than panicking
+    /// The unwind caused by dropping the coroutine is not affected
+    pub fn new_try(handler: impl FnOnce(&mut CoroutineChannel<Yield, Result<Return, CoroutinePanic>, Receive>, Receive) -> Return + 'a) -> Self {
+        Self::new(|chan, initial| {
+            catch_unwind(AssertUnwindSafe(|| handler(chan, initial))).map_err(|payload| {
+                if payload.is::<DropUnwind>() {
+                    resume_unwind(payload)
+                }
+                CoroutinePanic { message: panic_message(&payload), payload }
+            })
+        })
+    }
+}
+
+impl<'a, Yield: 'static, Return: 'static> Coroutine<'a, Yield, Return, ()> {
+    /// Resumes a coroutine which does not receive meaningful values, i.e. is equivalent to resume(())
+    pub fn resume_unit(&mut self) -> ResumeResult<Yield, Return> {
+        self.resume(())
+    }
+}
+
+impl<'a, Yield: 'static, Return: 'static, Receive: 'a> RestartableCoroutine<'a, Yield, Return, Receive> {
+    /// Constructs a new restartable coroutine by given closure
+    /// The coroutine behaves like one created by [Coroutine::new] until [restart] is called
+    pub fn new(handler: impl Fn(&mut CoroutineChannel<Yield, Return, Receive>, Receive) -> Return + 'a) -> Self {
+        let handler: Rc<DynRestartFn<'a, Yield, Return, Receive>> = Rc::new(handler);
+        let run = Self::create_run(&handler, StackFactory::default_stack());
+        Self(handler, run, 0)
+    }
+    /// Names the coroutine like [Coroutine::with_name], the name is kept by the runs started by [restart]
+    pub fn with_name(mut self, name: impl Into<Cow<'static, str>>) -> Self {
+        self.1.name = Some(name.into());
+        self
+    }
+    /// Sends a given value to the current run of the coroutine, see [Coroutine::resume]
+    pub fn resume(&mut self, send: Receive) -> ResumeResult<Yield, Return> {
+        let resumed = self.resume_checked(send);
+        self.1.rethrow(resumed)
+    }
+    /// Like [resume] but reports failures as [CoroutineError] instead of panicking
+    pub fn resume_checked(&mut self, send: Receive) -> Result<ResumeResult<Yield, Return>, CoroutineError> {
+        if let InvocationState::Init(_) = self.1.state {
+            self.2 += 1;
+        }
+        self.1.resume_checked(send)
+    }
+    /// queries whether the current run has completed execution
+    pub fn is_completed(&self) -> bool {
+        self.1.is_completed()
+    }
+    /// Returns how many times the coroutine has been started, i.e. how many runs have been resumed at least once
+    pub fn has_runs(&self) -> usize {
+        self.2
+    }
+    /// Tears down the current run (unwinding its context in case it is still running) and prepares a fresh invocation of the closure
+    /// The next resume starts the new run, whereby the callstack of the previous run is reused
+    pub fn restart(&mut self) {
+        let stack_factory = self.1.unwind_into_stack()
+            .map(StackFactory::of_stack)
+            .unwrap_or_else(StackFactory::default_stack);
+        let name = self.1.name.take();
+        self.1 = Self::create_run(&self.0, stack_factory);
+        self.1.name = name;
+    }
+    /// Creates a coroutine invoking the shared closure once
+    fn create_run(handler: &Rc<DynRestartFn<'a, Yield, Return, Receive>>, stack_factory: StackFactory) -> Coroutine<'a, Yield, Return, Receive> {
+        let handler = handler.clone();
+        Coroutine::new_with_factory(stack_factory, move |chan, initial| handler(chan, initial)).keep_stack(true)
+    }
+}
+
+impl<'a, Yield: 'static, Return: 'static> RestartableCoroutine<'a, Yield, Return, ()> {
+    /// Resumes the current run of a coroutine which does not receive meaningful values, see [Coroutine::resume_unit]
+    pub fn resume_unit(&mut self) -> ResumeResult<Yield, Return> {
+        self.resume(())
+    }
+}
+
+impl<'a, Yield: 'static, Return: 'static, Receive: 'a> TypedCoroutine<'a, Yield, Return, Receive> {
+    /// Constructs a new typed coroutine by given closure, which takes the same form as for [Coroutine::new]
+    pub fn new(handler: impl FnOnce(&mut CoroutineChannel<Yield, Return, Receive>, Receive) -> Return + 'a) -> Self {
+        Self(Coroutine::new(handler))
+    }
+    /// Starts the coroutine sending the initial value and runs it until it suspends for the first time
+    pub fn start(self, send: Receive) -> Suspended<'a, Yield, Return, Receive> {
+        Suspended::resume(self.0, send)
+    }
+}
+
+impl<'a, Yield: 'static, Return: 'static, Receive: 'a> Suspended<'a, Yield, Return, Receive> {
+    /// Resumes [coroutine] and wraps the outcome, whereby the coroutine is only handed back if it is able to continue
+    fn resume(mut coroutine: Coroutine<'a, Yield, Return, Receive>, send: Receive) -> Self {
+        match coroutine.resume(send) {
+            ResumeResult::Yield(y) => Suspended::Yielded(y, NeedsInput(coroutine)),
+            ResumeResult::Return(r) => Suspended::Finished(r),
+        }
+    }
+    /// Returns the yielded value together with the handle to resume the coroutine with, or None if it has finished
+    pub fn yielded(self) -> Option<(Yield, NeedsInput<'a, Yield, Return, Receive>)> {
+        match self {
+            Suspended::Yielded(y, next) => Some((y, next)),
+            Suspended::Finished(_) => None,
+        }
+    }
+    /// Returns the returned value, or None if the coroutine has yielded
+    pub fn finished(self) -> Option<Return> {
+        match self {
+            Suspended::Finished(r) => Some(r),
+            Suspended::Yielded(..) => None,
+        }
+    }
+}
+
+impl<'a, Yield: 'static, Return: 'static, Receive: 'a> NeedsInput<'a, Yield, Return, Receive> {
+    /// Sends a value answering the last yield and runs the coroutine until it suspends again
+    pub fn resume(self, send: Receive) -> Suspended<'a, Yield, Return, Receive> {
+        Suspended::resume(self.0, send)
+    }
+}
+
+impl<'a, Yield: 'static, Return: 'static, T: ?Sized + 'a> LendingCoroutine<'a, Yield, Return, T> {
+    /// Constructs a new lending coroutine by given closure, which reaches the reference lent by the first resume via [LendingChannel::current]
+    pub fn new(handler: impl FnOnce(&mut LendingChannel<Yield, Return, T>) -> Return + 'a) -> Self {
+        Self(Coroutine::new(move |channel, current| handler(&mut LendingChannel { channel, current })))
+    }
+    /// Lends [value] to the coroutine until it suspends again, see [Coroutine::resume]
+    pub fn resume(&mut self, value: &mut T) -> ResumeResult<Yield, Return> {
+        self.0.resume(value)
+    }
+    /// queries whether coroutine has completed execution
+    pub fn is_completed(&self) -> bool {
+        self.0.is_completed()
+    }
+}
+
+impl<'c, 'a, Yield: 'static, Return: 'static, T: ?Sized + 'a> LendingChannel<'c, 'a, Yield, Return, T> {
+    /// Gives access to the reference lent by the resume the coroutine is currently running for
+    pub fn current(&mut self) -> Lent<'_, T> {
+        // the invocation context holds the borrow of the value until the coroutine suspends, which requires this guard to be dropped
+        Lent(unsafe { &mut *self.current })
+    }
+    /// Suspends execution control yielding the given value and returns the reference lent by the next resume, see [CoroutineChannel::suspend]
+    pub fn suspend(&mut self, send: Yield) -> Lent<'_, T> {
+        self.current = self.channel.suspend(send);
+        self.current()
+    }
+}
+
+impl<'l, T: ?Sized> Deref for Lent<'l, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.0
+    }
+}
+
+impl<'l, T: ?Sized> DerefMut for Lent<'l, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.0
+    }
+}
+
+impl<'a, Yield: 'static, Return: 'static, Receive: 'a> SendCoroutine<'a, Yield, Return, Receive> {
+    /// Constructs a new coroutine by given closure which may be resumed from any thread
+    ///
+    /// # Safety
+    /// The values living on the callstack of the routine are not checked to be Send, the routine must not keep values bound to a thread
+    /// (like Rc or references to thread locals) alive while it is suspended
+    pub unsafe fn new(handler: impl FnOnce(&mut CoroutineChannel<Yield, Return, Receive>, Receive) -> Return + Send + 'a) -> Self {
+        Self(Coroutine::new(handler))
+    }
+    /// Sends a given value to the coroutine, see [Coroutine::resume]
+    pub fn resume(&mut self, send: Receive) -> ResumeResult<Yield, Return> {
+        self.0.resume(send)
+    }
+    /// Like [resume] but reports failures as [CoroutineError] instead of panicking, see [Coroutine::resume_checked]
+    pub fn resume_checked(&mut self, send: Receive) -> Result<ResumeResult<Yield, Return>, CoroutineError> {
+        self.0.resume_checked(send)
+    }
+    /// Like [resume] but returns None if the coroutine has already completed, see [Coroutine::resume_or_finished]
+    pub fn resume_or_finished(&mut self, send: Receive) -> Option<ResumeResult<Yield, Return>> {
+        self.0.resume_or_finished(send)
+    }
+    /// Hands out the data of the panic the routine has unwinded with, see [Coroutine::take_panic]
+    pub fn take_panic(&mut self) -> Option<PanicData> {
+        self.0.take_panic()
+    }
+}
+
+unsafe impl<'a, Yield: Send + 'static, Return: Send + 'static, Receive: Send + 'a> Send for SendCoroutine<'a, Yield, Return, Receive> {}
+
+impl<'a, Yield: 'static, Return: 'static, Receive: 'a> Deref for SendCoroutine<'a, Yield, Return, Receive> {
+    type Target = Coroutine<'a, Yield, Return, Receive>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a, Yield: 'static, Return: 'static, Receive: 'a> UnwindSafeCoroutine<'a, Yield, Return, Receive> {
+    /// Constructs a new coroutine by given closure, which can't capture anything a caught panic could leave broken (like mutable references)
+    ///
+    /// ```compile_fail,E0277
+    /// use rusterators::coroutines::UnwindSafeCoroutine;
+    /// let mut count = 0;
+    /// let co = UnwindSafeCoroutine::<(), (), ()>::new(|_, _| count += 1);
+    /// ```
+    pub fn new(handler: impl FnOnce(&mut CoroutineChannel<Yield, Return, Receive>, Receive) -> Return + UnwindSafe + 'a) -> Self {
+        Self(Coroutine::new(handler))
+    }
+    /// Sends a given value to the coroutine, see [Coroutine::resume]
+    pub fn resume(&mut self, send: Receive) -> ResumeResult<Yield, Return> {
+        self.0.resume(send)
+    }
+    /// Like [resume] but reports failures as [CoroutineError] instead of panicking, see [Coroutine::resume_checked]
+    pub fn resume_checked(&mut self, send: Receive) -> Result<ResumeResult<Yield, Return>, CoroutineError> {
+        self.0.resume_checked(send)
+    }
+    /// Like [resume] but returns None if the coroutine has already completed, see [Coroutine::resume_or_finished]
+    pub fn resume_or_finished(&mut self, send: Receive) -> Option<ResumeResult<Yield, Return>> {
+        self.0.resume_or_finished(send)
+    }
+    /// Hands out the data of the panic the routine has unwinded with, see [Coroutine::take_panic]
+    pub fn take_panic(&mut self) -> Option<PanicData> {
+        self.0.take_panic()
+    }
+}
+
+// The routine is unwind safe by the bound of [UnwindSafeCoroutine::new], so whatever it captures can't be observed broken after its panic has been caught.
+// The other fields of the coroutine preventing the auto impl don't break that either: a panic of the routine completes the coroutine before it is rethrown,
+// the stack factory is gone after the first resume, the OnceCells only initialize handles lazily and the stored panic data is just handed out again
+impl<'a, Yield: UnwindSafe + 'static, Return: UnwindSafe + 'static, Receive: UnwindSafe + 'a> UnwindSafe for UnwindSafeCoroutine<'a, Yield, Return, Receive> {}
+
+// Shared references only allow to query the coroutine and to create handles, none of which resumes the routine or mutates what it captured
+impl<'a, Yield: RefUnwindSafe + 'static, Return: RefUnwindSafe + 'static, Receive: RefUnwindSafe + 'a> RefUnwindSafe for UnwindSafeCoroutine<'a, Yield, Return, Receive> {}
+
+impl<'a, Yield: 'static, Return: 'static, Receive: 'a> Deref for UnwindSafeCoroutine<'a, Yield, Return, Receive> {
+    type Target = Coroutine<'a, Yield, Return, Receive>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Runs [f] with a [Scope] whose generators and coroutines may borrow anything outliving the call, like threads spawned by std::thread::scope
+/// A generator borrowing stack-local data can't leave the scope:
+///
+/// ```compile_fail
+/// use rusterators::coroutines::scope;
+/// use rusterators::generators::GeneratorChannel;
+/// let text = String::from("a bc def");
+/// let lengths = scope(|s| s.generator(|chan| chan.yield_all(text.split(' ').map(str::len))));
+/// ```
+///
+/// and borrowed data can't be changed while a generator is alive (see tests/ui for the errors expected):
+///
+/// ```compile_fail,E0502
+/// use rusterators::coroutines::scope;
+/// use rusterators::generators::GeneratorChannel;
+/// let mut text = String::from("a bc def");
+/// scope(|s| {
+///     let lengths = s.generator(|chan| chan.yield_all(text.split(' ').map(str::len)));
+///     text.clear();
+///     lengths.count()
+/// });
+/// ```
+pub fn scope<'env, T>(f: impl for<'scope> FnOnce(&'scope Scope<'scope, 'env>) -> T) -> T {
+    f(&Scope { scope: PhantomData, env: PhantomData })
+}
+
+/// Lets [a] and [b] converse until either returns: [b] is resumed with [first] and each request [a] yields, [a] with each response [b] yields
+/// Thus [a] starts with the response to [first] as initial value. A panic of either coroutine is rethrown telling which side it occurred on
+pub fn connect<'a, Req: 'static, Resp: 'static, Ra: 'static, Rb: 'static>(mut a: Coroutine<'a, Req, Ra, Resp>, mut b: Coroutine<'a, Resp, Rb, Req>, first: Req) -> DuplexOutcome<'a, Req, Resp, Ra, Rb> {
+    let mut request = first;
+    loop {
+        let response = match resume_side(&mut b, request, 'b') {
+            ResumeResult::Yield(response) => response,
+            ResumeResult::Return(rb) => return DuplexOutcome::BReturned(rb, a),
+        };
+        request = match resume_side(&mut a, response, 'a') {
+            ResumeResult::Yield(request) => request,
+            ResumeResult::Return(ra) => return DuplexOutcome::AReturned(ra, b),
+        };
+    }
+}
+
+/// Resumes a coroutine driven by [connect] panicking with a message naming its [side] if it fails
+fn resume_side<'a, Yield: 'static, Return: 'static, Receive: 'a>(co: &mut Coroutine<'a, Yield, Return, Receive>, send: Receive, side: char) -> ResumeResult<Yield, Return> {
+    co.resume_checked(send).unwrap_or_else(|e| panic!("side {} of duplex pair failed: {}", side, NamedError(&e, co.name())))
+}
+
+impl<'scope, 'env> Scope<'scope, 'env> {
+    /// Creates a coroutine by given closure which may borrow data living longer than the scope, see [Coroutine::new]
+    pub fn coroutine<Yield: 'static, Return: 'static, Receive: 'scope>(&'scope self, handler: impl FnOnce(&mut CoroutineChannel<Yield, Return, Receive>, Receive) -> Return + 'scope) -> Coroutine<'scope, Yield, Return, Receive> {
+        Coroutine::new(handler)
+    }
+    /// Creates a non-receiving generator by given closure which may borrow data living longer than the scope, see [BoostedGenerator::new]
+    pub fn generator<Yield: 'static, Return: 'static, F>(&'scope self, gen_fn: F) -> BoostedGenerator<'scope, Yield, Return, ()>
+        where F: FnOnce(&mut BoostedGeneratorChannel<Yield, Return, ()>) -> Return + 'scope {
+        BoostedGenerator::new(gen_fn)
+    }
+    /// Creates a receiving generator by given closure which may borrow data living longer than the scope, see [BoostedGenerator::new_receiving]
+    pub fn receiving_generator<Yield: 'static, Return: 'static, Receive: 'scope, F>(&'scope self, gen_fn: F) -> BoostedGenerator<'scope, Yield, Return, Receive>
+        where F: FnOnce(&mut BoostedGeneratorChannel<Yield, Return, Receive>, Receive) -> Return + 'scope {
+        BoostedGenerator::new_receiving(gen_fn)
+    }
+}
+
 impl<'a, Yield: 'static, Return: 'static, Receive: 'a> CoroutineChannel<'a, Yield, Return, Receive> {
     /// Suspends execution control to invocation context yielding the given value and waits for resume
     /// On resume it returns the value yielded by other contexts resume call
     pub fn suspend(&mut self, send: Yield) -> Receive {
-        let received = self.0.yield_with(SuspenseType::Yield(send));
+        self.switch(SuspenseType::Yield(send))
+    }
+
+    /// Like [suspend] but returns [Cancelled] instead of the received value if the coroutine has been cancelled
+    /// Once cancelled, every further call returns [Cancelled], so the routine should finish up and return
+    pub fn suspend_checked(&mut self, send: Yield) -> Result<Receive, Cancelled> {
+        let received = self.suspend(send);
+        if self.1 { Err(Cancelled) } else { Ok(received) }
+    }
+
+    /// Queries whether the coroutine has been cancelled by its [CancellationToken]
+    /// The cancellation becomes visible with the resume following the cancel call, which may be the initial one
+    pub fn is_cancelled(&self) -> bool {
+        self.1
+    }
+
+    /// Suspends execution control yielding all values of [batch] with a single context switch
+    /// The invocation context hands them out one by one, so each resume returns the next value while the coroutine stays suspended
+    /// Values sent along with those resumes are dropped, the value sent by the resume following the last value of the batch is returned
+    /// Panics if [batch] is empty
+    pub fn suspend_batch(&mut self, batch: Vec<Yield>) -> Receive {
+        assert!(!batch.is_empty(), "tried to suspend with an empty batch");
+        self.switch(SuspenseType::YieldBatch(batch))
+    }
+
+    /// Suspends execution control letting the invocation context call [target] for the values to yield until it returns None, which saves switching to this context for each of them
+    /// [target] gets the [DelegateStack] of the outermost coroutine, which nested coroutines resumed by [target] push their own delegates to
+    /// Values sent along with the resumes meanwhile are dropped like for [suspend_batch]. Returns once [target] has no value left or panicked
+    pub(crate) fn delegate(&mut self, target: &mut dyn FnMut(&mut DelegateStack<Yield>) -> Option<Yield>) {
+        trace::coroutine_event("delegate");
+        // the target outlives its use, since this context stays suspended until the invocation context is done with it
+        let target = unsafe {
+            transmute::<*mut (dyn FnMut(&mut DelegateStack<Yield>) -> Option<Yield> + '_), *mut (dyn FnMut(&mut DelegateStack<Yield>) -> Option<Yield> + 'static)>(target)
+        };
+        let received = self.0.switch(SuspenseType::Delegate(DelegateTarget(target)));
+        self.activate();
+        match received {
+            ResumeType::Delegated(cancelled) => self.1 |= cancelled,
+            ResumeType::Drop() => resume_unwind(Box::new(DropUnwind)),
+            _ => panic!("delegating coroutine resumed before its delegate completed"),
+        }
+    }
+
+    /// Transfers a suspending message to invocation context and waits for resume
+    fn switch(&mut self, message: SuspenseType<Yield, Return>) -> Receive {
+        trace::coroutine_event("yield");
+        let received = self.0.switch(message);
+        self.activate();
         self.receive(received)
     }
 
+    /// Registers this context as the one executing on the current thread, which is needed to recover from an overflow of its stack
+    #[inline]
+    fn activate(&mut self) {
+        #[cfg(all(unix, feature = "stack-overflow-recovery"))]
+        overflow::activate(&mut self.0 as *mut RawChannel<_, _> as *mut (), dispose_overflowed::<Yield, Return, Receive>);
+    }
+
     /// Internally handles transferred message
-    /// In case of a Yield just returns encapsulated value
-    /// In case of a Drop a panic is thrown after marking panic as "controlled stack unwind"
+    /// In case of a Yield just returns encapsulated value, a Cancelled one is returned after the cancellation has been noted
+    /// In case of a Drop a panic carrying [DropUnwind] is thrown as "controlled stack unwind"
     fn receive(&mut self, r: ResumeType<Receive>) -> Receive {
         match r {
             ResumeType::Yield(y) => y,
-            ResumeType::Drop() => {
+            ResumeType::Cancelled(y) => {
                 self.1 = true;
-                resume_unwind(Box::new(()))
+                y
             }
+            ResumeType::Delegated(_) => panic!("coroutine resumed as delegating without delegating"),
+            ResumeType::Drop() => resume_unwind(Box::new(DropUnwind))
         }
     }
 }
 
-impl<'a, Yield: 'static, Return: 'static, Receive: 'a> InvocationChannel<'a, Yield, Return, Receive> {
-    /// resumes execution of coroutine context yielding given value and waits for next suspend returning the encoded control flow type (Yield/Complete see [SuspenseType] and parameters)
-    fn suspend(&mut self, send: Receive) -> SuspenseType<Yield, Return> {
-        self.0.yield_with(ResumeType::Yield(send))
+/// Stack reserved in each segment for the frames setting it up, on top of the bytes requested by [CoroutineChannel::ensure_stack]
+#[cfg(feature = "segmented-stacks")]
+const SEGMENT_RESERVE: usize = 16 * 1024;
+
+#[cfg(feature = "segmented-stacks")]
+impl<'a, Yield: 'static, Return: 'static, Receive: 'a> CoroutineChannel<'a, Yield, Return, Receive> {
+    /// Runs [f] right away if at least [bytes] bytes are left on the current stack, otherwise on a fresh stack segment providing at least [bytes] bytes
+    /// This is the only point the stack of a coroutine ever grows at, e.g. a recursive parser calls it before descending
+    /// Suspending within [f] works as usual and dropping the coroutine meanwhile unwinds [f] before unwinding continues on the previous stack
+    /// The segment is released as soon as [f] returns. Overflows of a segment are not recovered by the stack-overflow-recovery feature
+    pub fn ensure_stack<R>(&mut self, bytes: usize, f: impl FnOnce(&mut Self) -> R) -> R {
+        if self.0.remaining_stack() >= bytes {
+            return f(self);
+        }
+        let segment = StackFactory::of_size(bytes + SEGMENT_RESERVE).build();
+        let previous_limit = self.0.replace_stack_limit(segment.bottom() as usize);
+        let result = {
+            let channel = &mut *self;
+            let mut segment = RawCoroutine::<(), Result<R, PanicData>>::spawn(StackFactory::of_stack(segment), move |_, ()| {
+                catch_unwind(AssertUnwindSafe(|| f(channel)))
+            });
+            segment.switch(())
+        };
+        self.0.replace_stack_limit(previous_limit);
+        result.unwrap_or_else(|p| resume_unwind(p))
     }
-    /// Causes coroutine execution context to unwind and checks whether consistent result is archieved
-    fn unwind(&mut self) {
-        match self.0.yield_with(ResumeType::Drop()) {
-            SuspenseType::Complete(CompleteType::Unwind(_)) => (),
-            _ => panic!("Invalid coroutine unwind result")
+}
+
+impl<Yield> DelegateStack<Yield> {
+    /// Calls the innermost delegate above [base] for its next value, a delegate having no value left or panicking is dropped along with everything above it
+    /// The panic is not passed on, the context delegating learns about it from the delegate itself (e.g. the result of the generator it yields from)
+    fn next_above(&mut self, base: usize) -> Option<Yield> {
+        while self.0.len() > base {
+            let innermost = self.0.len() - 1;
+            let target = self.0[innermost].0;
+            // the context delegating stays suspended in the meantime, so the target on its stack is still in place
+            match catch_unwind(AssertUnwindSafe(|| unsafe { (*target)(self) })) {
+                Ok(Some(y)) => return Some(y),
+                _ => self.0.truncate(innermost),
+            }
         }
+        None
     }
 }
 
+impl<'a, Yield: 'static, Return: 'static, Receive: 'a> InvocationChannel<'a, Yield, Return, Receive> {
+    /// Returns the callstack the coroutine context runs on
+    fn stack(&self) -> &OwnedStack {
+        self.0.stack()
+    }
+    /// resumes execution of coroutine context yielding given value and waits for next suspend returning the encoded control flow type (Yield/Complete see [SuspenseType] and parameters)
+    /// [cancelled] tells the coroutine context that it has been cancelled
+    fn suspend(&mut self, send: Receive, cancelled: bool) -> SuspenseType<Yield, Return> {
+        self.switch(if cancelled { ResumeType::Cancelled(send) } else { ResumeType::Yield(send) })
+    }
+    /// resumes execution of a coroutine context whose delegate has completed, see [CoroutineChannel::delegate]
+    fn end_delegation(&mut self, cancelled: bool) -> SuspenseType<Yield, Return> {
+        self.switch(ResumeType::Delegated(cancelled))
+    }
+    /// Causes coroutine execution context to unwind and checks whether consistent result is archieved
+    fn unwind(&mut self) {
+        self.unwind_checked().unwrap_or_else(|e| panic!("{}", e))
+    }
+    /// Like [unwind] but reports an inconsistent result as [CoroutineError::Protocol]
+    fn unwind_checked(&mut self) -> Result<(), CoroutineError> {
+        match self.request_unwind() {
+            SuspenseType::Complete(CompleteType::Unwind(_)) => Ok(()),
+            _ => Err(CoroutineError::Protocol("Invalid coroutine unwind result"))
+        }
+    }
+    /// Causes coroutine execution context to unwind and returns its answer, which is expected to be an Unwind completion
+    fn request_unwind(&mut self) -> SuspenseType<Yield, Return> {
+        self.switch(ResumeType::Drop())
+    }
+    /// Transfers [message] to the coroutine context and waits for its answer, whereby the thread is marked as running a coroutine context
+    fn switch(&mut self, message: ResumeType<Receive>) -> SuspenseType<Yield, Return> {
+        let _routine = backtrace::RoutineScope::enter();
+        self.0.switch(message)
+    }
+}
+
+/// Displays a [CoroutineError] naming the coroutine it occurred in, in case the coroutine has a name
+struct NamedError<'e>(&'e CoroutineError, Option<&'e str>);
+
+impl<'e> Display for NamedError<'e> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let subject = match self.1 {
+            Some(name) => format!("coroutine '{}'", name),
+            None => "Coroutine".to_string(),
+        };
+        match self.0 {
+            CoroutineError::Completed => write!(f, "tried to send to non-running context"),
+            CoroutineError::Panicked(Some(message)) => write!(f, "{} panicked: {}", subject, message),
+            CoroutineError::Panicked(None) => write!(f, "{} panicked", subject),
+            CoroutineError::Dropped => write!(f, "coroutine context dropped outside of coroutine destructor"),
+            CoroutineError::StackOverflow => write!(f, "{} stack overflowed", subject),
+            CoroutineError::Protocol(message) => write!(f, "{}", message),
+        }?;
+        match (self.0, self.1) {
+            (CoroutineError::Completed, Some(name)) | (CoroutineError::Dropped, Some(name)) | (CoroutineError::Protocol(_), Some(name)) =>
+                write!(f, " (coroutine '{}')", name),
+            _ => Ok(())
+        }
+    }
+}
+
+impl Display for CoroutineError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        NamedError(self, None).fmt(f)
+    }
+}
+
+impl Error for CoroutineError {}
+
+impl<Yield, Return> ResumeResult<Yield, Return> {
+    /// Queries whether the coroutine has yielded
+    pub fn is_yield(&self) -> bool {
+        matches!(self, ResumeResult::Yield(_))
+    }
+    /// Queries whether the coroutine has returned
+    pub fn is_return(&self) -> bool {
+        matches!(self, ResumeResult::Return(_))
+    }
+    /// Returns the yielded value, or None if the coroutine has returned
+    ///
+    /// ```
+    /// use rusterators::coroutines::Coroutine;
+    /// let mut co = Coroutine::<i32, &str, ()>::new(|chan, _| { chan.suspend(1); "done" });
+    /// assert_eq!(co.resume(()).yielded(), Some(1));
+    /// assert_eq!(co.resume(()).yielded(), None);
+    /// ```
+    pub fn yielded(self) -> Option<Yield> {
+        match self {
+            ResumeResult::Yield(y) => Some(y),
+            ResumeResult::Return(_) => None,
+        }
+    }
+    /// Returns the returned value, or None if the coroutine has yielded
+    ///
+    /// ```
+    /// use rusterators::coroutines::Coroutine;
+    /// let mut co = Coroutine::<i32, &str, ()>::new(|chan, _| { chan.suspend(1); "done" });
+    /// assert_eq!(co.resume(()).returned(), None);
+    /// assert_eq!(co.resume(()).returned(), Some("done"));
+    /// ```
+    pub fn returned(self) -> Option<Return> {
+        match self {
+            ResumeResult::Yield(_) => None,
+            ResumeResult::Return(r) => Some(r),
+        }
+    }
+    /// Returns the yielded value, panics if the coroutine has returned
+    ///
+    /// ```should_panic
+    /// use rusterators::coroutines::Coroutine;
+    /// let mut co = Coroutine::<i32, &str, ()>::new(|_, _| "done");
+    /// co.resume(()).unwrap_yield();
+    /// ```
+    pub fn unwrap_yield(self) -> Yield {
+        match self {
+            ResumeResult::Yield(y) => y,
+            ResumeResult::Return(_) => panic!("called `ResumeResult::unwrap_yield()` on a `Return` value"),
+        }
+    }
+    /// Returns the returned value, panics if the coroutine has yielded
+    ///
+    /// ```should_panic
+    /// use rusterators::coroutines::Coroutine;
+    /// let mut co = Coroutine::<i32, &str, ()>::new(|chan, _| { chan.suspend(1); "done" });
+    /// co.resume(()).unwrap_return();
+    /// ```
+    pub fn unwrap_return(self) -> Return {
+        match self {
+            ResumeResult::Yield(_) => panic!("called `ResumeResult::unwrap_return()` on a `Yield` value"),
+            ResumeResult::Return(r) => r,
+        }
+    }
+    /// Maps a yielded value by [f], leaving a returned value untouched
+    ///
+    /// ```
+    /// use rusterators::coroutines::ResumeResult;
+    /// let result = ResumeResult::<i32, &str>::Yield(2).map_yield(|y| y * 10);
+    /// assert_eq!(result, ResumeResult::Yield(20));
+    /// ```
+    pub fn map_yield<T>(self, f: impl FnOnce(Yield) -> T) -> ResumeResult<T, Return> {
+        match self {
+            ResumeResult::Yield(y) => ResumeResult::Yield(f(y)),
+            ResumeResult::Return(r) => ResumeResult::Return(r),
+        }
+    }
+    /// Maps a returned value by [f], leaving a yielded value untouched
+    ///
+    /// ```
+    /// use rusterators::coroutines::ResumeResult;
+    /// let result = ResumeResult::<i32, &str>::Return("done").map_return(str::len);
+    /// assert_eq!(result, ResumeResult::Return(4));
+    /// ```
+    pub fn map_return<T>(self, f: impl FnOnce(Return) -> T) -> ResumeResult<Yield, T> {
+        match self {
+            ResumeResult::Yield(y) => ResumeResult::Yield(y),
+            ResumeResult::Return(r) => ResumeResult::Return(f(r)),
+        }
+    }
+}
+
+/// A yield continues, a return breaks, which allows to drive coroutines by ? in functions returning ControlFlow
+///
+/// ```
+/// use std::ops::ControlFlow;
+/// use rusterators::coroutines::ResumeResult;
+/// assert_eq!(ControlFlow::from(ResumeResult::<i32, &str>::Yield(1)), ControlFlow::Continue(1));
+/// assert_eq!(ControlFlow::from(ResumeResult::<i32, &str>::Return("done")), ControlFlow::Break("done"));
+/// ```
+impl<Yield, Return> From<ResumeResult<Yield, Return>> for ControlFlow<Return, Yield> {
+    fn from(result: ResumeResult<Yield, Return>) -> Self {
+        match result {
+            ResumeResult::Yield(y) => ControlFlow::Continue(y),
+            ResumeResult::Return(r) => ControlFlow::Break(r),
+        }
+    }
+}
+
+impl CompletionWatcher {
+    /// Creates a watcher reporting [completion], i.e. a live coroutine if None
+    pub(crate) fn new(completion: Option<CompletionKind>) -> Self {
+        let watcher = Self(Arc::new(AtomicU8::new(0)));
+        if let Some(kind) = completion {
+            watcher.set(kind);
+        }
+        watcher
+    }
+    fn set(&self, kind: CompletionKind) {
+        let encoded = match kind {
+            CompletionKind::Returned => 1,
+            CompletionKind::Panicked => 2,
+            CompletionKind::StackOverflow => 3,
+            CompletionKind::Dropped => 4,
+        };
+        self.0.store(encoded, Ordering::Release)
+    }
+    /// Queries whether the coroutine is not live anymore, i.e. has completed or has been dropped
+    pub fn is_completed(&self) -> bool {
+        self.completion_kind().is_some()
+    }
+    /// Returns the way the coroutine stopped being live, or None while it is live
+    pub fn completion_kind(&self) -> Option<CompletionKind> {
+        match self.0.load(Ordering::Acquire) {
+            1 => Some(CompletionKind::Returned),
+            2 => Some(CompletionKind::Panicked),
+            3 => Some(CompletionKind::StackOverflow),
+            4 => Some(CompletionKind::Dropped),
+            _ => None
+        }
+    }
+}
+
+impl CancellationToken {
+    /// Cancels the coroutine, which notices it on its next resume
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Release)
+    }
+    /// Queries whether [cancel] has been called on any token of the coroutine
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+/// Extracts the message of a panic in case its data is a string, which is the case for panics raised by panic! and its relatives
+pub(crate) fn panic_message(panic: &PanicData) -> Option<String> {
+    panic.downcast_ref::<&str>().map(|s| s.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+}
+
 type DynFn<'a, Yield, Return, Receive> = dyn FnOnce(&mut CoroutineChannel<Yield, Return, Receive>, Receive) -> Return + 'a;
+type DynRestartFn<'a, Yield, Return, Receive> = dyn Fn(&mut CoroutineChannel<Yield, Return, Receive>, Receive) -> Return + 'a;
 
 /// "Bootstrap" function for coroutine context
 /// This wraps baremetal Boost:context execution by receiving closure struct, initing communication channel and wrapping closure execution in order to have a clean stack unwind in any case
-extern "C" fn run_co_context<Yield: 'static, Return: 'static, Receive>(raw_transfer: Transfer) -> ! {
-    let (mut exchange_transfer, routine_fn) =
-        ExchangingTransfer::<SuspenseType<Yield, Return>, ResumeType<Receive>>::
-        create_receiving::<Box<DynFn<Yield, Return, Receive>>>(raw_transfer);
-    let initial = exchange_transfer.suspend();
-    let mut channel = CoroutineChannel(exchange_transfer, false);
+/// The closure is received by value, i.e. [F] is either a boxed closure or the concrete type of an unboxed one
+extern "C" fn run_co_context<Yield: 'static, Return: 'static, Receive, F>(raw_transfer: Transfer) -> !
+    where F: FnOnce(&mut CoroutineChannel<Yield, Return, Receive>, Receive) -> Return {
+    // started by Coroutine::spawn passing the routine
+    let (mut raw_channel, routine_fn) = unsafe { RawChannel::<SuspenseType<Yield, Return>, ResumeType<Receive>>::accept::<F>(raw_transfer) };
+    let initial = raw_channel.suspend();
+    let mut channel = CoroutineChannel(raw_channel, false);
+    channel.activate();
+    backtrace::prepare();
 
     let result = catch_unwind(AssertUnwindSafe(|| {
        let initial = channel.receive(initial);
         routine_fn(&mut channel, initial)
     }));
 
-    channel.0.dispose_with(SuspenseType::Complete(match result {
-        Ok(ret) => CompleteType::Return(ret),
-        Err(p) => CompleteType::Unwind(if channel.1 { UnwindReason::Drop } else { UnwindReason::Panic(p) })
-    }))
+    // nothing may unwind beyond this point, the guard is left behind on the stack once the completion has been handed over
+    let _guard = AbortOnUnwind("completing the routine");
+    let complete = match catch_unwind(AssertUnwindSafe(|| completion(result))) {
+        Ok(complete) => complete,
+        // e.g. dropping the payload of the unwind panicked, which is reported instead
+        Err(p) => CompleteType::Unwind(UnwindReason::Panic(p, backtrace::take())),
+    };
+    channel.0.dispose_with(SuspenseType::Complete(complete))
+}
+
+/// Turns the result of a routine into the completion handed over to the invocation context
+fn completion<Return>(result: Result<Return, PanicData>) -> CompleteType<Return> {
+    let complete = match result {
+        Ok(ret) => CompleteType::Return(Box::new(ret)),
+        Err(p) => CompleteType::Unwind(if p.is::<DropUnwind>() { UnwindReason::Drop } else { UnwindReason::Panic(p, backtrace::take()) })
+    };
+    trace::coroutine_event(match complete {
+        CompleteType::Return(_) => "return",
+        CompleteType::Unwind(UnwindReason::Drop) => "drop",
+        CompleteType::Unwind(_) => "panic",
+    });
+    complete
+}
+
+/// Aborts the process with an explanation once dropped, i.e. as soon as a panic unwinds through the scope it lives in
+/// Guards code at the bottom of a coroutine context, which can't unwind any further
+struct AbortOnUnwind(&'static str);
+
+impl Drop for AbortOnUnwind {
+    fn drop(&mut self) {
+        eprintln!("panicked while {} at the bottom of a coroutine context, which can't be unwinded any further; aborting", self.0);
+        abort()
+    }
+}
+
+/// Completes the coroutine context owning [transfer] for good after its stack overflowed
+/// Called by the fault handler on the alternate signal stack, see [overflow::activate]
+#[cfg(all(unix, feature = "stack-overflow-recovery"))]
+unsafe fn dispose_overflowed<Yield: 'static, Return: 'static, Receive>(transfer: *mut ()) -> ! {
+    let channel = &mut *(transfer as *mut RawChannel<SuspenseType<Yield, Return>, ResumeType<Receive>>);
+    channel.dispose_with(SuspenseType::Complete(CompleteType::Unwind(UnwindReason::StackOverflow)))
+}
+
+/// Stands in for [overflow::ResumeScope] if stack overflow recovery is disabled
+#[cfg(not(all(unix, feature = "stack-overflow-recovery")))]
+struct ResumeScope;
+
+#[cfg(not(all(unix, feature = "stack-overflow-recovery")))]
+impl ResumeScope {
+    #[inline]
+    fn enter(_stack: &OwnedStack) -> Self {
+        ResumeScope
+    }
 }
 
 /// a lot of really good tests
 #[cfg(test)]
 mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
     use context::{Context, ContextFn, Transfer};
     use context::stack::ProtectedFixedSizeStack;
 
+    use std::hint::black_box;
+
+    use crate::generators::{Generator, GeneratorChannel};
+
+    use super::{connect, scope, AbortError, AbortOnUnwind, Cancelled, CompleteVariant, CompletionKind, Coroutine, CoroutinePanic, DuplexOutcome, LendingCoroutine, SendCoroutine, CoroutineError, ResumeResult, RestartableCoroutine, StackFactory, Suspended, TypedCoroutine, UnwindSafeCoroutine};
+
     static mut STATIC_TEST_STACK: Option<ProtectedFixedSizeStack> = None;
 
     fn create_test_context(test_fn: ContextFn, start_data: usize) -> Transfer {
@@ -248,4 +1633,1063 @@ mod tests {
         }
         unsafe { Transfer::new(Context::new(STATIC_TEST_STACK.as_ref().unwrap(), test_fn), start_data) }
     }
+
+    struct DropCounter(Rc<Cell<usize>>);
+
+    impl Drop for DropCounter {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1)
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn restartable_runs_again_after_completion() {
+        let mut co = RestartableCoroutine::new(|chan, start: i32| {
+            let next = chan.suspend(start + 1);
+            next * 2
+        });
+        for _ in 0..2 {
+            assert!(matches!(co.resume(1), ResumeResult::Yield(2)));
+            assert!(matches!(co.resume(5), ResumeResult::Return(10)));
+            assert!(co.is_completed());
+            co.restart();
+            assert!(!co.is_completed());
+        }
+        assert_eq!(co.has_runs(), 2);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    #[should_panic(expected = "coroutine 'restarted' panicked: second run failed")]
+    fn restartable_panic_names_coroutine() {
+        let mut co = RestartableCoroutine::<(), (), i32>::new(|_, run| {
+            if run > 1 {
+                panic!("second run failed")
+            }
+        }).with_name("restarted");
+        co.resume(1);
+        co.restart();
+        co.resume(2);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn restartable_counts_started_runs_only() {
+        let mut co = RestartableCoroutine::<(), i32, i32>::new(|_, i| i);
+        assert_eq!(co.has_runs(), 0);
+        co.restart();
+        assert_eq!(co.has_runs(), 0);
+        co.resume(1);
+        assert_eq!(co.has_runs(), 1);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn restartable_unwinds_running_context_on_restart() {
+        let drops = Rc::new(Cell::new(0));
+        let counter = drops.clone();
+        let mut co = RestartableCoroutine::new(move |chan, _: ()| {
+            let _guard = DropCounter(counter.clone());
+            loop {
+                chan.suspend(());
+            }
+        });
+        co.resume(());
+        co.restart();
+        assert_eq!(drops.get(), 1);
+        co.resume(());
+        assert_eq!(co.has_runs(), 2);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn restartable_reuses_stack_of_running_context() {
+        let mut co = RestartableCoroutine::new(|chan, _: ()| {
+            let local = 0u8;
+            chan.suspend(&local as *const u8 as usize);
+        });
+        let first = match co.resume(()) {
+            ResumeResult::Yield(address) => address,
+            _ => panic!("coroutine should yield")
+        };
+        co.restart();
+        let second = match co.resume(()) {
+            ResumeResult::Yield(address) => address,
+            _ => panic!("coroutine should yield")
+        };
+        assert_eq!(first, second);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn unboxed_coroutine_yields_and_returns() {
+        let offset = 10;
+        let mut co = Coroutine::new_unboxed(|chan, i: i32| {
+            let next = chan.suspend(i + offset);
+            next + offset
+        });
+        assert!(!co.is_completed());
+        assert!(matches!(co.resume(1), ResumeResult::Yield(11)));
+        assert!(matches!(co.resume(2), ResumeResult::Return(12)));
+        assert!(co.is_completed());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn unboxed_coroutine_unwinds_on_drop() {
+        let drops = Rc::new(Cell::new(0));
+        let counter = drops.clone();
+        let mut co = Coroutine::new_unboxed(move |chan, _: ()| {
+            let _guard = DropCounter(counter);
+            chan.suspend(());
+        });
+        co.resume(());
+        drop(co);
+        assert_eq!(drops.get(), 1);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn resume_unit_resumes_with_unit() {
+        let mut co = Coroutine::new(|chan, _| {
+            chan.suspend(1);
+            2
+        });
+        assert!(matches!(co.resume_unit(), ResumeResult::Yield(1)));
+        assert!(matches!(co.resume_unit(), ResumeResult::Return(2)));
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn batch_is_handed_out_by_single_switch() {
+        let switches = Rc::new(Cell::new(0));
+        let counter = switches.clone();
+        let mut co = Coroutine::new(move |chan, first: i32| {
+            counter.set(counter.get() + 1);
+            let next = chan.suspend_batch(vec![first, 2, 3]);
+            counter.set(counter.get() + 1);
+            next
+        });
+        assert!(matches!(co.resume(1), ResumeResult::Yield(1)));
+        assert!(matches!(co.resume(10), ResumeResult::Yield(2)));
+        assert!(matches!(co.resume(20), ResumeResult::Yield(3)));
+        assert_eq!(switches.get(), 1);
+        assert!(matches!(co.resume(30), ResumeResult::Return(30)));
+        assert_eq!(switches.get(), 2);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    #[should_panic(expected = "Coroutine panicked")]
+    fn empty_batch_is_rejected() {
+        let mut co = Coroutine::<i32, (), ()>::new(|chan, _| {
+            chan.suspend_batch(Vec::new());
+        });
+        co.resume(());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn typed_coroutine_alternates_until_finished() {
+        let co = TypedCoroutine::new(|chan, start: i32| {
+            let next = chan.suspend(start + 1);
+            next * 2
+        });
+        let (yielded, next) = co.start(1).yielded().unwrap();
+        assert_eq!(yielded, 2);
+        match next.resume(5) {
+            Suspended::Finished(r) => assert_eq!(r, 10),
+            Suspended::Yielded(..) => panic!("coroutine should finish"),
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn abort_unwinds_running_coroutine() {
+        let drops = Rc::new(Cell::new(0));
+        let counter = drops.clone();
+        let mut co = Coroutine::new(move |chan, _: ()| {
+            let _guard = DropCounter(counter);
+            chan.suspend(());
+        });
+        co.resume(());
+        assert!(co.abort().is_ok());
+        assert_eq!(drops.get(), 1);
+        assert!(Coroutine::<(), (), ()>::new(|_, _| ()).abort().is_ok());
+    }
+
+    struct PanickingGuard;
+
+    impl Drop for PanickingGuard {
+        fn drop(&mut self) {
+            panic!("cleanup failed")
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn abort_reports_panicking_cleanup() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let mut co = Coroutine::new(|chan, _: ()| {
+            let _guard = PanickingGuard;
+            let _ = catch_unwind(AssertUnwindSafe(|| chan.suspend(())));
+        });
+        co.resume(());
+        match co.abort() {
+            Err(AbortError::CleanupPanicked(payload)) => assert_eq!(*payload.downcast_ref::<&str>().unwrap(), "cleanup failed"),
+            other => panic!("unexpected abort result {:?}", other),
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn abort_leaks_intercepting_coroutine() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let drops = Rc::new(Cell::new(0));
+        let counter = drops.clone();
+        let mut co = Coroutine::new(move |chan, _: ()| {
+            let _guard = DropCounter(counter);
+            loop {
+                let _ = catch_unwind(AssertUnwindSafe(|| chan.suspend(())));
+            }
+        });
+        co.resume(());
+        // dropping the aborted coroutine does not request another unwind, which would be intercepted again
+        assert!(matches!(co.abort(), Err(AbortError::Intercepted)));
+        assert_eq!(drops.get(), 0);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn buffered_yields_merge_sorted_coroutines() {
+        let sorted = |values: Vec<i32>| Coroutine::<i32, (), ()>::new(move |chan, _| {
+            for v in values {
+                chan.suspend(v);
+            }
+        });
+        let mut heads = [sorted(vec![1, 4, 5]), sorted(vec![2, 3, 6])];
+        for head in heads.iter_mut() {
+            head.resume_buffered(());
+        }
+        let mut merged = Vec::new();
+        while let Some(next) = heads.iter_mut()
+            .filter(|head| head.last_yield().is_some())
+            .min_by_key(|head| *head.last_yield().unwrap()) {
+            merged.push(next.take_yield().unwrap());
+            assert!(next.last_yield().is_none());
+            next.resume_buffered(());
+        }
+        assert_eq!(merged, vec![1, 2, 3, 4, 5, 6]);
+        assert!(heads.iter().all(|head| head.is_completed()));
+    }
+
+    /// Counts up until it gets cancelled and returns the count
+    fn counting_until_cancelled() -> Coroutine<'static, u32, u32, ()> {
+        Coroutine::new(|chan, _| {
+            let mut count = 0;
+            while !chan.is_cancelled() {
+                count += 1;
+                if chan.suspend_checked(count) == Err(Cancelled) {
+                    break;
+                }
+            }
+            count
+        })
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn cancellation_before_first_resume() {
+        let mut co = counting_until_cancelled();
+        co.cancellation_token().cancel();
+        assert!(matches!(co.resume(()), ResumeResult::Return(0)));
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn cancellation_mid_stream() {
+        let mut co = counting_until_cancelled();
+        let token = co.cancellation_token();
+        assert!(matches!(co.resume(()), ResumeResult::Yield(1)));
+        assert!(matches!(co.resume(()), ResumeResult::Yield(2)));
+        std::thread::spawn(move || token.cancel()).join().unwrap();
+        assert!(co.cancellation_token().is_cancelled());
+        assert!(matches!(co.resume(()), ResumeResult::Return(2)));
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn send_coroutine_resumes_on_other_threads() {
+        use std::thread;
+
+        let mut co = unsafe {
+            SendCoroutine::new(|chan, start: thread::ThreadId| {
+                let second = chan.suspend(start);
+                (second, thread::current().id())
+            })
+        };
+        let created_on = thread::current().id();
+        let (mut co, started_on) = thread::spawn(move || {
+            let started_on = thread::current().id();
+            match co.resume(started_on) {
+                ResumeResult::Yield(id) => assert_eq!(id, started_on),
+                _ => panic!("coroutine should yield")
+            }
+            (co, started_on)
+        }).join().unwrap();
+        let (received, completed_on) = thread::spawn(move || match co.resume(thread::current().id()) {
+            ResumeResult::Return((received, ran_on)) => {
+                assert_eq!(received, thread::current().id());
+                (received, ran_on)
+            }
+            _ => panic!("coroutine should return")
+        }).join().unwrap();
+        assert_eq!(received, completed_on);
+        assert_ne!(started_on, completed_on);
+        assert_ne!(created_on, completed_on);
+    }
+
+    #[test]
+    fn unwind_safe_coroutine_is_caught_without_assertion() {
+        use std::panic::catch_unwind;
+
+        let mut co = UnwindSafeCoroutine::<u32, (), u32>::new(|chan, first| {
+            chan.suspend(first);
+            panic!("failed")
+        });
+        let result = catch_unwind(move || {
+            assert!(matches!(co.resume(1), ResumeResult::Yield(1)));
+            co.resume(2)
+        });
+        assert!(result.is_err());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn new_try_returns_panic_as_error() {
+        let mut co = Coroutine::new_try(|chan, limit: i32| {
+            let value = chan.suspend(());
+            if value > limit {
+                panic!("{} exceeds {}", value, limit);
+            }
+            value
+        });
+        assert!(matches!(co.resume(3), ResumeResult::Yield(())));
+        match co.resume(5) {
+            ResumeResult::Return(Err(CoroutinePanic { message, payload })) => {
+                assert_eq!(message.as_deref(), Some("5 exceeds 3"));
+                assert!(payload.is::<String>());
+            }
+            _ => panic!("coroutine should return the panic")
+        }
+        assert!(co.is_completed());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn new_try_still_unwinds_on_drop() {
+        let drops = Rc::new(Cell::new(0));
+        let counter = drops.clone();
+        let mut co = Coroutine::<(), Result<(), CoroutinePanic>, ()>::new_try(move |chan, _| {
+            let _guard = DropCounter(counter);
+            chan.suspend(());
+        });
+        co.resume(());
+        drop(co);
+        assert_eq!(drops.get(), 1);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn resume_or_finished_reports_completion() {
+        let mut co = Coroutine::<i32, i32, ()>::new(|chan, _| {
+            chan.suspend(1);
+            2
+        });
+        assert!(matches!(co.resume_or_finished(()), Some(ResumeResult::Yield(1))));
+        assert!(matches!(co.resume_or_finished(()), Some(ResumeResult::Return(2))));
+        assert!(co.resume_or_finished(()).is_none());
+        assert!(co.resume_or_finished(()).is_none());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn resume_checked_reports_completion() {
+        let mut co = Coroutine::<(), i32, ()>::new(|_, _| 1);
+        assert!(matches!(co.resume_checked(()), Ok(ResumeResult::Return(1))));
+        assert_eq!(co.resume_checked(()).unwrap_err(), CoroutineError::Completed);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn resume_checked_reports_panic_message() {
+        let mut co = Coroutine::<(), (), i32>::new(|_, i| panic!("failed with {}", i));
+        assert_eq!(co.resume_checked(3).unwrap_err(), CoroutineError::Panicked(Some("failed with 3".to_string())));
+        assert!(co.is_completed());
+    }
+
+    /// Panic payload which panics again when it is dropped
+    struct PanicOnDrop;
+
+    impl Drop for PanicOnDrop {
+        fn drop(&mut self) {
+            panic!("payload dropped")
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn payload_panicking_on_drop_is_handed_over() {
+        let mut co = Coroutine::<(), (), ()>::new(|_, _| std::panic::resume_unwind(Box::new(PanicOnDrop)));
+        assert_eq!(co.resume_checked(()), Err(CoroutineError::Panicked(None)));
+        let payload = co.take_panic().unwrap();
+        assert!(std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| drop(payload))).is_err());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn unwinding_out_of_context_bottom_aborts_with_message() {
+        const CHILD: &str = "RUSTERATORS_ABORT_CHILD";
+        if std::env::var_os(CHILD).is_some() {
+            let _guard = AbortOnUnwind("testing the guard");
+            panic!("escaping");
+        }
+        // the abort takes down the process, so the test runs itself again in a child process
+        let output = std::process::Command::new(std::env::current_exe().unwrap())
+            .args(["--exact", "coroutines::tests::unwinding_out_of_context_bottom_aborts_with_message", "--nocapture", "--test-threads=1"])
+            .env(CHILD, "1")
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("panicked while testing the guard at the bottom of a coroutine context"));
+    }
+
+    #[cfg(feature = "debug-stack-poison")]
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn completed_stack_is_poisoned() {
+        let mut co = Coroutine::<usize, (), ()>::new(|chan, _| {
+            let local = [0x11u8; 64];
+            chan.suspend(std::hint::black_box(&local).as_ptr() as usize);
+        }).keep_stack(true);
+        let escaped = co.resume(()).unwrap_yield() as *const u8;
+        assert_eq!(unsafe { escaped.read_volatile() }, 0x11);
+        co.resume(()).unwrap_return();
+        // the completed coroutine has kept its stack, which has been overwritten
+        let poisoned = (0..64).all(|i| unsafe { escaped.add(i).read_volatile() } == crate::stack::STACK_POISON);
+        assert!(poisoned);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn large_return_does_not_inflate_yield_message() {
+        use std::mem::size_of;
+        use super::SuspenseType;
+        // the batch variant is the largest one left, a word sized yield still fits next to its discriminant
+        assert!(size_of::<SuspenseType<u32, [u8; 256]>>() <= size_of::<Vec<u32>>() + size_of::<usize>());
+        assert_eq!(size_of::<SuspenseType<u32, [u8; 256]>>(), size_of::<SuspenseType<u32, ()>>());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    #[should_panic(expected = "re-entrant resume of coroutine 'reentrant'")]
+    fn reentrant_resume_panics() {
+        let target: Rc<Cell<*mut Coroutine<'static, (), (), ()>>> = Rc::new(Cell::new(std::ptr::null_mut()));
+        let inner = target.clone();
+        let mut co = Coroutine::new(move |_, _| {
+            // a callback reaching the coroutine while it runs, e.g. by a pointer handed to foreign code
+            unsafe { (*inner.get()).resume(()) };
+        }).with_name("reentrant");
+        // both resumes go through the same raw pointer, so only the code under test borrows the coroutine
+        target.set(&mut co);
+        unsafe { (*target.get()).resume(()) };
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn raw_parts_round_trip() {
+        let co = Coroutine::<i32, i32, i32>::new(|chan, first| {
+            let second = chan.suspend(first + 1);
+            first + second
+        });
+        let mut co = match co.into_raw_parts() {
+            Err(co) => co,
+            Ok(_) => panic!("coroutine has not been started yet"),
+        };
+        assert_eq!(co.resume(1), ResumeResult::Yield(2));
+        let (transfer, state, stack) = co.into_raw_parts().ok().expect("suspended coroutine");
+        let mut co = unsafe { Coroutine::<i32, i32, i32>::from_raw_parts(transfer, state, stack) };
+        assert_eq!(co.resume(5), ResumeResult::Return(6));
+        assert!(co.into_raw_parts().is_err());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn forget_skips_unwinding() {
+        let drops = Rc::new(Cell::new(0));
+        let counter = drops.clone();
+        let mut co = Coroutine::<(), (), ()>::new(move |chan, _| {
+            let _guard = DropCounter(counter);
+            chan.suspend(());
+        });
+        co.resume(());
+        co.forget();
+        assert_eq!(drops.get(), 0);
+
+        let counter = drops.clone();
+        let mut co = Coroutine::<(), (), ()>::new(move |chan, _| {
+            let _guard = DropCounter(counter);
+            chan.suspend(());
+        }).leak_on_drop(true);
+        co.resume(());
+        drop(co);
+        assert_eq!(drops.get(), 0);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn stack_range_contains_locals() {
+        let mut co = Coroutine::<usize, (), ()>::new_with_factory(StackFactory::of_size(64 * 1024), |chan, _| {
+            let local = 0u8;
+            chan.suspend(&local as *const u8 as usize);
+        });
+        assert_eq!(co.stack_range(), None);
+        let address = co.resume(()).unwrap_yield();
+        assert!(co.stack_range().unwrap().contains(&address));
+        assert!(co.stack_size().unwrap() >= 64 * 1024);
+        co.resume(());
+        assert_eq!(co.stack_size(), None);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn watcher_reports_completion_kind() {
+        let mut co = Coroutine::<(), (), ()>::new(|_, _| panic!("failed"));
+        let watcher = co.watcher();
+        assert_eq!(watcher.completion_kind(), None);
+        assert!(co.resume_checked(()).is_err());
+        assert_eq!(watcher.completion_kind(), Some(CompletionKind::Panicked));
+        drop(co);
+        assert_eq!(watcher.completion_kind(), Some(CompletionKind::Panicked));
+
+        let co = Coroutine::<(), (), ()>::new(|_, _| ());
+        let watcher = co.watcher();
+        drop(co);
+        assert_eq!(watcher.completion_kind(), Some(CompletionKind::Dropped));
+
+        let mut co = Coroutine::<(), (), ()>::new(|_, _| ());
+        co.resume(());
+        assert_eq!(co.watcher().completion_kind(), Some(CompletionKind::Returned));
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn watcher_is_polled_from_other_thread() {
+        use std::sync::mpsc;
+        use std::thread;
+
+        let mut co = Coroutine::<usize, (), ()>::new(|chan, _| (0..100).for_each(|i| { chan.suspend(i); }));
+        let watcher = co.watcher();
+        let (started, wait) = mpsc::channel();
+        let poller = thread::spawn(move || {
+            started.send(watcher.is_completed()).unwrap();
+            while !watcher.is_completed() {
+                thread::yield_now();
+            }
+            watcher.completion_kind()
+        });
+        assert!(!wait.recv().unwrap());
+        while co.resume(()).is_yield() {}
+        assert_eq!(poller.join().unwrap(), Some(CompletionKind::Returned));
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn new_with_initial_starts_with_bundled_value() {
+        let mut co = Coroutine::<i32, i32, i32>::new_with_initial(10, |chan, mut sum| {
+            for _ in 0..2 {
+                sum += chan.suspend(sum);
+            }
+            sum
+        });
+        assert_eq!(co.resume(1), ResumeResult::Yield(11));
+        assert_eq!(co.resume(2), ResumeResult::Return(13));
+        assert_eq!(co.stats().resumes, 2);
+        assert_eq!(co.stats().yields, 1);
+
+        let mut returning = Coroutine::<(), i32, i32>::new_with_initial(10, |_, initial| initial);
+        assert_eq!(returning.resume(1), ResumeResult::Return(10));
+        assert_eq!(returning.stats().resumes, 1);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn invoke_returns_first_resume_result() {
+        let (mut co, first) = Coroutine::<i32, i32, i32>::invoke(|chan, initial| chan.suspend(initial * 2) + 1, 4);
+        assert_eq!(first, ResumeResult::Yield(8));
+        assert_eq!(co.resume(10), ResumeResult::Return(11));
+
+        let (co, first) = Coroutine::<(), &str, ()>::invoke(|_, _| "done", ());
+        assert_eq!(first, ResumeResult::Return("done"));
+        assert!(co.is_completed());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn unprotected_stack_runs_coroutine() {
+        let mut co = Coroutine::<usize, (), ()>::new_with_factory(StackFactory::unprotected(32 * 1024), |chan, _| {
+            let local = 0u8;
+            chan.suspend(&local as *const u8 as usize);
+        });
+        let address = co.resume(()).unwrap_yield();
+        assert!(co.stack_range().unwrap().contains(&address));
+        co.resume(());
+        assert!(co.is_completed());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn new_in_reuses_stack_of_completed_coroutine() {
+        let mut first = Coroutine::<usize, (), ()>::new_with_factory(StackFactory::of_size(32 * 1024), |chan, _| {
+            chan.suspend(1);
+        }).keep_stack(true);
+        first.resume(());
+        let range = first.stack_range();
+        first.resume(());
+        assert!(first.is_completed());
+        let stack = first.into_stack().unwrap();
+
+        let mut second = Coroutine::<usize, usize, usize>::new_in(stack, |chan, _| chan.suspend(2) + 1);
+        assert_eq!(second.resume(0), ResumeResult::Yield(2));
+        assert_eq!(second.stack_range(), range);
+        assert_eq!(second.resume(3), ResumeResult::Return(4));
+        assert!(Coroutine::<(), (), ()>::new(|_, _| ()).into_stack().is_none());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn completion_info_reports_variant() {
+        let mut co = Coroutine::<(), i32, ()>::new(|_, _| 1);
+        assert!(co.completion_info().is_none());
+        co.resume(());
+        let info = co.completion_info().unwrap();
+        assert_eq!(info.variant, CompleteVariant::Return);
+        assert!(info.backtrace.is_none());
+    }
+
+    #[cfg(feature = "backtrace")]
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn completion_info_carries_panic_site_backtrace() {
+        use std::backtrace::BacktraceStatus;
+
+        #[inline(never)]
+        fn failing_step() {
+            panic!("step failed")
+        }
+        let mut co = Coroutine::<(), (), ()>::new(|_, _| failing_step());
+        assert!(co.resume_checked(()).is_err());
+        let info = co.completion_info().unwrap();
+        assert_eq!(info.variant, CompleteVariant::Unwind);
+        let backtrace = info.backtrace.unwrap();
+        if backtrace.status() == BacktraceStatus::Captured {
+            assert!(backtrace.to_string().contains("failing_step"));
+        }
+    }
+
+    #[cfg(feature = "backtrace")]
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn unrelated_panic_leaves_no_backtrace_behind() {
+        use std::panic::{catch_unwind, resume_unwind};
+
+        #[inline(never)]
+        fn unrelated_failure() {
+            panic!("unrelated")
+        }
+        // starts the coroutine once, so the hook capturing backtraces is installed
+        Coroutine::<(), (), ()>::new(|_, _| ()).resume(());
+        assert!(catch_unwind(unrelated_failure).is_err());
+        // resuming a panic doesn't run the hook, so the backtrace is captured at the bottom of the coroutine context instead
+        let mut co = Coroutine::<(), (), ()>::new(|_, _| resume_unwind(Box::new("resumed")));
+        assert!(co.resume_checked(()).is_err());
+        let backtrace = co.completion_info().unwrap().backtrace.unwrap().to_string();
+        assert!(!backtrace.contains("unrelated_failure"));
+    }
+
+    fn panic_message_of<R>(co: &mut Coroutine<(), R, ()>) -> String {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let payload = catch_unwind(AssertUnwindSafe(|| co.resume(()))).err().unwrap();
+        // the backtrace feature appends the backtrace of the panic site in case RUST_BACKTRACE is set
+        let message = payload.downcast_ref::<String>().unwrap();
+        message.split("\ncoroutine backtrace:").next().unwrap().to_string()
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn panic_message_includes_name_and_literal() {
+        let mut co = Coroutine::<(), (), ()>::new(|_, _| panic!("unexpected EOF")).with_name("tokenizer");
+        assert_eq!(panic_message_of(&mut co), "coroutine 'tokenizer' panicked: unexpected EOF");
+        assert_eq!(*co.take_panic().unwrap().downcast_ref::<&str>().unwrap(), "unexpected EOF");
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn panic_message_includes_formatted_payload() {
+        let line = 3;
+        let mut co = Coroutine::<(), (), ()>::new(move |_, _| panic!("unexpected EOF at line {}", line)).with_name("tokenizer");
+        assert_eq!(panic_message_of(&mut co), "coroutine 'tokenizer' panicked: unexpected EOF at line 3");
+        assert_eq!(co.take_panic().unwrap().downcast_ref::<String>().unwrap(), "unexpected EOF at line 3");
+        assert!(co.take_panic().is_none());
+
+        let mut unnamed = Coroutine::<(), (), ()>::new(|_, _| panic!("unexpected EOF"));
+        assert_eq!(panic_message_of(&mut unnamed), "Coroutine panicked: unexpected EOF");
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn scoped_generators_borrow_local_data() {
+        let text = String::from("a bc def");
+        let mut offsets = vec![0];
+        let lengths: Vec<usize> = scope(|s| {
+            let mut receiving = s.receiving_generator(|chan, first: usize| {
+                let mut offset = first;
+                for word in text.split(' ') {
+                    offset = chan.yield_val(offset + word.len());
+                }
+            });
+            let mut offset = 0;
+            while let Some(next) = receiving.resume(offset) {
+                offsets.push(next + 1);
+                offset = next + 1;
+            }
+            s.generator(|chan| chan.yield_all(text.split(' ').map(str::len))).collect()
+        });
+        assert_eq!(lengths, vec![1, 2, 3]);
+        assert_eq!(offsets, vec![0, 2, 5, 9]);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn lending_coroutine_borrows_for_single_resume() {
+        let mut co = LendingCoroutine::<usize, usize, Vec<usize>>::new(|chan| {
+            let mut total = 0;
+            let mut values = chan.current();
+            loop {
+                let len = values.len();
+                values.push(len);
+                total += values.len();
+                if total >= 5 {
+                    return total;
+                }
+                values = chan.suspend(total);
+            }
+        });
+        let mut values = Vec::new();
+        assert!(matches!(co.resume(&mut values), ResumeResult::Yield(1)));
+        values.push(10);
+        assert!(matches!(co.resume(&mut values), ResumeResult::Yield(4)));
+        assert_eq!(values, vec![0, 10, 2]);
+        let mut others = Vec::new();
+        assert!(matches!(co.resume(&mut others), ResumeResult::Return(5)));
+        assert_eq!(others, vec![0]);
+        assert!(co.is_completed());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn coroutine_carries_name() {
+        let co = Coroutine::<(), (), ()>::new(|_, _| ());
+        assert_eq!(co.name(), None);
+        assert_eq!(co.with_name("worker").name(), Some("worker"));
+    }
+
+    #[inline(never)]
+    fn use_large_stack_array() {
+        let buffer = [1u8; 64 * 1024];
+        black_box(&buffer);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn stack_high_water_mark_covers_stack_allocated_array() {
+        let mut co = Coroutine::new_with_factory(StackFactory::of_size_measured(256 * 1024), |chan, _: ()| {
+            chan.suspend(());
+            use_large_stack_array();
+        });
+        co.resume(());
+        let before = co.stack_high_water_mark().unwrap();
+        assert!(before < 64 * 1024);
+        co.resume(());
+        assert!(co.is_completed());
+        let mark = co.stack_high_water_mark().unwrap();
+        assert!((64 * 1024..256 * 1024).contains(&mark));
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn stack_high_water_mark_requires_measuring_factory() {
+        let mut co = Coroutine::<(), (), ()>::new(|_, _| ());
+        assert!(co.stack_high_water_mark().is_none());
+        co.resume(());
+        assert!(co.stack_high_water_mark().is_none());
+    }
+
+    #[cfg(feature = "tracing")]
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn tracing_reports_every_switch() {
+        use std::cell::RefCell;
+        use std::fmt::Debug;
+        use std::sync::{Arc, Mutex};
+
+        use tracing::{Event, Id, Metadata, Subscriber};
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Record};
+
+        /// Records events as (span fields, event label) where the label is the kind of a coroutine switch or the message
+        #[derive(Default)]
+        struct Recorder {
+            spans: Mutex<Vec<String>>,
+            entered: Mutex<Vec<usize>>,
+            events: Arc<Mutex<Vec<(String, String)>>>,
+        }
+
+        #[derive(Default)]
+        struct Fields(String, Option<String>);
+
+        impl Visit for Fields {
+            fn record_str(&mut self, field: &Field, value: &str) {
+                if field.name() == "kind" {
+                    self.1 = Some(value.to_string());
+                }
+                self.0 += &format!("{}={} ", field.name(), value);
+            }
+            fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+                if field.name() == "message" && self.1.is_none() {
+                    self.1 = Some(format!("{:?}", value));
+                }
+                self.0 += &format!("{}={:?} ", field.name(), value);
+            }
+        }
+
+        impl Subscriber for Recorder {
+            fn enabled(&self, _: &Metadata) -> bool { true }
+            fn new_span(&self, span: &Attributes) -> Id {
+                let mut fields = Fields::default();
+                span.record(&mut fields);
+                let mut spans = self.spans.lock().unwrap();
+                spans.push(fields.0.trim_end().to_string());
+                Id::from_u64(spans.len() as u64)
+            }
+            fn record(&self, _: &Id, _: &Record) {}
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+            fn event(&self, event: &Event) {
+                let mut fields = Fields::default();
+                event.record(&mut fields);
+                let span = match self.entered.lock().unwrap().last() {
+                    Some(id) => self.spans.lock().unwrap()[id - 1].clone(),
+                    None => String::new(),
+                };
+                self.events.lock().unwrap().push((span, fields.1.unwrap_or_default()));
+            }
+            fn enter(&self, span: &Id) {
+                self.entered.lock().unwrap().push(span.into_u64() as usize);
+            }
+            fn exit(&self, _: &Id) {
+                self.entered.lock().unwrap().pop();
+            }
+        }
+
+        let recorder = Recorder::default();
+        let events = recorder.events.clone();
+        let results = RefCell::new(Vec::new());
+        tracing::subscriber::with_default(recorder, || {
+            let mut co = Coroutine::new(|chan, _| {
+                chan.suspend(1);
+                chan.suspend(2);
+            }).with_name("two-yield");
+            while !co.is_completed() {
+                results.borrow_mut().push(co.resume(()));
+            }
+        });
+        assert_eq!(results.borrow().len(), 3);
+
+        let span = |switch| format!("name=two-yield switch={} kind=resume", switch);
+        let expected = vec![
+            (span(1), "yield".to_string()), (span(1), "coroutine suspended".to_string()),
+            (span(2), "yield".to_string()), (span(2), "coroutine suspended".to_string()),
+            (span(3), "return".to_string()), (span(3), "coroutine suspended".to_string()),
+        ];
+        assert_eq!(*events.lock().unwrap(), expected);
+    }
+
+    #[cfg(all(unix, feature = "stack-overflow-recovery"))]
+    #[inline(never)]
+    fn recurse_deeply(depth: usize) -> usize {
+        let frame = [depth as u8; 512];
+        black_box(&frame);
+        if depth == usize::MAX {
+            return 0;
+        }
+        recurse_deeply(depth + 1) + frame[0] as usize
+    }
+
+    #[cfg(all(unix, feature = "stack-overflow-recovery"))]
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn stack_overflow_completes_coroutine() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let mut co = Coroutine::<(), usize, ()>::new_with_factory(StackFactory::of_size(64 * 1024), |_, _| recurse_deeply(0));
+        let payload = catch_unwind(AssertUnwindSafe(|| co.resume(()))).unwrap_err();
+        assert_eq!(payload.downcast_ref::<String>().unwrap(), "Coroutine stack overflowed");
+        assert!(co.is_completed());
+
+        let mut next = Coroutine::<(), i32, ()>::new(|_, _| 1);
+        assert!(matches!(next.resume(()), ResumeResult::Return(1)));
+    }
+
+    #[cfg(all(unix, feature = "stack-overflow-recovery"))]
+    #[inline(never)]
+    fn recurse_with_large_frames(depth: usize) -> usize {
+        // each frame spans more than a page, so a single guard page may be skipped
+        let frame = [depth as u8; 3 * 4096];
+        black_box(&frame);
+        if depth == usize::MAX {
+            return 0;
+        }
+        recurse_with_large_frames(depth + 1) + frame[0] as usize
+    }
+
+    #[cfg(all(unix, feature = "stack-overflow-recovery"))]
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn stack_overflow_with_large_frames_hits_guard_region() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let mut co = Coroutine::<(), usize, ()>::new_with_factory(StackFactory::of_size_with_guard(64 * 1024, 4), |_, _| recurse_with_large_frames(0));
+        let payload = catch_unwind(AssertUnwindSafe(|| co.resume(()))).unwrap_err();
+        assert_eq!(payload.downcast_ref::<String>().unwrap(), "Coroutine stack overflowed");
+        assert_eq!(co.watcher().completion_kind(), Some(CompletionKind::StackOverflow));
+    }
+
+    #[cfg(feature = "segmented-stacks")]
+    #[inline(never)]
+    fn recurse_kib(depth: usize) -> usize {
+        let frame = [depth as u8; 1024];
+        black_box(&frame);
+        if depth == 0 {
+            return 0;
+        }
+        recurse_kib(depth - 1) + 1
+    }
+
+    #[cfg(feature = "segmented-stacks")]
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn ensure_stack_recurses_beyond_initial_stack() {
+        let mut co = Coroutine::<usize, usize, ()>::new_with_factory(StackFactory::of_size(32 * 1024), |chan, _| {
+            let small = chan.ensure_stack(1024, |chan| {
+                let local = 0u8;
+                chan.suspend(black_box(&local) as *const u8 as usize);
+                recurse_kib(4)
+            });
+            let deep = chan.ensure_stack(512 * 1024, |chan| {
+                let local = 0u8;
+                chan.suspend(black_box(&local) as *const u8 as usize);
+                recurse_kib(300)
+            });
+            small + deep
+        });
+        let in_place = co.resume(()).unwrap_yield();
+        assert!(co.stack_range().unwrap().contains(&in_place));
+        let on_segment = co.resume(()).unwrap_yield();
+        assert!(!co.stack_range().unwrap().contains(&on_segment));
+        assert_eq!(co.resume(()), ResumeResult::Return(304));
+    }
+
+    #[cfg(feature = "segmented-stacks")]
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn ensure_stack_unwinds_segment_on_drop() {
+        let dropped = Rc::new(Cell::new(0));
+        let counter = dropped.clone();
+        let mut co = Coroutine::<(), (), ()>::new_with_factory(StackFactory::of_size(32 * 1024), move |chan, _| {
+            let _outer = DropCounter(counter.clone());
+            chan.ensure_stack(256 * 1024, |chan| {
+                let _inner = DropCounter(counter);
+                chan.suspend(())
+            })
+        });
+        co.resume(());
+        assert_eq!(dropped.get(), 0);
+        drop(co);
+        assert_eq!(dropped.get(), 2);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn connected_coroutines_play_ping_pong() {
+        let player = Coroutine::new(|chan, mut ball: u32| {
+            let mut rounds = 0;
+            while rounds < 3 {
+                rounds += 1;
+                ball = chan.suspend(ball + 1);
+            }
+            (rounds, ball)
+        });
+        let wall = Coroutine::<u32, (), u32>::new(|chan, mut ball| loop {
+            ball = chan.suspend(ball * 10);
+        });
+        match connect(player, wall, 1) {
+            DuplexOutcome::AReturned(result, mut wall) => {
+                assert_eq!(result, (3, 11110));
+                assert!(!wall.is_completed());
+                assert_eq!(wall.resume(2), ResumeResult::Yield(20));
+            }
+            DuplexOutcome::BReturned(..) => panic!("endless responder returned"),
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn connected_responder_returns_first() {
+        let client = Coroutine::<&str, usize, usize>::new(|chan, _| loop {
+            chan.suspend("request");
+        });
+        let server = Coroutine::new(|chan, mut request: &str| {
+            let mut served = Vec::new();
+            while served.len() < 2 {
+                served.push(request);
+                request = chan.suspend(request.len());
+            }
+            served
+        });
+        match connect(client, server, "hello") {
+            DuplexOutcome::BReturned(served, client) => {
+                assert_eq!(served, vec!["hello", "request"]);
+                assert_eq!(client.stats().resumes, 2);
+            }
+            DuplexOutcome::AReturned(..) => panic!("endless client returned"),
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    #[should_panic(expected = "side b of duplex pair failed: coroutine 'server' panicked: bad request")]
+    fn connect_tells_side_that_panicked() {
+        let client = Coroutine::<u32, (), u32>::new(|chan, mut v| loop {
+            v = chan.suspend(v);
+        });
+        let server = Coroutine::<u32, (), u32>::new(|chan, v| {
+            chan.suspend(v);
+            panic!("bad request")
+        }).with_name("server");
+        connect(client, server, 1);
+    }
 }
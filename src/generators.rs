@@ -1,4 +1,24 @@
-use crate::coroutines::{Coroutine, CoroutineChannel, ResumeResult};
+pub mod gen_utils;
+mod adapters;
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::iter::{FromIterator, FusedIterator};
+use std::ops::{ControlFlow, Range};
+use std::panic::{AssertUnwindSafe, catch_unwind, RefUnwindSafe, UnwindSafe};
+use std::sync::mpsc::{self, Receiver};
+use std::thread::{self, JoinHandle};
+
+use crate::coroutines::{panic_message, CancellationToken, CompletionKind, DelegateStack, CompletionWatcher, Coroutine, CoroutineChannel, CoroutineError, CoroutinePanic, ResumeResult, StackFactory, Stats};
+use crate::io::{GeneratorReader, GeneratorWriter};
+use crate::transfer::RingExchange;
+// generators reading and writing through std::io are reachable next to the other generators too
+pub use crate::io;
+#[cfg(feature = "futures")]
+pub use crate::stream::StreamAdapter;
+#[cfg(feature = "rayon")]
+pub use crate::parallel::ParYields;
+pub use self::adapters::{ChainGen, Chunks, EnumerateYields, FilterYield, FlattenGen, MapYield, Piped, RoundRobin, ScanYields, TakeYields, TakeYieldsWhile, TeeHandle, Zipped, pipe, round_robin, zip};
 
 /// General Closure signature that is used by full fletched Generator
 pub type BoostedGenFn<Yield, Return, Receive> = dyn FnOnce(&mut BoostedGeneratorChannel<Yield, Return, Receive>, Receive) -> Return;
@@ -15,8 +35,24 @@ pub trait Generator<'a>{
     /// Resumes or starts execution of this generators callstack sending [send] to it
     /// Returns Option containing a value of type Yield in case generator yields a value and suspends or None of generator completes
     /// This method may not be called after it returned None once or behaviour is undefined(most likely this would cause a panic)
+    /// The generators of this crate however keep returning None in that case
     /// [has_completed] will return true iif resume has returned None once
     fn resume(&mut self,send:Self::Receive) -> Option<Self::Yield>;
+    /// Like [resume] but returning why a generator failed while resumed instead of passing its panic on
+    /// By default the panic is caught and reported by its message, the generators of this crate report the failure of their coroutine without unwinding
+    fn resume_caught(&mut self, send: Self::Receive) -> Result<Option<Self::Yield>, GeneratorError> {
+        catch_unwind(AssertUnwindSafe(|| self.resume(send))).map_err(|payload| GeneratorError::Panicked { message: panic_message(&payload) })
+    }
+    /// Asks the generating function to finish early, which it observes from the next resume on (see [BoostedGeneratorChannel::is_cancelled])
+    /// Generators which can't be cancelled ignore the request, like the ones of this crate do once they have completed
+    fn cancel(&self) {}
+    /// Resumes the generator on behalf of a generator yielding from it (see [GeneratorChannel::yield_from]), by default like [resume]
+    /// The generators of this crate push the generators they yield from themselves to [delegates] instead of resuming them, so the outermost generator resumes the innermost one directly
+    #[doc(hidden)]
+    fn resume_delegated(&mut self, send: Self::Receive, delegates: &mut DelegateStack<Self::Yield>) -> Option<Self::Yield> {
+        let _ = delegates;
+        self.resume(send)
+    }
 }
 
 /// A ResultingGenerator is a [Generator] with the additional ability to return a value indepent of the yielded data
@@ -25,52 +61,278 @@ pub trait ResultingGenerator<'a>:Generator<'a> {
     type Return:'static;
     /// Converts Generator into its resulting value whereby,
     /// Ok(r) means the generator has successfully generated a return value(which might be another Result as well)
-    /// Err(()) means that generator stack has been unwinded before it's execution completed (most likely due to a panic)
-    /// This methods panics if generator has not completed yet, i.e. [has_completed] returns false
-    fn result(self) -> Result<Self::Return,()>;
+    /// Err(e) tells why there is no return value: the generator stack has been unwinded before it's execution completed or the generator has not completed yet, i.e. [has_completed] returns false
+    fn result(self) -> Result<Self::Return, GeneratorError> where Self: Sized;
+    /// Like [result] but handing the generator back along with the error, so e.g. iteration can go on if it has not completed yet
+    // the generator is handed back on failure like by Rc::try_unwrap, boxing it would cost an allocation for nothing
+    #[allow(clippy::result_large_err)]
+    fn try_result(self) -> Result<Self::Return, (Self, GeneratorError)> where Self: Sized;
+    /// Resumes the generator until it completes dropping the values it yields and returns its result, values taken before are not yielded again
+    /// A panic of the generator ends draining and is returned like [result] reports it
+    fn drain(self) -> Result<Self::Return, GeneratorError> where Self: Sized + Generator<'a, Receive=()> {
+        self.drain_with(|_| ())
+    }
+    /// Like [drain] but passing each yielded value to [f]
+    fn drain_with(mut self, mut f: impl FnMut(Self::Yield)) -> Result<Self::Return, GeneratorError> where Self: Sized + Generator<'a, Receive=()> {
+        // only the resume is guarded, a panic of f is passed on
+        while let Ok(Some(val)) = self.resume_caught(()) {
+            f(val);
+        }
+        self.result()
+    }
+    /// Collects the yielded values not taken yet into [C] making use of the size hint of the generator and returns them along with the result
+    /// A panic of the generator ends collecting, the values collected so far are returned along with the error
+    fn collect_with_result<C: FromIterator<Self::Yield>>(mut self) -> (C, Result<Self::Return, GeneratorError>)
+        where Self: Sized + Generator<'a, Receive=()> + Iterator<Item=<Self as Generator<'a>>::Yield> {
+        let collected = UntilPanic(&mut self, false).collect();
+        (collected, self.result())
+    }
+}
+
+/// Reasons a [ResultingGenerator] has no return value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GeneratorError {
+    /// The generating function panicked, carries the panic message if the panic data has been a string
+    /// Other failures of the underlying coroutine, like a stack overflow, are reported as panic carrying the error message
+    Panicked { message: Option<String> },
+    /// The coroutine context has been unwinded without the generator requesting it
+    Dropped,
+    /// The generator is still resumeable
+    NotCompleted,
+    /// The return value has already been taken by [BoostedGenerator::take_result]
+    Taken,
+}
+
+/// Adapters available on all [Generator]s, which keep the adapted generator and thereby its result reachable unlike the ones of [Iterator]
+pub trait GeneratorExt<'a>: Generator<'a> + Sized {
+    /// Maps each yielded value by [f], the generator keeps running on its own callstack
+    fn map_yield<U: 'static, F: FnMut(Self::Yield) -> U>(self, f: F) -> MapYield<Self, F> {
+        MapYield::new(self, f)
+    }
+    /// Skips the yielded values [pred] rejects, resuming the generator again with a clone of the value sent by the resume that got them
+    fn filter_yield<P: FnMut(&Self::Yield) -> bool>(self, pred: P) -> FilterYield<Self, P> {
+        FilterYield::new(self, pred)
+    }
+    /// Yields at most [n] values of the generator, which is only cancelled and run to completion by [TakeYields::finish] to get the result
+    fn take_yields(self, n: usize) -> TakeYields<Self> {
+        TakeYields::new(self, n)
+    }
+    /// Yields the values of the generator until [pred] rejects one, the generator is only cancelled and run to completion by [TakeYieldsWhile::finish] to get the result
+    fn take_yields_while<P: FnMut(&Self::Yield) -> bool>(self, pred: P) -> TakeYieldsWhile<Self, P> {
+        TakeYieldsWhile::new(self, pred)
+    }
+    /// Yields the values of this generator and then the ones of [b], which is only resumed once this generator returned
+    fn chain_gen<B>(self, b: B) -> ChainGen<Self, Self::Return, B, impl FnOnce() -> B> where Self: ResultingGenerator<'a> {
+        self.chain_gen_with(move || b)
+    }
+    /// Like [chain_gen] but creating the second generator by [factory] once this generator returned, it isn't created at all if this one fails
+    fn chain_gen_with<B, F: FnOnce() -> B>(self, factory: F) -> ChainGen<Self, Self::Return, B, F> where Self: ResultingGenerator<'a> {
+        ChainGen::new(self, factory)
+    }
+    /// Yields the values of the generators this generator yields, each one is run to completion before this generator is resumed again
+    /// A panic of an inner generator is passed on as it is, [FlattenGen::last_failed] tells the position of the generator among the yielded ones
+    fn flatten_gen(self) -> FlattenGen<Self> where Self: Iterator, Self::Item: Iterator {
+        FlattenGen::new(self)
+    }
+    /// Pairs each yielded value with its position, starting at 0
+    fn enumerate_yields(self) -> EnumerateYields<Self> {
+        EnumerateYields::new(self)
+    }
+    /// Maps the yielded values by [f] with access to [state] like [Iterator::scan], f returning None ends the yields of the adapter
+    /// The rest of the generator is only run by [ScanYields::finish] to get the result
+    fn scan_yields<S, U: 'static, F: FnMut(&mut S, Self::Yield) -> Option<U>>(self, state: S, f: F) -> ScanYields<Self, S, F> {
+        ScanYields::new(self, state, f)
+    }
+    /// Collects the yielded values into batches of [n], the last one holding the rest is shorter
+    /// Panics if [n] is 0
+    fn chunks(self, n: usize) -> Chunks<Self> {
+        assert!(n > 0, "chunk size must not be 0");
+        Chunks::new(self, n)
+    }
+    /// Splits the yielded values into two handles each handing out all of them, the generator is only resumed when the leading handle needs a new value
+    /// The values the lagging handle has not taken yet are buffered without limit, see [TeeHandle::buffered_len]
+    fn tee(self) -> (TeeHandle<Self>, TeeHandle<Self>) where Self: Generator<'a, Receive=()> + Iterator<Item=<Self as Generator<'a>>::Yield>, Self::Yield: Clone {
+        TeeHandle::pair(self)
+    }
+    /// Turns the generator into a [futures_core::Stream] of its values, whose polls resume the generator right away
+    /// A panic of the generator ends the stream, see [StreamAdapter::into_result]
+    #[cfg(feature = "futures")]
+    fn into_stream(self) -> StreamAdapter<Self> where Self: Iterator<Item=Self::Yield> {
+        StreamAdapter::new(self)
+    }
+    /// Drives the generator on a thread of its own sending its values to the returned [Receiver], which buffers at most [capacity] of them
+    /// The result, or the panic of the generator, is reported by the [ResultHandle]. Once the receiver has been dropped, the generator is cancelled
+    /// and resumed a last time like by [try_fold_yields], so a generator observing the cancellation lets the thread exit. Generators created from closures can be sent by [SendGenerator]
+    fn spawn_onto_thread(self, capacity: usize) -> (Receiver<Self::Yield>, ResultHandle<Self::Return>)
+        where Self: ResultingGenerator<'a, Receive=()> + Send + 'static, Self::Yield: Send, Self::Return: Send {
+        let (sender, receiver) = mpsc::sync_channel(capacity);
+        (receiver, ResultHandle::spawn(self, move |val| sender.send(val).is_ok()))
+    }
+    /// Drives the generator on a thread of its own handing its values to a rayon [ParallelIterator](rayon::iter::ParallelIterator), buffering at most [buffer] of them
+    /// The result, or the panic of the generator, is reported by the [ResultHandle] once the values have been consumed. If the parallel iterator is dropped early,
+    /// the generator is cancelled and resumed a last time like by [try_fold_yields]. Generators created from closures can be sent by [SendGenerator]
+    #[cfg(feature = "rayon")]
+    fn par_bridge_buffered(self, buffer: usize) -> (ParYields<Self::Yield>, ResultHandle<Self::Return>)
+        where Self: ResultingGenerator<'a, Receive=()> + Send + 'static, Self::Yield: Send, Self::Return: Send {
+        ParYields::spawn(self, buffer)
+    }
+    /// Turns a generator yielding byte chunks into an [io::Read](std::io::Read), which resumes the generator once the current chunk has been read
+    /// A panic of the generator is read as error, the result is available by [GeneratorReader::into_result] once the reader has reached its end
+    fn into_reader(self) -> GeneratorReader<Self> where Self: Generator<'a, Yield=Vec<u8>, Receive=()> {
+        GeneratorReader::new(self)
+    }
+    /// Like [into_reader] for generators yielding single bytes, which are collected into chunks of 4096 so reading doesn't resume the generator for every byte
+    /// The bytes of a chunk not complete yet are lost if the generator panics
+    fn into_byte_reader(self) -> GeneratorReader<Chunks<Self>> where Self: Generator<'a, Yield=u8, Receive=()> + Iterator<Item=u8> {
+        self.chunks(4096).into_reader()
+    }
+    /// Turns a receiving generator into an [io::Write](std::io::Write), which resumes the generator with the written bytes in chunks of 8192
+    /// [GeneratorWriter::finish] ends the data by an empty chunk and returns the result of the generator
+    fn into_writer(self) -> GeneratorWriter<Self> where Self: Generator<'a, Receive=Vec<u8>> {
+        self.into_writer_with_chunk_size(8192)
+    }
+    /// Like [into_writer] but sending chunks of [chunk_size]
+    /// Panics if [chunk_size] is 0
+    fn into_writer_with_chunk_size(self, chunk_size: usize) -> GeneratorWriter<Self> where Self: Generator<'a, Receive=Vec<u8>> {
+        GeneratorWriter::new(self, chunk_size)
+    }
+    /// Folds the yielded values not taken yet into an accumulator by [f] like [Iterator::fold] and returns it along with the result of the generator
+    /// A panic of the generator ends folding and is returned like [ResultingGenerator::result] reports it
+    fn fold_yields<B, F: FnMut(B, Self::Yield) -> B>(mut self, init: B, mut f: F) -> (B, Result<Self::Return, GeneratorError>) where Self: ResultingGenerator<'a, Receive=()> {
+        let mut acc = init;
+        while let Ok(Some(val)) = self.resume_caught(()) {
+            acc = f(acc, val);
+        }
+        (acc, self.result())
+    }
+    /// Like [fold_yields] but ending early once [f] breaks, in which case the generator is cancelled (see [Generator::cancel]) and resumed a last time
+    /// A generator returning once it observes the cancellation still provides its result, otherwise [GeneratorError::NotCompleted] is returned along with the break value
+    fn try_fold_yields<B, F: FnMut(B, Self::Yield) -> ControlFlow<B, B>>(mut self, init: B, mut f: F) -> (ControlFlow<B, B>, Result<Self::Return, GeneratorError>) where Self: ResultingGenerator<'a, Receive=()> {
+        let mut acc = init;
+        while let Ok(Some(val)) = self.resume_caught(()) {
+            match f(acc, val) {
+                ControlFlow::Continue(next) => acc = next,
+                ControlFlow::Break(done) => {
+                    self.cancel();
+                    let _ = self.resume_caught(());
+                    return (ControlFlow::Break(done), self.result());
+                }
+            }
+        }
+        (ControlFlow::Continue(acc), self.result())
+    }
+}
+
+impl<'a, G: Generator<'a>> GeneratorExt<'a> for G {}
+
+/// Conversions of any [Iterator] into a generator, e.g. to pass it to [GeneratorChannel::yield_from] or keep it alongside generators created from closures
+pub trait IteratorExt: Iterator + Sized {
+    /// Wraps the iterator into an [IterGenerator], which advances it right in the resuming context without allocating a callstack
+    fn into_generator(self) -> IterGenerator<Self> {
+        IterGenerator::new(self)
+    }
+    /// Wraps the iterator into a [BoringGenerator], which advances it on a callstack of its own costing two context switches per value
+    /// Only worth it if a coroutine is needed in particular, e.g. to count the resumes by [BoringGenerator::stats]
+    fn into_coroutine_generator<'a>(self) -> BoringGenerator<'a, Self::Item> where Self: 'a, Self::Item: 'static {
+        BoringGenerator::from_iter(self)
+    }
 }
+
+impl<I: Iterator> IteratorExt for I {}
+
+/// Iterator over the values of a generator ending at a panic of the generator instead of passing it on, see [ResultingGenerator::collect_with_result]
+/// The second field tells whether the generator panicked
+struct UntilPanic<'g, G>(&'g mut G, bool);
+
 /// Marker trait stating that Generator does not receive meaningful values. Thus it can be iterated over (with resume(()) without further information.
 /// This was designed to genericly implement iterator (impl<G:IgnorantGenerator> Iterator for G like), but it turned out to be complicated. Such this trait is somewhat useless but kept for later ideas
 /// TODO find better design approach
 pub trait IgnorantGenerator<'a,Yield:'static>:Generator<'a,Yield=Yield,Receive=()>+Iterator<Item=Yield> {}
 
 /// [GeneratorChannel] is the interface that connects the generating closure with the invocation context and provides a method to yield a value as well was utility methods handling iterator related stuff
+/// Helpers taking `&mut impl GeneratorChannel` can be called by the closures of all generator kinds. The trait is object safe too,
+/// whereby the methods taking iterators or generators are only available on concrete channels
 pub trait GeneratorChannel<'a> {
     type Yield:'static;
     type Receive:'a;
     /// yields execution to waiting invocation context sending given [val]
     fn yield_val(&mut self,val:Self::Yield) -> Self::Receive;
 
+    /// yields all values of [batch] returning the value received after the last one
+    /// Implementations may transfer the whole batch with a single context switch, by default each value is yielded on its own
+    /// Panics if [batch] is empty
+    fn yield_batch(&mut self, batch: Vec<Self::Yield>) -> Self::Receive {
+        let mut received = None;
+        for val in batch {
+            received = Some(self.yield_val(val));
+        }
+        received.expect("tried to yield an empty batch")
+    }
+
     /// yields all values from given iterator
-    fn yield_all(&mut self, iter: impl Iterator<Item=Self::Yield>) {
+    fn yield_all(&mut self, iter: impl Iterator<Item=Self::Yield>) where Self: Sized {
         for i in iter {
             self.yield_val(i);
         }
     }
 
+    /// yields all values from given iterator collecting up to [N] of them in a [RingExchange] before they are handed over
+    /// Implementations may hand over the collected values with a single context switch, by default each value is yielded on its own
+    /// Values received meanwhile are dropped like for [yield_batch]
+    fn yield_all_buffered<const N: usize>(&mut self, iter: impl Iterator<Item=Self::Yield>) where Self: Sized {
+        self.yield_all(iter)
+    }
+
+    /// yields the values of the Ok items of given iterator until it returns an Err, which is returned without consuming the remaining items
+    fn yield_all_results<E>(&mut self, iter: impl Iterator<Item=Result<Self::Yield, E>>) -> Result<(), E> where Self: Sized {
+        for i in iter {
+            self.yield_val(i?);
+        }
+        Ok(())
+    }
+
     /// Flat yields a iterator of yield value iterators
-    fn yield_all_flat<I:Iterator<Item=Self::Yield>>(&mut self, iters:impl Iterator<Item=I>) {
+    fn yield_all_flat<I:Iterator<Item=Self::Yield>>(&mut self, iters:impl Iterator<Item=I>) where Self: Sized {
         for iter in iters {
             self.yield_all(iter);
         }
     }
     /// Iterates given non-receiving Generator [gen] and returns the result afterwards
-    fn yield_from<R:'static>(&mut self, mut gen: impl IgnorantGenerator<'a,Self::Yield>+ResultingGenerator<'a,Yield=Self::Yield,Return=R, Receive=()>) -> R {
-        self.yield_all(&mut gen);
-        gen.result().unwrap()
+    /// A panic of [gen] ends the iteration and is returned as [GeneratorError::Panicked], so the caller decides whether to propagate it
+    fn yield_from<R:'static>(&mut self, mut gen: impl IgnorantGenerator<'a,Self::Yield>+ResultingGenerator<'a,Yield=Self::Yield,Return=R, Receive=()>) -> Result<R, GeneratorError> where Self: Sized {
+        // only the resume of [gen] is guarded, unwinds of this context (e.g. because it is dropped) pass through yield_val
+        while let Ok(Some(val)) = gen.resume_caught(()) {
+            self.yield_val(val);
+        }
+        gen.result()
+    }
+    /// Like [yield_from] but for generators receiving values: [gen] is resumed with [first] and then with each value received for the values it yields
+    /// [first] is usually the value this generator has been resumed with last, e.g. its initial value
+    fn yield_from_receiving<R:'static>(&mut self, mut gen: impl ResultingGenerator<'a,Yield=Self::Yield,Return=R,Receive=Self::Receive>, first: Self::Receive) -> Result<R, GeneratorError> where Self: Sized {
+        let mut send = first;
+        while let Ok(Some(val)) = gen.resume_caught(send) {
+            send = self.yield_val(val);
+        }
+        gen.result()
     }
 }
 
 /// A simple Generator implementation only supporting non-receiving, ignorant generators by building a thin wrapper around Coroutines rearranging the user interface more or less
 /// Not that flexible but straight forward to use
-pub struct BoringGenerator<'a, Yield: 'static>(Coroutine<'a, Yield, (), ()>);
+/// Generators created by [new_resulting] keep the value their generating function returns, see [ResultingGenerator]
+pub struct BoringGenerator<'a, Yield: 'static, Return: 'static = ()>(Coroutine<'a, Yield, Return, ()>, Option<Return>, SizeHint);
+
+/// Generator handing out the values of an iterator without a coroutine, so no callstack is allocated and resuming costs no context switch
+/// Fits wherever a non-receiving generator is expected but there is nothing to suspend, e.g. to pass an iterator to [GeneratorChannel::yield_from]
+/// The second field tells whether the iterator has been exhausted
+pub struct IterGenerator<I>(I, bool);
 
 /// Channel implementation for [BoringGeneratorChannel]
-/// TODO check whether generating closure may receive something like "impl GeneratorChannel" to be a) more generic and b) makes it possible to hide concrete structs
-pub struct BoringGeneratorChannel<'a, 'b: 'a, Yield: 'static>(&'a mut CoroutineChannel<'b, Yield, (), ()>);
+/// Code shared with other generator kinds should take the channel as [GeneratorChannel] instead
+pub struct BoringGeneratorChannel<'a, 'b: 'a, Yield: 'static, Return: 'static = ()>(&'a mut CoroutineChannel<'b, Yield, Return, ()>);
 
 /// [Generator] implementation providing full-fledged resulting generators which might be ignorant but can also receive values
-pub struct BoostedGenerator<'a, Yield: 'static, Return: 'static, Receive: 'a>(BoostedGeneratorState<'a, Yield, Return, Receive>);
+pub struct BoostedGenerator<'a, Yield: 'static, Return: 'static, Receive: 'a>(BoostedGeneratorState<'a, Yield, Return, Receive>, SizeHint);
 
 /// Wrapper around CoroutineChannel passed to generator function/closure offering the possibility to yield values
 pub struct BoostedGeneratorChannel<'a, 'b: 'a, Yield: 'static, Return: 'static, Receive: 'a>(&'a mut CoroutineChannel<'b, Yield, Return, Receive>);
@@ -78,30 +340,159 @@ pub struct BoostedGeneratorChannel<'a, 'b: 'a, Yield: 'static, Return: 'static,
 /// Iterator over receiving generators containing a Closure as a source of input values
 pub struct BoostedGeneratorIterator<'a, Yield: 'static, Return: 'static, Receive: 'a, RF: FnMut() -> Receive>(BoostedGenerator<'a, Yield, Return, Receive>, RF);
 
+/// Generator allowing to look at the next value before taking it while the result stays reachable, see [BoostedGenerator::peekable]
+/// The second field buffers the outcome of the resume done by [peek] if any
+pub struct PeekableGenerator<G: Iterator>(G, Option<Option<G::Item>>);
+
+/// Generator which may be moved to other threads and resumed there like a [SendCoroutine](crate::coroutines::SendCoroutine), see [SendGenerator::new]
+pub struct SendGenerator<G>(G);
+
+/// Generator whose generating function is [UnwindSafe], so a panic of it can be caught without [AssertUnwindSafe]
+/// Created by [BoringGenerator::new_unwind_safe], [BoostedGenerator::new_unwind_safe] and [BoostedGenerator::new_receiving_unwind_safe]
+pub struct UnwindSafeGenerator<G>(G);
+
+/// Handle to the result of a generator driven by a thread of its own, see [GeneratorExt::spawn_onto_thread]
+pub struct ResultHandle<R>(JoinHandle<Result<R, GeneratorError>>);
+
+/// Bounds of the number of values a generator is still going to yield as announced by [BoostedGenerator::with_size_hint] or [BoringGenerator::with_size_hint]
+#[derive(Debug, Clone, Copy)]
+struct SizeHint(usize, Option<usize>);
+
 /// Holds the current execution state of the generator wrapping the invocation state of the Coroutine and buffering the extra return value
+/// along with the final statistics of the coroutine
+// a generator spends most of its life running, so boxing the coroutine would only cost an allocation
+#[allow(clippy::large_enum_variant)]
 enum BoostedGeneratorState<'a, Yield: 'static, Return: 'static, Receive: 'a> {
     RUNNING(Coroutine<'a, Yield, Return, Receive>),
-    COMPLETED(Return),
+    COMPLETED(Return, Stats),
+    /// Keeps the watcher of the coroutine, which still knows the way it completed
+    #[allow(clippy::upper_case_acronyms)]
+    FAILED(GeneratorError, Stats, CompletionWatcher),
+    /// Left behind by [BoostedGenerator::take_result]
+    #[allow(clippy::upper_case_acronyms)]
+    TAKEN(Stats),
 }
 
 impl<'a, Yield: 'static> BoringGenerator<'a, Yield> {
     /// Creates a new BoringGenerator using [gen_fn] as generating function yielding its return value (there it must return data of type Yield)
-    pub fn new_with_return<F>(gen_fn: F) -> Self where F: FnOnce(&mut BoringGeneratorChannel<Yield>) -> Yield + 'static {
+    #[deprecated(note = "the return value is yielded as extra element, use new_resulting to keep it apart as result")]
+    pub fn new_with_return<F>(gen_fn: F) -> Self where F: FnOnce(&mut BoringGeneratorChannel<Yield>) -> Yield + 'a {
         Self::new(|chan| {
             let ret_yield = gen_fn(chan);
             chan.yield_val(ret_yield);
         })
     }
     /// Creates a new BoringGenerator using [gen_fn] as generating function ignoring its return value
-    pub fn new<F>(gen_fn: F) -> Self where F: FnOnce(&mut BoringGeneratorChannel<Yield>) + 'static {
-        Self(Coroutine::new(|chan, _| {
+    pub fn new<F>(gen_fn: F) -> Self where F: FnOnce(&mut BoringGeneratorChannel<Yield>) + 'a {
+        Self::new_with_factory(StackFactory::default_stack(), gen_fn)
+    }
+    /// Like [new] but building the callstack by [stack_factory] on first resume
+    pub fn new_with_factory<F>(stack_factory: StackFactory, gen_fn: F) -> Self where F: FnOnce(&mut BoringGeneratorChannel<Yield>) + 'a {
+        Self::new_resulting_with_factory(stack_factory, gen_fn)
+    }
+    /// Like [new] but running on a callstack of [stack_size] bytes, see [StackFactory::of_size]
+    pub fn new_with_stack<F>(stack_size: usize, gen_fn: F) -> Self where F: FnOnce(&mut BoringGeneratorChannel<Yield>) + 'a {
+        Self::new_with_factory(StackFactory::of_size(stack_size), gen_fn)
+    }
+    /// Like [new] but for an unwind safe [gen_fn], which makes the generator unwind safe as well, see [UnwindSafeGenerator]
+    pub fn new_unwind_safe<F>(gen_fn: F) -> UnwindSafeGenerator<Self> where F: FnOnce(&mut BoringGeneratorChannel<Yield>) + UnwindSafe + 'a {
+        UnwindSafeGenerator(Self::new(gen_fn))
+    }
+    /// Creates a new BoringGenerator yielding the values of [iter] from its coroutine
+    /// [IterGenerator] hands out the values without allocating a callstack, so it should be preferred unless a BoringGenerator is needed in particular
+    /// There is no `From<I: Iterator>` conversion as for [IterGenerator], since a BoringGenerator is an iterator itself and the impl would conflict with `From<T> for T`
+    // FromIterator can't bound the iterator by the lifetime of the generator
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_iter(iter: impl Iterator<Item=Yield> + 'a) -> Self {
+        Self::new(move |chan| chan.yield_all(iter))
+    }
+}
+
+impl<'a, Yield: 'static, Return: 'static> BoringGenerator<'a, Yield, Return> {
+    /// Creates a new BoringGenerator using [gen_fn] as generating function whose return value is kept as result of the generator
+    pub fn new_resulting<F>(gen_fn: F) -> Self where F: FnOnce(&mut BoringGeneratorChannel<Yield, Return>) -> Return + 'a {
+        Self::new_resulting_with_factory(StackFactory::default_stack(), gen_fn)
+    }
+    /// Like [new_resulting] but building the callstack by [stack_factory] on first resume
+    pub fn new_resulting_with_factory<F>(stack_factory: StackFactory, gen_fn: F) -> Self where F: FnOnce(&mut BoringGeneratorChannel<Yield, Return>) -> Return + 'a {
+        Self(Coroutine::new_with_factory(stack_factory, |chan, _| {
             let mut gen_chan = BoringGeneratorChannel(chan);
-            gen_fn(&mut gen_chan);
-        }))
+            gen_fn(&mut gen_chan)
+        }), None, SizeHint::UNKNOWN)
+    }
+    /// Announces that the generator is going to yield at least [lower] and at most [upper] values, which its [Iterator::size_hint] passes on counting down as values are yielded
+    /// E.g. collecting the values then allocates only once, wrong bounds don't break anything but that
+    pub fn with_size_hint(mut self, lower: usize, upper: Option<usize>) -> Self {
+        self.2 = SizeHint(lower, upper);
+        self
+    }
+    /// Boxes the generator as trait object, e.g. to keep generators of different types in one collection
+    pub fn boxed(self) -> Box<dyn Generator<'a, Yield=Yield, Receive=()> + 'a> {
+        Box::new(self)
+    }
+    /// Wraps the generator into one allowing to [PeekableGenerator::peek] at the next value, see [BoostedGenerator::peekable]
+    pub fn peekable(self) -> PeekableGenerator<Self> {
+        PeekableGenerator(self, None)
+    }
+    /// Returns the statistics of the underlying coroutine, see [Coroutine::stats]
+    pub fn stats(&self) -> Stats {
+        self.0.stats()
+    }
+    /// Returns a handle observing the completion of the underlying coroutine, see [Coroutine::watcher]
+    pub fn watcher(&self) -> CompletionWatcher {
+        self.0.watcher()
+    }
+    /// Returns the size of the underlying coroutines callstack, see [Coroutine::stack_size]
+    pub fn stack_size(&self) -> Option<usize> {
+        self.0.stack_size()
+    }
+    /// Returns the address range of the underlying coroutines callstack, see [Coroutine::stack_range]
+    pub fn stack_range(&self) -> Option<Range<usize>> {
+        self.0.stack_range()
+    }
+    /// Like [Generator::resume] but reports failures of the underlying coroutine as [CoroutineError] instead of panicking
+    /// Resuming a completed BoringGenerator is not considered a failure but keeps returning None
+    pub fn resume_checked(&mut self, send: ()) -> Result<Option<Yield>, CoroutineError> {
+        if self.has_completed() {
+            return Ok(None);
+        }
+        match self.0.resume_checked(send)? {
+            ResumeResult::Yield(y) => {
+                self.2.yielded();
+                Ok(Some(y))
+            }
+            ResumeResult::Return(r) => {
+                self.1 = Some(r);
+                Ok(None)
+            }
+        }
+    }
+    /// Keeps the return value or counts the yielded one
+    fn handle(&mut self, resumed: ResumeResult<Yield, Return>) -> Option<Yield> {
+        match resumed {
+            ResumeResult::Yield(y) => {
+                self.2.yielded();
+                Some(y)
+            }
+            ResumeResult::Return(r) => {
+                self.1 = Some(r);
+                None
+            }
+        }
+    }
+    /// Determines why there is no return value, which is kept once the routine returned
+    fn error(&self) -> GeneratorError {
+        match self.0.watcher().completion_kind() {
+            None => GeneratorError::NotCompleted,
+            Some(CompletionKind::Panicked) => GeneratorError::Panicked { message: self.0.panic_message() },
+            Some(CompletionKind::StackOverflow) => GeneratorError::from(CoroutineError::StackOverflow),
+            Some(CompletionKind::Dropped) => GeneratorError::Dropped,
+            Some(CompletionKind::Returned) => GeneratorError::Taken,
+        }
     }
 }
 
-impl<'a, Yield: 'static> Generator<'a> for BoringGenerator<'a, Yield> {
+impl<'a, Yield: 'static, Return: 'static> Generator<'a> for BoringGenerator<'a, Yield, Return> {
     type Yield = Yield;
     type Receive = ();
 
@@ -110,125 +501,1789 @@ impl<'a, Yield: 'static> Generator<'a> for BoringGenerator<'a, Yield> {
     }
 
     fn resume(&mut self, send: Self::Receive) -> Option<Self::Yield> {
-        let resumed=if self.has_completed() {None} else {Some(self.0.resume(send))};
-        match resumed {
-            Some(ResumeResult::Yield(y)) => Some(y),
-            _ => None
+        let resumed = self.0.resume_or_finished(send)?;
+        self.handle(resumed)
+    }
+
+    fn resume_caught(&mut self, send: ()) -> Result<Option<Yield>, GeneratorError> {
+        self.resume_checked(send).map_err(GeneratorError::from)
+    }
+
+    fn cancel(&self) {
+        self.0.cancellation_token().cancel()
+    }
+
+    fn resume_delegated(&mut self, send: (), delegates: &mut DelegateStack<Yield>) -> Option<Yield> {
+        if self.0.is_completed() {
+            return None;
+        }
+        let resumed = self.0.resume_delegated(send, delegates);
+        self.handle(resumed)
+    }
+}
+
+impl<'a, Yield: 'static, Return: 'static> ResultingGenerator<'a> for BoringGenerator<'a, Yield, Return> {
+    type Return = Return;
+
+    fn result(mut self) -> Result<Return, GeneratorError> {
+        self.1.take().ok_or_else(|| self.error())
+    }
+
+    fn try_result(mut self) -> Result<Return, (Self, GeneratorError)> {
+        match self.1.take() {
+            Some(r) => Ok(r),
+            None => {
+                let error = self.error();
+                Err((self, error))
+            }
         }
     }
 }
 
 impl<'a, Yield:'static,G:Generator<'a,Yield=Yield,Receive=()>+Iterator<Item=Yield>> IgnorantGenerator<'a,Yield> for G {}
 
-impl<'a, Yield: 'static> Iterator for BoringGenerator<'a, Yield> {
+impl<'a, G: Generator<'a> + ?Sized> Generator<'a> for Box<G> {
+    type Yield = G::Yield;
+    type Receive = G::Receive;
+
+    fn has_completed(&self) -> bool {
+        (**self).has_completed()
+    }
+
+    fn resume(&mut self, send: Self::Receive) -> Option<Self::Yield> {
+        (**self).resume(send)
+    }
+
+    fn resume_caught(&mut self, send: Self::Receive) -> Result<Option<Self::Yield>, GeneratorError> {
+        (**self).resume_caught(send)
+    }
+
+    fn cancel(&self) {
+        (**self).cancel()
+    }
+
+    fn resume_delegated(&mut self, send: Self::Receive, delegates: &mut DelegateStack<Self::Yield>) -> Option<Self::Yield> {
+        (**self).resume_delegated(send, delegates)
+    }
+}
+
+impl<'a, G: Generator<'a> + ?Sized> Generator<'a> for &mut G {
+    type Yield = G::Yield;
+    type Receive = G::Receive;
+
+    fn has_completed(&self) -> bool {
+        (**self).has_completed()
+    }
+
+    fn resume(&mut self, send: Self::Receive) -> Option<Self::Yield> {
+        (**self).resume(send)
+    }
+
+    fn resume_caught(&mut self, send: Self::Receive) -> Result<Option<Self::Yield>, GeneratorError> {
+        (**self).resume_caught(send)
+    }
+
+    fn cancel(&self) {
+        (**self).cancel()
+    }
+
+    fn resume_delegated(&mut self, send: Self::Receive, delegates: &mut DelegateStack<Self::Yield>) -> Option<Self::Yield> {
+        (**self).resume_delegated(send, delegates)
+    }
+}
+
+impl<'a, Yield: 'static, Return: 'static> Iterator for BoringGenerator<'a, Yield, Return> {
     type Item = Yield;
 
     fn next(&mut self) -> Option<Yield> {
         self.resume(())
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.2.remaining(self.has_completed())
+    }
 }
 
-impl<'a, Y: 'static, Ret: 'static, Rec: 'a> BoostedGenerator<'a, Y, Ret, Rec> {
-    /// Factory function creating a new generator with input capabilities
-    pub fn new_receiving<F>(gen_fn: F) -> Self
-        where F: FnOnce(&mut BoostedGeneratorChannel<Y, Ret, Rec>, Rec) -> Ret + 'static {
-        Self(BoostedGeneratorState::RUNNING(Coroutine::new(|chan, i| {
-            let mut gen_chan = BoostedGeneratorChannel(chan);
-            gen_fn(&mut gen_chan,i)
-        })))
+/// Completed generators keep returning None instead of resuming the finished coroutine
+impl<'a, Yield: 'static, Return: 'static> FusedIterator for BoringGenerator<'a, Yield, Return> {}
+
+impl<I: Iterator> IterGenerator<I> {
+    /// Wraps [iter], which is advanced by each resume of the generator
+    pub fn new(iter: I) -> Self {
+        IterGenerator(iter, false)
     }
-    /// Creates a iterator for a non-ignorant Generator using the passed [source] closure as source of receive values
-    pub fn create_iter<RF:FnMut()->Rec>(self, source:RF) -> BoostedGeneratorIterator<'a,Y,Ret,Rec,RF> {
-        BoostedGeneratorIterator(self,source)
+    /// Hands back the wrapped iterator
+    pub fn into_inner(self) -> I {
+        self.0
     }
 }
 
-impl<'a, Y: 'static, Ret: 'static, Rec: 'a> ResultingGenerator<'a> for BoostedGenerator<'a, Y, Ret, Rec> {
-    type Return = Ret;
+impl<I: Iterator> From<I> for IterGenerator<I> {
+    fn from(iter: I) -> Self {
+        Self::new(iter)
+    }
+}
 
-    fn result(self) -> Result<Ret, ()> {
-        if self.has_completed() {
-            match self.0 {
-                BoostedGeneratorState::COMPLETED(r) => Ok(r),
-                _ => Err(())
-            }
-        } else {
-            panic!("generator hasn't completed yet")
+impl<'a, I: Iterator> Generator<'a> for IterGenerator<I> where I::Item: 'static {
+    type Yield = I::Item;
+    type Receive = ();
+
+    fn has_completed(&self) -> bool {
+        self.1
+    }
+
+    fn resume(&mut self, _: ()) -> Option<I::Item> {
+        self.next()
+    }
+}
+
+impl<'a, I: Iterator> ResultingGenerator<'a> for IterGenerator<I> where I::Item: 'static {
+    type Return = ();
+
+    /// An iterator has no value of its own, so the result is () once the iterator has been exhausted
+    fn result(self) -> Result<(), GeneratorError> {
+        self.try_result().map_err(|(_, e)| e)
+    }
+
+    fn try_result(self) -> Result<(), (Self, GeneratorError)> {
+        if self.1 { Ok(()) } else { Err((self, GeneratorError::NotCompleted)) }
+    }
+}
+
+impl<I: Iterator> Iterator for IterGenerator<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        if self.1 {
+            return None;
         }
+        let val = self.0.next();
+        self.1 = val.is_none();
+        val
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.1 { (0, Some(0)) } else { self.0.size_hint() }
     }
 }
-impl<'a, Y: 'static, Ret: 'static, Rec: 'a> Generator<'a> for BoostedGenerator<'a, Y, Ret, Rec> {
-    type Yield = Y;
-    type Receive = Rec;
+
+/// The iterator is not advanced anymore once it returned None
+impl<I: Iterator> FusedIterator for IterGenerator<I> {}
+
+impl<G> SendGenerator<G> {
+    /// Wraps [generator], which may be moved to other threads afterwards as long as the values it yields, receives and returns are Send
+    ///
+    /// # Safety
+    /// Like for [SendCoroutine::new](crate::coroutines::SendCoroutine::new) the values living on the callstack of the generating function are not checked to be Send,
+    /// neither are the ones captured by adapters. None of them may be bound to a thread (like Rc or references to thread locals)
+    pub unsafe fn new(generator: G) -> Self {
+        SendGenerator(generator)
+    }
+    /// Hands back the wrapped generator
+    pub fn into_inner(self) -> G {
+        self.0
+    }
+}
+
+unsafe impl<'a, G: ResultingGenerator<'a>> Send for SendGenerator<G> where G::Yield: Send, G::Receive: Send, G::Return: Send {}
+
+impl<'a, G: Generator<'a>> Generator<'a> for SendGenerator<G> {
+    type Yield = G::Yield;
+    type Receive = G::Receive;
 
     fn has_completed(&self) -> bool {
-        match &self.0 {
-            BoostedGeneratorState::COMPLETED(_) => true,
-            BoostedGeneratorState::RUNNING(co) => {
-                co.is_completed()
-            }
-        }
+        self.0.has_completed()
     }
 
-    fn resume(&mut self, send: Self::Receive) -> Option<Self::Yield> {
-        let next = match &mut self.0 {
-            BoostedGeneratorState::RUNNING(co) => co.resume(send),
-            BoostedGeneratorState::COMPLETED(_) => panic!("invalid generator state")
-        };
-        match next {
-            ResumeResult::Return(r) => {
-                self.0 = BoostedGeneratorState::COMPLETED(r);
-                None
-            }
-            ResumeResult::Yield(v) => Some(v)
-        }
+    fn resume(&mut self, send: G::Receive) -> Option<G::Yield> {
+        self.0.resume(send)
+    }
+
+    fn resume_caught(&mut self, send: G::Receive) -> Result<Option<G::Yield>, GeneratorError> {
+        self.0.resume_caught(send)
+    }
+
+    fn cancel(&self) {
+        self.0.cancel()
+    }
+
+    fn resume_delegated(&mut self, send: G::Receive, delegates: &mut DelegateStack<G::Yield>) -> Option<G::Yield> {
+        self.0.resume_delegated(send, delegates)
     }
 }
 
-impl<'a, Y: 'static, Ret: 'static> BoostedGenerator<'a, Y, Ret, ()> {
-    /// Create a generator which does not receive meaninful values and there may ignore it (closure does not receive initial argument as second parameter)
-    /// Returns an initialized Generator with allocated callstack ready for iteration
-    pub fn new<F>(gen_fn: F) -> Self
-        where F: FnOnce(&mut BoostedGeneratorChannel<Y, Ret, ()>) -> Ret + 'static {
-        Self::new_receiving(|chan, _| {
-            gen_fn(chan)
-        })
+impl<'a, G: ResultingGenerator<'a>> ResultingGenerator<'a> for SendGenerator<G> {
+    type Return = G::Return;
+
+    fn result(self) -> Result<G::Return, GeneratorError> {
+        self.0.result()
+    }
+
+    fn try_result(self) -> Result<G::Return, (Self, GeneratorError)> {
+        self.0.try_result().map_err(|(generator, e)| (SendGenerator(generator), e))
     }
 }
 
+impl<G: Iterator> Iterator for SendGenerator<G> {
+    type Item = G::Item;
 
-impl<'a, Y: 'static, Ret: 'static> Iterator for BoostedGenerator<'a, Y, Ret, ()> {
-    type Item = Y;
-    /// offers non destructive iteration
-    fn next(&mut self) -> Option<Self::Item> {
-        self.resume(())
+    fn next(&mut self) -> Option<G::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
     }
 }
 
-impl<'a, 'b: 'a, Y: 'static> GeneratorChannel<'a> for BoringGeneratorChannel<'a, 'b, Y> {
-    type Yield = Y;
-    type Receive = ();
+impl<G: FusedIterator> FusedIterator for SendGenerator<G> {}
 
-    /// Send single [val] and yields execution
-    fn yield_val(&mut self, val: Y) {
-        self.0.suspend(val)
+impl<G> UnwindSafeGenerator<G> {
+    /// Hands back the wrapped generator
+    pub fn into_inner(self) -> G {
+        self.0
     }
 }
 
-impl<'a, 'b: 'a, Y: 'static, Ret: 'static, Rec: 'a> GeneratorChannel<'a> for BoostedGeneratorChannel<'a, 'b, Y, Ret, Rec> {
-    type Yield = Y;
-    type Receive = Rec;
+// The constructors only wrap generators whose generating function is unwind safe, so whatever it captures can't be observed broken after its panic has been caught.
+// The generator itself completes before the panic is rethrown and the rest of its state is unwind safe as argued for [UnwindSafeCoroutine](crate::coroutines::UnwindSafeCoroutine)
+impl<'a, G: ResultingGenerator<'a>> UnwindSafe for UnwindSafeGenerator<G> where G::Yield: UnwindSafe, G::Receive: UnwindSafe, G::Return: UnwindSafe {}
 
-    /// Send single [val] and yields execution
-    fn yield_val(&mut self, val: Y) -> Rec {
-        self.0.suspend(val)
+// Shared references only allow to query whether the generator has completed and to cancel it, neither of which resumes the generating function
+impl<'a, G: ResultingGenerator<'a>> RefUnwindSafe for UnwindSafeGenerator<G> where G::Yield: RefUnwindSafe, G::Receive: RefUnwindSafe, G::Return: RefUnwindSafe {}
+
+impl<'a, G: Generator<'a>> Generator<'a> for UnwindSafeGenerator<G> {
+    type Yield = G::Yield;
+    type Receive = G::Receive;
+
+    fn has_completed(&self) -> bool {
+        self.0.has_completed()
+    }
+
+    fn resume(&mut self, send: G::Receive) -> Option<G::Yield> {
+        self.0.resume(send)
+    }
+
+    fn resume_caught(&mut self, send: G::Receive) -> Result<Option<G::Yield>, GeneratorError> {
+        self.0.resume_caught(send)
+    }
+
+    fn cancel(&self) {
+        self.0.cancel()
+    }
+
+    fn resume_delegated(&mut self, send: G::Receive, delegates: &mut DelegateStack<G::Yield>) -> Option<G::Yield> {
+        self.0.resume_delegated(send, delegates)
     }
 }
 
-impl<'a, Y, Ret, Rec, RF: Fn() -> Rec> Iterator for BoostedGeneratorIterator<'a, Y, Ret, Rec, RF> {
-    type Item = Y;
+impl<'a, G: ResultingGenerator<'a>> ResultingGenerator<'a> for UnwindSafeGenerator<G> {
+    type Return = G::Return;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.0.resume((self.1)())
+    fn result(self) -> Result<G::Return, GeneratorError> {
+        self.0.result()
+    }
+
+    fn try_result(self) -> Result<G::Return, (Self, GeneratorError)> {
+        self.0.try_result().map_err(|(generator, e)| (UnwindSafeGenerator(generator), e))
+    }
+}
+
+impl<G: Iterator> Iterator for UnwindSafeGenerator<G> {
+    type Item = G::Item;
+
+    fn next(&mut self) -> Option<G::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<G: FusedIterator> FusedIterator for UnwindSafeGenerator<G> {}
+
+impl<R: Send + 'static> ResultHandle<R> {
+    /// Drives [generator] on a new thread handing each value it yields to [send], once [send] returns false the generator is cancelled like by [GeneratorExt::try_fold_yields]
+    pub(crate) fn spawn<'a, G, S>(generator: G, mut send: S) -> Self
+        where G: ResultingGenerator<'a, Receive=(), Return=R> + Send + 'static, S: FnMut(G::Yield) -> bool + Send + 'static {
+        ResultHandle(thread::spawn(move || {
+            generator.try_fold_yields((), |(), val| if send(val) { ControlFlow::Continue(()) } else { ControlFlow::Break(()) }).1
+        }))
+    }
+}
+
+impl<R> ResultHandle<R> {
+    /// Blocks until the generator completed and returns its result like [ResultingGenerator::result] reports it
+    pub fn join(self) -> Result<R, GeneratorError> {
+        self.0.join().unwrap_or_else(|panic| Err(GeneratorError::Panicked { message: panic_message(&panic) }))
     }
-}
\ No newline at end of file
+    /// Tells whether the driving thread has finished, so [join] won't block
+    pub fn is_finished(&self) -> bool {
+        self.0.is_finished()
+    }
+}
+
+impl<'a, Y: 'static, Ret: 'static, Rec: 'a> BoostedGenerator<'a, Y, Ret, Rec> {
+    /// Factory function creating a new generator with input capabilities
+    pub fn new_receiving<F>(gen_fn: F) -> Self
+        where F: FnOnce(&mut BoostedGeneratorChannel<Y, Ret, Rec>, Rec) -> Ret + 'a {
+        Self::new_receiving_with_factory(StackFactory::default_stack(), gen_fn)
+    }
+    /// Like [new_receiving] but starting [gen_fn] with [initial], so the value sent by the first resume answers its first yield, see [Coroutine::new_with_initial]
+    pub fn new_receiving_with_initial<F>(initial: Rec, gen_fn: F) -> Self
+        where F: FnOnce(&mut BoostedGeneratorChannel<Y, Ret, Rec>, Rec) -> Ret + 'a {
+        Self(BoostedGeneratorState::RUNNING(Coroutine::new_with_initial(initial, |chan, i| {
+            let mut gen_chan = BoostedGeneratorChannel(chan);
+            gen_fn(&mut gen_chan, i)
+        })), SizeHint::UNKNOWN)
+    }
+    /// Like [new_receiving] but building the callstack by [stack_factory] on first resume
+    pub fn new_receiving_with_factory<F>(stack_factory: StackFactory, gen_fn: F) -> Self
+        where F: FnOnce(&mut BoostedGeneratorChannel<Y, Ret, Rec>, Rec) -> Ret + 'a {
+        Self(BoostedGeneratorState::RUNNING(Coroutine::new_with_factory(stack_factory, |chan, i| {
+            let mut gen_chan = BoostedGeneratorChannel(chan);
+            gen_fn(&mut gen_chan,i)
+        })), SizeHint::UNKNOWN)
+    }
+    /// Like [new_receiving] but running on a callstack of [stack_size] bytes, see [StackFactory::of_size]
+    pub fn new_receiving_with_stack<F>(stack_size: usize, gen_fn: F) -> Self
+        where F: FnOnce(&mut BoostedGeneratorChannel<Y, Ret, Rec>, Rec) -> Ret + 'a {
+        Self::new_receiving_with_factory(StackFactory::of_size(stack_size), gen_fn)
+    }
+    /// Like [new_receiving] but for an unwind safe [gen_fn], which makes the generator unwind safe as well, see [UnwindSafeGenerator]
+    pub fn new_receiving_unwind_safe<F>(gen_fn: F) -> UnwindSafeGenerator<Self>
+        where F: FnOnce(&mut BoostedGeneratorChannel<Y, Ret, Rec>, Rec) -> Ret + UnwindSafe + 'a {
+        UnwindSafeGenerator(Self::new_receiving(gen_fn))
+    }
+    /// Announces that the generator is going to yield at least [lower] and at most [upper] values, which its [Iterator::size_hint] passes on counting down as values are yielded
+    /// E.g. collecting the values then allocates only once, wrong bounds don't break anything but that
+    pub fn with_size_hint(mut self, lower: usize, upper: Option<usize>) -> Self {
+        self.1 = SizeHint(lower, upper);
+        self
+    }
+    /// Boxes the generator as trait object, e.g. to keep generators of different types in one collection
+    pub fn boxed(self) -> Box<dyn Generator<'a, Yield=Y, Receive=Rec> + 'a> {
+        Box::new(self)
+    }
+    /// Like [Generator::resume] but reports failures as [CoroutineError] instead of panicking
+    /// Resuming a completed generator is not considered a failure but keeps returning None
+    pub fn resume_checked(&mut self, send: Rec) -> Result<Option<Y>, CoroutineError> {
+        self.resume_by(|co| co.resume_checked(send))
+    }
+    /// Resumes the coroutine of a running generator by [resume] and moves the generator to the state the coroutine ends up in
+    fn resume_by(&mut self, resume: impl FnOnce(&mut Coroutine<'a, Y, Ret, Rec>) -> Result<ResumeResult<Y, Ret>, CoroutineError>) -> Result<Option<Y>, CoroutineError> {
+        let next = match &mut self.0 {
+            BoostedGeneratorState::RUNNING(co) if !co.is_completed() => resume(co),
+            _ => return Ok(None)
+        };
+        match next {
+            Ok(ResumeResult::Return(r)) => {
+                let stats = self.stats();
+                self.0 = BoostedGeneratorState::COMPLETED(r, stats);
+                Ok(None)
+            }
+            Ok(ResumeResult::Yield(v)) => {
+                self.1.yielded();
+                Ok(Some(v))
+            }
+            Err(e) => {
+                let (stats, watcher) = (self.stats(), self.watcher());
+                self.0 = BoostedGeneratorState::FAILED(GeneratorError::from(e.clone()), stats, watcher);
+                Err(e)
+            }
+        }
+    }
+    /// Returns the statistics of the underlying coroutine, see [Coroutine::stats]
+    pub fn stats(&self) -> Stats {
+        match &self.0 {
+            BoostedGeneratorState::RUNNING(co) => co.stats(),
+            BoostedGeneratorState::COMPLETED(_, stats) | BoostedGeneratorState::FAILED(_, stats, _) | BoostedGeneratorState::TAKEN(stats) => *stats,
+        }
+    }
+    /// Returns a handle observing the completion of the underlying coroutine, see [Coroutine::watcher]
+    /// The coroutine of a completed generator is gone already, so its watcher reports the return right away
+    pub fn watcher(&self) -> CompletionWatcher {
+        match &self.0 {
+            BoostedGeneratorState::RUNNING(co) => co.watcher(),
+            BoostedGeneratorState::COMPLETED(..) | BoostedGeneratorState::TAKEN(_) => CompletionWatcher::new(Some(CompletionKind::Returned)),
+            BoostedGeneratorState::FAILED(_, _, watcher) => watcher.clone(),
+        }
+    }
+    /// Returns the size of the underlying coroutines callstack, see [Coroutine::stack_size]
+    pub fn stack_size(&self) -> Option<usize> {
+        self.stack_range().map(|range| range.len())
+    }
+    /// Returns the address range of the underlying coroutines callstack, see [Coroutine::stack_range]
+    pub fn stack_range(&self) -> Option<Range<usize>> {
+        match &self.0 {
+            BoostedGeneratorState::RUNNING(co) => co.stack_range(),
+            BoostedGeneratorState::COMPLETED(..) | BoostedGeneratorState::FAILED(..) | BoostedGeneratorState::TAKEN(_) => None,
+        }
+    }
+    /// Returns the value [gen_fn] returned, or None if the generator has not returned (yet) or its result has been taken
+    pub fn result_ref(&self) -> Option<&Ret> {
+        match &self.0 {
+            BoostedGeneratorState::COMPLETED(r, _) => Some(r),
+            _ => None
+        }
+    }
+    /// Like [result_ref] but allowing to modify the value in place
+    pub fn result_mut(&mut self) -> Option<&mut Ret> {
+        match &mut self.0 {
+            BoostedGeneratorState::COMPLETED(r, _) => Some(r),
+            _ => None
+        }
+    }
+    /// Moves the value [gen_fn] returned out of the generator, which stays completed
+    /// Returns None like [result_ref] does, in particular on every call but the first
+    pub fn take_result(&mut self) -> Option<Ret> {
+        match self.0 {
+            BoostedGeneratorState::COMPLETED(_, stats) => match std::mem::replace(&mut self.0, BoostedGeneratorState::TAKEN(stats)) {
+                BoostedGeneratorState::COMPLETED(r, _) => Some(r),
+                _ => unreachable!()
+            },
+            _ => None
+        }
+    }
+    /// Returns the token to cancel the generator with, see [Coroutine::cancellation_token]
+    /// The generating function observes the cancellation by [BoostedGeneratorChannel::is_cancelled], cancelling a completed generator has no effect
+    pub fn cancellation_token(&self) -> CancellationToken {
+        match &self.0 {
+            BoostedGeneratorState::RUNNING(co) => co.cancellation_token(),
+            _ => CancellationToken::default()
+        }
+    }
+    /// Creates a iterator for a non-ignorant Generator using the passed [source] closure as source of receive values
+    pub fn create_iter<RF:FnMut()->Rec>(self, source:RF) -> BoostedGeneratorIterator<'a,Y,Ret,Rec,RF> {
+        BoostedGeneratorIterator(self,source)
+    }
+}
+
+impl<'a, Y: 'static, Ret: 'static, Rec: 'a> ResultingGenerator<'a> for BoostedGenerator<'a, Y, Ret, Rec> {
+    type Return = Ret;
+
+    fn result(self) -> Result<Ret, GeneratorError> {
+        match self.0 {
+            BoostedGeneratorState::COMPLETED(r, _) => Ok(r),
+            BoostedGeneratorState::FAILED(e, ..) => Err(e),
+            BoostedGeneratorState::TAKEN(_) => Err(GeneratorError::Taken),
+            BoostedGeneratorState::RUNNING(_) => Err(GeneratorError::NotCompleted),
+        }
+    }
+
+    fn try_result(self) -> Result<Ret, (Self, GeneratorError)> {
+        let error = match &self.0 {
+            BoostedGeneratorState::COMPLETED(..) => return self.result().map_err(|_| unreachable!()),
+            BoostedGeneratorState::FAILED(e, ..) => e.clone(),
+            BoostedGeneratorState::TAKEN(_) => GeneratorError::Taken,
+            BoostedGeneratorState::RUNNING(_) => GeneratorError::NotCompleted,
+        };
+        Err((self, error))
+    }
+}
+impl<'a, Y: 'static, Ret: 'static, Rec: 'a> Generator<'a> for BoostedGenerator<'a, Y, Ret, Rec> {
+    type Yield = Y;
+    type Receive = Rec;
+
+    fn has_completed(&self) -> bool {
+        match &self.0 {
+            BoostedGeneratorState::COMPLETED(..) | BoostedGeneratorState::FAILED(..) | BoostedGeneratorState::TAKEN(_) => true,
+            BoostedGeneratorState::RUNNING(co) => {
+                co.is_completed()
+            }
+        }
+    }
+
+    fn resume(&mut self, send: Self::Receive) -> Option<Self::Yield> {
+        self.resume_checked(send).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn resume_caught(&mut self, send: Rec) -> Result<Option<Y>, GeneratorError> {
+        self.resume_checked(send).map_err(GeneratorError::from)
+    }
+
+    fn cancel(&self) {
+        self.cancellation_token().cancel()
+    }
+
+    fn resume_delegated(&mut self, send: Rec, delegates: &mut DelegateStack<Y>) -> Option<Y> {
+        self.resume_by(|co| co.resume_delegated_checked(send, delegates)).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl<'a, Y: 'static, Ret: 'static> BoostedGenerator<'a, Y, Ret, ()> {
+    /// Create a generator which does not receive meaninful values and there may ignore it (closure does not receive initial argument as second parameter)
+    /// Returns an initialized Generator with allocated callstack ready for iteration
+    pub fn new<F>(gen_fn: F) -> Self
+        where F: FnOnce(&mut BoostedGeneratorChannel<Y, Ret, ()>) -> Ret + 'a {
+        Self::new_with_factory(StackFactory::default_stack(), gen_fn)
+    }
+    /// Like [new] but building the callstack by [stack_factory] on first resume
+    pub fn new_with_factory<F>(stack_factory: StackFactory, gen_fn: F) -> Self
+        where F: FnOnce(&mut BoostedGeneratorChannel<Y, Ret, ()>) -> Ret + 'a {
+        Self::new_receiving_with_factory(stack_factory, |chan, _| {
+            gen_fn(chan)
+        })
+    }
+    /// Like [new] but running on a callstack of [stack_size] bytes, see [StackFactory::of_size]
+    pub fn new_with_stack<F>(stack_size: usize, gen_fn: F) -> Self
+        where F: FnOnce(&mut BoostedGeneratorChannel<Y, Ret, ()>) -> Ret + 'a {
+        Self::new_with_factory(StackFactory::of_size(stack_size), gen_fn)
+    }
+    /// Like [new] but for an unwind safe [gen_fn], which makes the generator unwind safe as well, see [UnwindSafeGenerator]
+    ///
+    /// ```compile_fail,E0277
+    /// use rusterators::generators::{BoostedGenerator, GeneratorChannel};
+    /// let mut count = 0;
+    /// let gen = BoostedGenerator::<u32, (), ()>::new_unwind_safe(|chan| { count += 1; chan.yield_val(count); });
+    /// ```
+    pub fn new_unwind_safe<F>(gen_fn: F) -> UnwindSafeGenerator<Self>
+        where F: FnOnce(&mut BoostedGeneratorChannel<Y, Ret, ()>) -> Ret + UnwindSafe + 'a {
+        UnwindSafeGenerator(Self::new(gen_fn))
+    }
+}
+
+
+impl<'a, Y: 'static, Ret: 'static, Rec: 'a> BoostedGenerator<'a, Y, Result<Ret, CoroutinePanic>, Rec> {
+    /// Like [new_receiving] but catching a panic of [gen_fn] instead of rethrowing it, see [Coroutine::new_try]
+    /// The generator then completes and [ResultingGenerator::result] returns Ok(Err(..)) carrying the panic
+    pub fn new_receiving_try<F>(gen_fn: F) -> Self
+        where F: FnOnce(&mut BoostedGeneratorChannel<Y, Result<Ret, CoroutinePanic>, Rec>, Rec) -> Ret + 'a {
+        Self(BoostedGeneratorState::RUNNING(Coroutine::new_try(|chan, i| {
+            let mut gen_chan = BoostedGeneratorChannel(chan);
+            gen_fn(&mut gen_chan, i)
+        })), SizeHint::UNKNOWN)
+    }
+}
+
+impl<'a, Y: 'static, Ret: 'static> BoostedGenerator<'a, Y, Result<Ret, CoroutinePanic>, ()> {
+    /// Like [new] but catching a panic of [gen_fn] instead of rethrowing it, see [new_receiving_try]
+    pub fn new_try<F>(gen_fn: F) -> Self
+        where F: FnOnce(&mut BoostedGeneratorChannel<Y, Result<Ret, CoroutinePanic>, ()>) -> Ret + 'a {
+        Self::new_receiving_try(|chan, _| gen_fn(chan))
+    }
+}
+
+/// Generators receiving values with a default, in particular (), are iterated by resuming them with the default value
+impl<'a, Y: 'static, Ret: 'static, Rec: Default + 'a> Iterator for BoostedGenerator<'a, Y, Ret, Rec> {
+    type Item = Y;
+    /// offers non destructive iteration
+    fn next(&mut self) -> Option<Self::Item> {
+        self.resume(Rec::default())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.1.remaining(self.has_completed())
+    }
+}
+
+/// Completed generators keep returning None, keeping the buffered return value
+impl<'a, Y: 'static, Ret: 'static, Rec: Default + 'a> FusedIterator for BoostedGenerator<'a, Y, Ret, Rec> {}
+
+impl<'a, Y: 'static, Ret: 'static> BoostedGenerator<'a, Y, Ret, ()> {
+    /// Wraps the generator into one allowing to [PeekableGenerator::peek] at the next value
+    /// Unlike [Iterator::peekable] the wrapper is a [ResultingGenerator] itself, so the result can still be read after iteration
+    pub fn peekable(self) -> PeekableGenerator<Self> {
+        PeekableGenerator(self, None)
+    }
+}
+
+impl<'a, G: Generator<'a, Receive=()> + Iterator<Item=<G as Generator<'a>>::Yield>> PeekableGenerator<G> {
+    /// Returns the value the next call of [Iterator::next] hands out without taking it, resuming the generator if there is no such value buffered yet
+    pub fn peek(&mut self) -> Option<&G::Item> {
+        let generator = &mut self.0;
+        self.1.get_or_insert_with(|| generator.next()).as_ref()
+    }
+    /// Like [peek] but allowing to modify the value in place
+    pub fn peek_mut(&mut self) -> Option<&mut G::Item> {
+        let generator = &mut self.0;
+        self.1.get_or_insert_with(|| generator.next()).as_mut()
+    }
+}
+
+impl<G: Iterator> Iterator for PeekableGenerator<G> {
+    type Item = G::Item;
+
+    fn next(&mut self) -> Option<G::Item> {
+        match self.1.take() {
+            Some(peeked) => peeked,
+            None => self.0.next()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.0.size_hint();
+        match &self.1 {
+            Some(Some(_)) => (lower.saturating_add(1), upper.and_then(|upper| upper.checked_add(1))),
+            Some(None) => (0, Some(0)),
+            None => (lower, upper)
+        }
+    }
+}
+
+impl<G: FusedIterator> FusedIterator for PeekableGenerator<G> {}
+
+impl<'a, G: Generator<'a, Receive=()> + Iterator<Item=<G as Generator<'a>>::Yield>> Generator<'a> for PeekableGenerator<G> {
+    type Yield = G::Yield;
+    type Receive = ();
+
+    /// A buffered value is still handed out, even if the underlying generator has completed
+    fn has_completed(&self) -> bool {
+        !matches!(self.1, Some(Some(_))) && self.0.has_completed()
+    }
+
+    fn resume(&mut self, _: ()) -> Option<G::Yield> {
+        self.next()
+    }
+
+    fn cancel(&self) {
+        self.0.cancel()
+    }
+}
+
+impl<'a, G: ResultingGenerator<'a, Receive=()> + Iterator<Item=<G as Generator<'a>>::Yield>> ResultingGenerator<'a> for PeekableGenerator<G> {
+    type Return = G::Return;
+
+    fn result(self) -> Result<G::Return, GeneratorError> {
+        self.try_result().map_err(|(_, e)| e)
+    }
+
+    fn try_result(self) -> Result<G::Return, (Self, GeneratorError)> {
+        if let Some(Some(_)) = self.1 {
+            return Err((self, GeneratorError::NotCompleted));
+        }
+        let peeked = self.1;
+        self.0.try_result().map_err(|(generator, e)| (PeekableGenerator(generator, peeked), e))
+    }
+}
+
+impl<'g, G: Iterator> Iterator for UntilPanic<'g, G> {
+    type Item = G::Item;
+
+    fn next(&mut self) -> Option<G::Item> {
+        if self.1 {
+            return None;
+        }
+        let generator = &mut self.0;
+        catch_unwind(AssertUnwindSafe(|| generator.next())).unwrap_or_else(|_| {
+            self.1 = true;
+            None
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.1 { (0, Some(0)) } else { self.0.size_hint() }
+    }
+}
+
+impl SizeHint {
+    const UNKNOWN: SizeHint = SizeHint(0, None);
+
+    /// Counts down the bounds after a value has been yielded
+    fn yielded(&mut self) {
+        self.0 = self.0.saturating_sub(1);
+        self.1 = self.1.map(|upper| upper.saturating_sub(1));
+    }
+
+    /// Returns the bounds in the form of [Iterator::size_hint], which are zero once the generator has completed
+    fn remaining(self, completed: bool) -> (usize, Option<usize>) {
+        if completed { (0, Some(0)) } else { (self.0, self.1) }
+    }
+}
+
+impl From<CoroutineError> for GeneratorError {
+    fn from(error: CoroutineError) -> Self {
+        match error {
+            CoroutineError::Panicked(message) => GeneratorError::Panicked { message },
+            CoroutineError::Dropped => GeneratorError::Dropped,
+            e => GeneratorError::Panicked { message: Some(e.to_string()) },
+        }
+    }
+}
+
+impl Display for GeneratorError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            GeneratorError::Panicked { message: Some(message) } => write!(f, "generator panicked: {}", message),
+            GeneratorError::Panicked { message: None } => write!(f, "generator panicked"),
+            GeneratorError::Dropped => write!(f, "generator context dropped before it completed"),
+            GeneratorError::NotCompleted => write!(f, "generator hasn't completed yet"),
+            GeneratorError::Taken => write!(f, "generator result has already been taken"),
+        }
+    }
+}
+
+impl Error for GeneratorError {}
+
+impl<'a, 'b: 'a, Y: 'static, Ret: 'static> BoringGeneratorChannel<'a, 'b, Y, Ret> {
+    /// Queries whether the generator has been cancelled by [Generator::cancel], see [CoroutineChannel::is_cancelled]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.is_cancelled()
+    }
+}
+
+impl<'a, 'b: 'a, Y: 'static, Ret: 'static> GeneratorChannel<'a> for BoringGeneratorChannel<'a, 'b, Y, Ret> {
+    type Yield = Y;
+    type Receive = ();
+
+    /// Send single [val] and yields execution
+    fn yield_val(&mut self, val: Y) {
+        self.0.suspend(val)
+    }
+
+    /// Sends all values of [batch] with a single context switch
+    fn yield_batch(&mut self, batch: Vec<Y>) {
+        self.0.suspend_batch(batch)
+    }
+
+    /// Lets the resuming context resume [gen] directly, so its values don't pass this generator with two context switches each
+    fn yield_from<R: 'static>(&mut self, mut gen: impl IgnorantGenerator<'a, Y> + ResultingGenerator<'a, Yield=Y, Return=R, Receive=()>) -> Result<R, GeneratorError> {
+        self.0.delegate(&mut |delegates| gen.resume_delegated((), delegates));
+        gen.result()
+    }
+
+    /// Lets the resuming context take the values out of the ring, so it only switches to this generator once the ring is empty
+    /// Panics if [N] is 0
+    fn yield_all_buffered<const N: usize>(&mut self, iter: impl Iterator<Item=Y>) {
+        yield_buffered_by(self.0, RingExchange::<Y, N>::new(), iter)
+    }
+}
+
+impl<'a, 'b: 'a, Y: 'static, Ret: 'static, Rec: 'a> BoostedGeneratorChannel<'a, 'b, Y, Ret, Rec> {
+    /// Queries whether the generator has been cancelled by its [CancellationToken] or [Generator::cancel], see [CoroutineChannel::is_cancelled]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.is_cancelled()
+    }
+}
+
+impl<'a, 'b: 'a, Y: 'static, Ret: 'static, Rec: 'a> GeneratorChannel<'a> for BoostedGeneratorChannel<'a, 'b, Y, Ret, Rec> {
+    type Yield = Y;
+    type Receive = Rec;
+
+    /// Send single [val] and yields execution
+    fn yield_val(&mut self, val: Y) -> Rec {
+        self.0.suspend(val)
+    }
+
+    /// Sends all values of [batch] with a single context switch
+    fn yield_batch(&mut self, batch: Vec<Y>) -> Rec {
+        self.0.suspend_batch(batch)
+    }
+
+    /// Lets the resuming context resume [gen] directly, so its values don't pass this generator with two context switches each
+    /// Nested generators yielding from each other thereby cost two context switches per value regardless of their depth, the values received meanwhile are dropped
+    fn yield_from<R: 'static>(&mut self, mut gen: impl IgnorantGenerator<'a, Y> + ResultingGenerator<'a, Yield=Y, Return=R, Receive=()>) -> Result<R, GeneratorError> {
+        self.0.delegate(&mut |delegates| gen.resume_delegated((), delegates));
+        gen.result()
+    }
+
+    /// Lets the resuming context take the values out of the ring, so it only switches to this generator once the ring is empty
+    /// Panics if [N] is 0
+    fn yield_all_buffered<const N: usize>(&mut self, iter: impl Iterator<Item=Y>) {
+        yield_buffered_by(self.0, RingExchange::<Y, N>::new(), iter)
+    }
+}
+
+/// Collects the values of [iter] in [ring] on the callstack of the coroutine, which delegates to taking them out of the ring each time it is full and once [iter] is exhausted
+/// A ring left behind by an unwind of the coroutine drops the values it still holds
+fn yield_buffered_by<Y: 'static, Ret: 'static, Rec, const N: usize>(chan: &mut CoroutineChannel<Y, Ret, Rec>, mut ring: RingExchange<Y, N>, iter: impl Iterator<Item=Y>) {
+    for val in iter {
+        if let Err(val) = ring.push(val) {
+            chan.delegate(&mut |_| ring.pop());
+            // the delegate only returns once the ring is empty
+            let _ = ring.push(val);
+        }
+    }
+    if !ring.is_empty() {
+        chan.delegate(&mut |_| ring.pop());
+    }
+}
+
+impl<'a, Y, Ret, Rec, RF: FnMut() -> Rec> BoostedGeneratorIterator<'a, Y, Ret, Rec, RF> {
+    /// Hands back the generator dropping the source of receive values
+    pub fn into_inner(self) -> BoostedGenerator<'a, Y, Ret, Rec> {
+        self.0
+    }
+    /// Returns the result of the generator, see [ResultingGenerator::result]
+    pub fn result(self) -> Result<Ret, GeneratorError> {
+        self.0.result()
+    }
+}
+
+impl<'a, Y, Ret, Rec, RF: FnMut() -> Rec> Iterator for BoostedGeneratorIterator<'a, Y, Ret, Rec, RF> {
+    type Item = Y;
+
+    /// The source is not called anymore once the generator has completed
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0.has_completed() {
+            return None;
+        }
+        self.0.resume((self.1)())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.1.remaining(self.0.has_completed())
+    }
+}
+
+impl<'a, Y, Ret, Rec, RF: FnMut() -> Rec> FusedIterator for BoostedGeneratorIterator<'a, Y, Ret, Rec, RF> {}
+
+/// Creates a [BoostedGenerator] from a block of statements, which yield values by `yield_!(value)` and return the result of the generator by `ret_!(value)`
+/// The block ends up in a `move` closure, whose channel is not named by the block. A block completing without `ret_!` returns ()
+///
+/// ```
+/// use rusterators::generator;
+/// use rusterators::generators::ResultingGenerator;
+/// let mut gen = generator! {
+///     for t in 0..3 {
+///         yield_!(t);
+///     }
+///     ret_!(42)
+/// };
+/// assert_eq!(gen.by_ref().collect::<Vec<_>>(), vec![0, 1, 2]);
+/// assert_eq!(gen.result(), Ok(42));
+/// ```
+///
+/// A closure header creates a receiving generator starting with the value sent by the first resume, `yield_!` evaluates to the value sent by the following one
+///
+/// ```
+/// use rusterators::generator;
+/// use rusterators::generators::Generator;
+/// let mut sums = generator!(|input: i32| {
+///     let mut total = input;
+///     loop {
+///         total += yield_!(total);
+///     }
+/// });
+/// assert_eq!(sums.resume(1), Some(1));
+/// assert_eq!(sums.resume(2), Some(3));
+/// ```
+///
+/// `yield_!` and `ret_!` only exist within the block:
+///
+/// ```compile_fail
+/// use rusterators::generator;
+/// use rusterators::generators::BoostedGenerator;
+/// fn helper() {
+///     yield_!(1);
+/// }
+/// let gen: BoostedGenerator<i32, (), ()> = generator! { helper() };
+/// ```
+///
+/// and all values yielded have to be of the same type:
+///
+/// ```compile_fail,E0308
+/// use rusterators::generator;
+/// let gen = generator! {
+///     yield_!(1);
+///     yield_!("two");
+/// };
+/// ```
+///
+/// Note that `ret_!` within a nested closure returns from that closure instead of the generator
+#[macro_export]
+macro_rules! generator {
+    (|$input:ident : $receive:ty| $body:block) => {
+        $crate::generators::BoostedGenerator::new_receiving(move |__generator_channel, $input: $receive| {
+            #[allow(unused_macros)]
+            macro_rules! yield_ {
+                ($val:expr) => { $crate::generators::GeneratorChannel::yield_val(__generator_channel, $val) };
+            }
+            #[allow(unused_macros)]
+            macro_rules! ret_ {
+                () => { return };
+                ($val:expr) => { return $val };
+            }
+            $body
+        })
+    };
+    ($($body:tt)*) => {
+        $crate::generators::BoostedGenerator::new(move |__generator_channel| {
+            #[allow(unused_macros)]
+            macro_rules! yield_ {
+                ($val:expr) => { $crate::generators::GeneratorChannel::yield_val(__generator_channel, $val) };
+            }
+            #[allow(unused_macros)]
+            macro_rules! ret_ {
+                () => { return };
+                ($val:expr) => { return $val };
+            }
+            $($body)*
+        })
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::collections::HashSet;
+    use std::iter::FusedIterator;
+    use std::ops::ControlFlow;
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+    use std::rc::Rc;
+
+    use crate::coroutines::{CompletionKind, CoroutineError, StackFactory};
+
+    use super::{BoostedGenerator, BoringGenerator, Generator, GeneratorChannel, GeneratorError, GeneratorExt, IterGenerator, IteratorExt, ResultingGenerator, SendGenerator};
+
+    /// Yields the values until cancelled, returning how many it yielded
+    pub(super) fn counting(values: impl Iterator<Item=i32> + 'static) -> BoostedGenerator<'static, i32, usize, ()> {
+        BoostedGenerator::new(|chan| {
+            let mut count = 0;
+            for value in values {
+                if chan.is_cancelled() {
+                    break;
+                }
+                chan.yield_val(value);
+                count += 1;
+            }
+            count
+        })
+    }
+
+    /// Yields the running total of the received values until it receives 0 or is cancelled, returning the total
+    pub(super) fn summing() -> BoostedGenerator<'static, i32, i32, i32> {
+        BoostedGenerator::new_receiving(|chan, mut value| {
+            let mut total = 0;
+            while value != 0 && !chan.is_cancelled() {
+                total += value;
+                value = chan.yield_val(total);
+            }
+            total
+        })
+    }
+
+    #[test]
+    fn generators_run_on_factory_stack() {
+        let mut boosted = BoostedGenerator::new_with_factory(StackFactory::of_size(64 * 1024), |chan| {
+            chan.yield_all(0..3);
+            "done"
+        });
+        assert_eq!((&mut boosted).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(boosted.result(), Ok("done"));
+
+        let boring = BoringGenerator::new_with_factory(StackFactory::of_size(64 * 1024), |chan| chan.yield_all(0..3));
+        assert_eq!(boring.collect::<Vec<_>>(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn generators_run_on_stack_of_given_size() {
+        let mut boosted = BoostedGenerator::new_with_stack(64 * 1024, |chan| chan.yield_val(1));
+        assert_eq!(boosted.resume(()), Some(1));
+        assert!(boosted.stack_size().unwrap() >= 64 * 1024);
+
+        let mut receiving = BoostedGenerator::<u32, u32, u32>::new_receiving_with_stack(64 * 1024, |chan, first| chan.yield_val(first) + 1);
+        assert_eq!(receiving.resume(1), Some(1));
+        assert!(receiving.stack_size().unwrap() >= 64 * 1024);
+        assert_eq!(receiving.resume(2), None);
+        assert_eq!(receiving.result(), Ok(3));
+
+        let mut boring = BoringGenerator::new_with_stack(64 * 1024, |chan| chan.yield_all(0..3));
+        assert_eq!(boring.next(), Some(0));
+        assert!(boring.stack_size().unwrap() >= 64 * 1024);
+        assert_eq!(boring.collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn stats_count_resumes_and_yields() {
+        let mut boosted = BoostedGenerator::new(|chan| chan.yield_all(0..10));
+        assert_eq!((&mut boosted).count(), 10);
+        let stats = boosted.stats();
+        assert_eq!((stats.resumes, stats.yields, stats.returned), (11, 10, true));
+
+        let mut boring = BoringGenerator::new(|chan| chan.yield_all(0..10));
+        assert_eq!((&mut boring).count(), 10);
+        assert!(boring.has_completed());
+        let stats = boring.stats();
+        assert_eq!((stats.resumes, stats.yields, stats.returned), (11, 10, true));
+    }
+
+    #[test]
+    fn exhausted_generators_keep_returning_none() {
+        let mut boosted = BoostedGenerator::new(|chan| {
+            chan.yield_val(1);
+            2
+        });
+        assert_eq!(boosted.resume(()), Some(1));
+        assert_eq!(boosted.resume(()), None);
+        assert_eq!(boosted.resume(()), None);
+        assert_eq!(boosted.resume_checked(()), Ok(None));
+        assert_eq!(boosted.result(), Ok(2));
+
+        let mut boring = BoringGenerator::new(|chan| chan.yield_val(1));
+        assert_eq!(boring.resume(()), Some(1));
+        assert_eq!(boring.resume(()), None);
+        assert_eq!(boring.resume(()), None);
+    }
+
+    #[test]
+    fn exhausted_generators_fuse() {
+        fn assert_fused<I: FusedIterator<Item=i32>>(mut iter: I) {
+            assert_eq!(iter.by_ref().count(), 2);
+            for _ in 0..3 {
+                assert_eq!(iter.next(), None);
+            }
+        }
+        assert_fused(BoostedGenerator::new(|chan| chan.yield_all(0..2)));
+        assert_fused(BoringGenerator::new(|chan| chan.yield_all(0..2)));
+
+        let zipped = BoostedGenerator::new(|chan| chan.yield_all(0..2)).zip(0..5).collect::<Vec<_>>();
+        assert_eq!(zipped, vec![(0, 0), (1, 1)]);
+        let zipped = (0..5).zip(BoringGenerator::new(|chan| chan.yield_all(0..2))).collect::<Vec<_>>();
+        assert_eq!(zipped, vec![(0, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn result_tells_why_generator_failed() {
+        let gen = BoostedGenerator::new(|chan| chan.yield_val(1));
+        assert_eq!(gen.result(), Err(GeneratorError::NotCompleted));
+
+        let mut gen = BoostedGenerator::<i32, (), ()>::new(|_| panic!("broken {}", "generator"));
+        assert!(catch_unwind(AssertUnwindSafe(|| gen.next())).is_err());
+        assert!(gen.has_completed());
+        assert_eq!(gen.stats().resumes, 1);
+        assert_eq!(gen.watcher().completion_kind(), Some(CompletionKind::Panicked));
+        let error = gen.result().unwrap_err();
+        assert_eq!(error, GeneratorError::Panicked { message: Some("broken generator".to_string()) });
+        assert_eq!(error.to_string(), "generator panicked: broken generator");
+
+        let mut gen = BoostedGenerator::<i32, (), ()>::new(|_| std::panic::panic_any(3));
+        assert!(gen.resume_checked(()).is_err());
+        assert_eq!(gen.result(), Err(GeneratorError::Panicked { message: None }));
+
+        assert_eq!(GeneratorError::from(CoroutineError::Dropped), GeneratorError::Dropped);
+        assert_eq!(GeneratorError::from(CoroutineError::StackOverflow), GeneratorError::Panicked { message: Some("Coroutine stack overflowed".to_string()) });
+    }
+
+    #[test]
+    fn result_is_inspected_before_taken() {
+        struct Summary<'a> {
+            gen: BoostedGenerator<'a, i32, Vec<&'static str>, ()>,
+        }
+        let mut summary = Summary {
+            gen: BoostedGenerator::new(|chan| {
+                chan.yield_all(0..3);
+                vec!["skipped line"]
+            })
+        };
+        assert_eq!(summary.gen.result_ref(), None);
+        assert_eq!(summary.gen.take_result(), None);
+        assert_eq!(summary.gen.by_ref().sum::<i32>(), 3);
+        assert_eq!(summary.gen.result_ref(), Some(&vec!["skipped line"]));
+        summary.gen.result_mut().unwrap().push("empty line");
+        assert_eq!(summary.gen.take_result(), Some(vec!["skipped line", "empty line"]));
+
+        assert!(summary.gen.has_completed());
+        assert_eq!(summary.gen.result_ref(), None);
+        assert_eq!(summary.gen.take_result(), None);
+        assert_eq!(summary.gen.next(), None);
+        assert_eq!(summary.gen.stats().yields, 3);
+        assert_eq!(summary.gen.result(), Err(GeneratorError::Taken));
+    }
+
+    #[test]
+    fn try_result_hands_back_running_generator() {
+        let mut gen = BoostedGenerator::new(|chan| {
+            chan.yield_all(0..2);
+            "done"
+        });
+        assert_eq!(gen.next(), Some(0));
+        let (mut gen, error) = gen.try_result().err().unwrap();
+        assert_eq!(error, GeneratorError::NotCompleted);
+        assert_eq!(gen.by_ref().collect::<Vec<_>>(), vec![1]);
+        assert_eq!(gen.try_result().ok(), Some("done"));
+
+        let mut gen = BoostedGenerator::<i32, (), ()>::new(|_| panic!("failed"));
+        assert!(gen.resume_checked(()).is_err());
+        let (gen, error) = gen.try_result().err().unwrap();
+        assert_eq!(error, GeneratorError::Panicked { message: Some("failed".to_string()) });
+        assert!(gen.has_completed());
+        assert_eq!(gen.stats().resumes, 1);
+    }
+
+    #[test]
+    fn boring_generator_keeps_return_value_apart() {
+        let mut gen = BoringGenerator::new_resulting(|chan| {
+            chan.yield_all(vec!["a", "b", "c"].into_iter());
+            3usize
+        });
+        assert_eq!(gen.by_ref().collect::<Vec<_>>(), vec!["a", "b", "c"]);
+        assert_eq!(gen.next(), None);
+        assert_eq!(gen.stats().yields, 3);
+        assert_eq!(gen.result(), Ok(3));
+
+        let gen = BoringGenerator::new_resulting(|chan| chan.yield_val(1));
+        let (mut gen, error) = gen.try_result().err().unwrap();
+        assert_eq!(error, GeneratorError::NotCompleted);
+        assert_eq!(gen.by_ref().count(), 1);
+        assert_eq!(gen.try_result().ok(), Some(()));
+
+        let mut gen = BoringGenerator::<i32, i32>::new_resulting(|_| panic!("failed"));
+        assert!(catch_unwind(AssertUnwindSafe(|| gen.next())).is_err());
+        assert_eq!(gen.result(), Err(GeneratorError::Panicked { message: Some("failed".to_string()) }));
+    }
+
+    #[cfg(all(unix, feature = "stack-overflow-recovery"))]
+    #[inline(never)]
+    fn recurse_kib(depth: usize) -> usize {
+        let frame = [depth as u8; 1024];
+        std::hint::black_box(&frame);
+        if depth == 0 {
+            return 0;
+        }
+        recurse_kib(depth - 1) + 1
+    }
+
+    #[cfg(all(unix, feature = "stack-overflow-recovery"))]
+    #[test]
+    fn deep_recursion_depends_on_generator_stack() {
+        let recursing = |size| BoostedGenerator::<(), usize, ()>::new_with_stack(size, |_| recurse_kib(256));
+
+        let mut tiny = recursing(32 * 1024);
+        assert_eq!(tiny.resume_checked(()), Err(CoroutineError::StackOverflow));
+        assert_eq!(tiny.result(), Err(GeneratorError::Panicked { message: Some("Coroutine stack overflowed".to_string()) }));
+
+        let mut large = recursing(1024 * 1024);
+        assert_eq!(large.resume_checked(()), Ok(None));
+        assert_eq!(large.result(), Ok(256));
+    }
+
+    #[test]
+    fn size_hint_counts_down_announced_values() {
+        let mut gen = BoostedGenerator::new(|chan| chan.yield_all(0..100)).with_size_hint(100, Some(100));
+        assert_eq!(gen.size_hint(), (100, Some(100)));
+        gen.next();
+        assert_eq!(gen.size_hint(), (99, Some(99)));
+        let values = gen.collect::<Vec<_>>();
+        assert_eq!((values.len(), values.capacity()), (99, 99));
+
+        let values = BoringGenerator::new(|chan| chan.yield_all(0..100)).with_size_hint(100, None).collect::<Vec<_>>();
+        assert_eq!((values.len(), values.capacity()), (100, 100));
+
+        let mut gen = BoringGenerator::new(|chan| chan.yield_all(0..2)).with_size_hint(10, Some(10));
+        assert_eq!(gen.by_ref().count(), 2);
+        assert_eq!(gen.size_hint(), (0, Some(0)));
+        assert_eq!(BoringGenerator::new(|chan| chan.yield_val(1)).size_hint(), (0, None));
+    }
+
+    #[test]
+    fn boxed_generators_are_drained_uniformly() {
+        fn drain<'a>(gen: &mut dyn Generator<'a, Yield=String, Receive=()>) -> Vec<String> {
+            let mut events = Vec::new();
+            while let Some(event) = gen.resume(()) {
+                events.push(event);
+            }
+            events
+        }
+        let mut sources: Vec<Box<dyn Generator<Yield=String, Receive=()>>> = vec![
+            BoringGenerator::new(|chan| chan.yield_val("click".to_string())).boxed(),
+            BoostedGenerator::<_, (), ()>::new(|chan| chan.yield_all((1..3).map(|i| format!("tick {}", i)))).boxed(),
+        ];
+        let events = sources.iter_mut().flat_map(|gen| drain(gen.as_mut())).collect::<Vec<_>>();
+        assert_eq!(events, vec!["click", "tick 1", "tick 2"]);
+        assert!(sources.iter().all(|gen| gen.has_completed()));
+
+        let mut boxed: Box<dyn Generator<Yield=i32, Receive=()>> = Box::new(BoringGenerator::new(|chan| chan.yield_all(0..2)));
+        fn first<'a, G: Generator<'a, Receive=()>>(mut gen: G) -> Option<G::Yield> {
+            gen.resume(())
+        }
+        assert_eq!(first(&mut boxed), Some(0));
+        assert_eq!(boxed.resume(()), Some(1));
+        assert_eq!(boxed.resume(()), None);
+    }
+
+    #[test]
+    fn peekable_generator_keeps_result_reachable() {
+        let mut gen = BoostedGenerator::new(|chan| {
+            chan.yield_all(1..3);
+            "done"
+        }).peekable();
+        assert_eq!(gen.peek(), Some(&1));
+        assert_eq!(gen.peek(), Some(&1));
+        assert_eq!(gen.size_hint(), (1, None));
+        assert_eq!(gen.next(), Some(1));
+        *gen.peek_mut().unwrap() *= 10;
+        assert!(!gen.has_completed());
+        let (mut gen, error) = gen.try_result().err().unwrap();
+        assert_eq!(error, GeneratorError::NotCompleted);
+        assert_eq!(gen.next(), Some(20));
+        assert_eq!(gen.peek(), None);
+        assert_eq!(gen.peek(), None);
+        assert!(gen.has_completed());
+        assert_eq!(gen.next(), None);
+        assert_eq!(gen.result(), Ok("done"));
+
+        let mut gen = BoringGenerator::new_resulting(|chan| {
+            chan.yield_val('a');
+            1
+        }).peekable();
+        assert_eq!(gen.peek(), Some(&'a'));
+        assert_eq!(gen.by_ref().collect::<String>(), "a");
+        assert_eq!(gen.result(), Ok(1));
+    }
+
+    #[test]
+    fn yield_from_returns_result_of_sub_generator() {
+        let mut gen = BoostedGenerator::new(|chan| {
+            chan.yield_val(0);
+            chan.yield_from(BoostedGenerator::new(|sub| {
+                sub.yield_all(1..3);
+                "sub done"
+            }))
+        });
+        assert_eq!(gen.by_ref().collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(gen.result(), Ok(Ok("sub done")));
+    }
+
+    #[test]
+    fn yield_from_hands_panic_of_sub_generator_to_parent() {
+        let mut gen = BoostedGenerator::new(|chan| {
+            let sub_result = chan.yield_from(BoostedGenerator::<_, (), ()>::new(|sub| {
+                sub.yield_val(1);
+                panic!("sub failed")
+            }));
+            chan.yield_val(2);
+            sub_result
+        });
+        assert_eq!(gen.by_ref().collect::<Vec<_>>(), vec![1, 2]);
+        let error = gen.result().unwrap().unwrap_err();
+        assert!(matches!(&error, GeneratorError::Panicked { message: Some(message) } if message.ends_with("sub failed")), "{}", error);
+    }
+
+    #[test]
+    fn dropping_parent_drops_sub_generator() {
+        struct Flag(Rc<Cell<bool>>);
+        impl Drop for Flag {
+            fn drop(&mut self) {
+                self.0.set(true)
+            }
+        }
+        let dropped = Rc::new(Cell::new(false));
+        let flag = Flag(dropped.clone());
+        let mut gen = BoostedGenerator::new(move |chan| {
+            let result = chan.yield_from(BoostedGenerator::new(move |sub| {
+                let _flag = flag;
+                sub.yield_all(0..10);
+            }));
+            assert!(result.is_ok(), "parent resumed after drop");
+        });
+        assert_eq!(gen.next(), Some(0));
+        assert!(!dropped.get());
+        drop(gen);
+        assert!(dropped.get());
+    }
+
+    /// Walks a complete binary tree of [depth] levels in preorder, each node being yielded by a generator of its own which returns the size of its subtree
+    fn walk_tree(depth: u32, node: u32) -> BoostedGenerator<'static, u32, u32, ()> {
+        BoostedGenerator::new(move |chan| {
+            chan.yield_val(node);
+            if depth == 0 {
+                return 1;
+            }
+            let left = chan.yield_from(walk_tree(depth - 1, 2 * node)).unwrap();
+            let right = chan.yield_from(walk_tree(depth - 1, 2 * node + 1)).unwrap();
+            1 + left + right
+        })
+    }
+
+    #[test]
+    fn nested_yield_from_walks_tree() {
+        let mut tree = walk_tree(9, 1);
+        let mut nodes = Vec::new();
+        // preorder visits a left child right after its parent
+        let mut expected = vec![1];
+        for node in tree.by_ref() {
+            nodes.push(node);
+            if nodes.len() < 10 {
+                expected.push(2 * node);
+            }
+        }
+        assert_eq!(nodes.len(), 1023);
+        assert_eq!(nodes[..10], expected[..10]);
+        assert_eq!(nodes.iter().copied().collect::<HashSet<_>>().len(), 1023);
+        assert_eq!(tree.result(), Ok(1023));
+    }
+
+    #[test]
+    fn adapters_between_nested_generators_apply() {
+        let mut gen = BoostedGenerator::new(|chan| {
+            let inner = BoostedGenerator::new(|sub| sub.yield_from(walk_tree(1, 1)).unwrap());
+            chan.yield_from(inner.map_yield(|node| node * 10)).unwrap()
+        });
+        assert_eq!(gen.by_ref().collect::<Vec<_>>(), vec![10, 20, 30]);
+        assert_eq!(gen.result(), Ok(3));
+    }
+
+    #[test]
+    fn partly_resumed_generator_is_yielded_from() {
+        let mut inner = BoostedGenerator::new(|sub| sub.yield_from(walk_tree(2, 1)).unwrap());
+        assert_eq!(inner.next(), Some(1));
+        let mut gen = BoostedGenerator::new(move |chan| chan.yield_from(inner).unwrap());
+        assert_eq!(gen.by_ref().collect::<Vec<_>>(), vec![2, 4, 5, 3, 6, 7]);
+        assert_eq!(gen.result(), Ok(7));
+    }
+
+    #[test]
+    fn panic_of_innermost_generator_reaches_its_parent() {
+        let mut gen = BoostedGenerator::new(|chan| {
+            let middle = BoostedGenerator::new(|sub| {
+                let failed = sub.yield_from(BoostedGenerator::<_, (), ()>::new(|inner| {
+                    inner.yield_val(1);
+                    panic!("innermost failed")
+                }));
+                sub.yield_val(2);
+                failed.is_err()
+            });
+            chan.yield_from(middle).unwrap()
+        });
+        assert_eq!(gen.by_ref().collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(gen.result(), Ok(true));
+    }
+
+    #[test]
+    fn dropping_outermost_generator_drops_nested_ones() {
+        struct Count(Rc<Cell<usize>>);
+        impl Drop for Count {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1)
+            }
+        }
+        fn nested(depth: usize, dropped: Rc<Cell<usize>>) -> BoostedGenerator<'static, usize, (), ()> {
+            BoostedGenerator::new(move |chan| {
+                let _count = Count(dropped.clone());
+                if depth == 0 {
+                    chan.yield_all(0..);
+                } else {
+                    chan.yield_from(nested(depth - 1, dropped)).unwrap();
+                }
+            })
+        }
+        let dropped = Rc::new(Cell::new(0));
+        let mut gen = nested(20, dropped.clone());
+        assert_eq!(gen.by_ref().take(3).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(dropped.get(), 0);
+        drop(gen);
+        assert_eq!(dropped.get(), 21);
+    }
+
+    #[test]
+    fn initial_value_starts_receiving_generator() {
+        let mut gen = BoostedGenerator::new_receiving_with_initial("a", |chan, first: &str| {
+            let mut received = vec![first];
+            for i in 0..2 {
+                received.push(chan.yield_val(i));
+            }
+            received
+        });
+        assert_eq!(gen.resume("b"), Some(1));
+        assert_eq!(gen.resume("c"), None);
+        assert_eq!(gen.result(), Ok(vec!["a", "b", "c"]));
+    }
+
+    #[test]
+    fn values_sent_while_yielding_from_are_dropped() {
+        let mut gen = BoostedGenerator::new_receiving(|chan, first: &str| {
+            let mut received = vec![first];
+            received.push(chan.yield_val("own"));
+            chan.yield_from(BoostedGenerator::new(|sub| sub.yield_all(vec!["sub 1", "sub 2"].into_iter()))).unwrap();
+            received.push(chan.yield_val("own again"));
+            received
+        });
+        assert_eq!(gen.resume("a"), Some("own"));
+        assert_eq!(gen.resume("b"), Some("sub 1"));
+        assert_eq!(gen.resume("dropped"), Some("sub 2"));
+        assert_eq!(gen.resume("dropped too"), Some("own again"));
+        assert_eq!(gen.resume("c"), None);
+        assert_eq!(gen.result(), Ok(vec!["a", "b", "c"]));
+    }
+
+    #[test]
+    fn cancellation_while_yielding_from_reaches_parent() {
+        let mut gen = BoostedGenerator::new(|chan| {
+            chan.yield_from(IterGenerator::new(0..3)).unwrap();
+            if chan.is_cancelled() { "cancelled" } else { "finished" }
+        });
+        assert_eq!(gen.next(), Some(0));
+        gen.cancel();
+        assert_eq!(gen.by_ref().collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(gen.result(), Ok("cancelled"));
+    }
+
+    #[test]
+    fn empty_sub_generator_is_skipped() {
+        let mut gen = BoostedGenerator::new(|chan| {
+            chan.yield_from(IterGenerator::new(0..0)).unwrap();
+            chan.yield_from(BoringGenerator::new(|_| ())).unwrap();
+            chan.yield_val(1);
+            "done"
+        });
+        assert_eq!(gen.by_ref().collect::<Vec<_>>(), vec![1]);
+        assert_eq!(gen.stats().yields, 1);
+        assert_eq!(gen.result(), Ok("done"));
+    }
+
+    #[test]
+    fn yield_from_receiving_forwards_received_values() {
+        let mut gen = BoostedGenerator::new_receiving(|chan, first| {
+            let total = chan.yield_from_receiving(summing(), first).unwrap();
+            chan.yield_val(-total);
+            total * 2
+        });
+        assert_eq!(gen.resume(1), Some(1));
+        assert_eq!(gen.resume(2), Some(3));
+        assert_eq!(gen.resume(4), Some(7));
+        assert_eq!(gen.resume(0), Some(-7));
+        assert_eq!(gen.resume(0), None);
+        assert_eq!(gen.result(), Ok(14));
+    }
+
+    #[test]
+    fn buffered_values_are_yielded_once() {
+        let mut boosted = BoostedGenerator::new(|chan| {
+            chan.yield_all_buffered::<4>(0..10);
+            "done"
+        });
+        assert_eq!(boosted.by_ref().collect::<Vec<_>>(), (0..10).collect::<Vec<_>>());
+        assert_eq!(boosted.result(), Ok("done"));
+
+        let boring = BoringGenerator::new(|chan| chan.yield_all_buffered::<1>("abc".chars()));
+        assert_eq!(boring.collect::<String>(), "abc");
+
+        let value = Rc::new(());
+        let shared = value.clone();
+        let mut dropped = BoostedGenerator::new(move |chan| chan.yield_all_buffered::<4>(std::iter::repeat_n(shared, 10)));
+        assert_eq!(dropped.by_ref().take(5).count(), 5);
+        // the rest of the second ring, the value waiting for the third one and the one left in the iterator
+        assert_eq!(Rc::strong_count(&value), 1 + 3 + 1 + 1);
+        drop(dropped);
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+
+    #[test]
+    fn yield_all_results_stops_at_first_error() {
+        // returns the yielded values and the outcome along with the number of items left
+        fn yielded(items: Vec<Result<i32, &'static str>>) -> (Vec<i32>, Result<(), &'static str>, usize) {
+            let mut items = items.into_iter();
+            let mut gen = BoostedGenerator::new(move |chan| {
+                let outcome = chan.yield_all_results(&mut items);
+                (outcome, items.len())
+            });
+            let values = gen.by_ref().collect();
+            let (outcome, left) = gen.result().unwrap();
+            (values, outcome, left)
+        }
+        assert_eq!(yielded(vec![Ok(1), Ok(2)]), (vec![1, 2], Ok(()), 0));
+        assert_eq!(yielded(vec![Err("first"), Ok(2)]), (vec![], Err("first"), 1));
+        assert_eq!(yielded(vec![Ok(1), Err("second"), Ok(3), Err("fourth")]), (vec![1], Err("second"), 2));
+    }
+
+    #[test]
+    fn iterators_are_wrapped_as_generators() {
+        let mut boring = BoringGenerator::from_iter("ab".chars());
+        assert_eq!(boring.by_ref().collect::<String>(), "ab");
+        assert!(boring.has_completed());
+        assert_eq!(boring.stats().resumes, 3);
+
+        let mut stackless = IterGenerator::from(vec![1, 2, 3].into_iter());
+        assert_eq!(stackless.size_hint(), (3, Some(3)));
+        assert_eq!(stackless.resume(()), Some(1));
+        assert!(!stackless.has_completed());
+        let (mut stackless, error) = stackless.try_result().err().unwrap();
+        assert_eq!(error, GeneratorError::NotCompleted);
+        assert_eq!(stackless.by_ref().collect::<Vec<_>>(), vec![2, 3]);
+        assert!(stackless.has_completed());
+        assert_eq!(stackless.next(), None);
+        assert_eq!(stackless.result(), Ok(()));
+
+        let mut gen = BoostedGenerator::new(|chan| {
+            chan.yield_val(0);
+            chan.yield_from(IterGenerator::new(1..3))
+        });
+        assert_eq!(gen.by_ref().collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(gen.result(), Ok(Ok(())));
+    }
+
+    #[test]
+    fn adapted_iterators_are_delegated_to() {
+        let mut gen = BoostedGenerator::new(|chan| {
+            let stackless = chan.yield_from((1..3).map(|i| i * 10).into_generator());
+            let coroutine = chan.yield_from(vec![30, 40].into_iter().into_coroutine_generator());
+            (stackless, coroutine)
+        });
+        assert_eq!(gen.by_ref().collect::<Vec<_>>(), vec![10, 20, 30, 40]);
+        assert_eq!(gen.result(), Ok((Ok(()), Ok(()))));
+
+        let mut sources: Vec<Box<dyn Generator<Yield=char, Receive=()>>> = vec![Box::new("ab".chars().into_generator()), "c".chars().into_coroutine_generator().boxed()];
+        let drained = sources.iter_mut().flat_map(|gen| std::iter::from_fn(move || gen.resume(()))).collect::<String>();
+        assert_eq!(drained, "abc");
+    }
+
+    #[test]
+    fn macro_generators_yield_and_return() {
+        let words = vec!["a", "bc", "def"];
+        let mut lengths = crate::generator! {
+            let mut total = 0;
+            for word in words {
+                total += word.len();
+                yield_!(word.len());
+            }
+            ret_!(total)
+        };
+        assert_eq!(lengths.by_ref().collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(lengths.result(), Ok(6));
+
+        let mut unit = crate::generator! {
+            yield_!('x');
+            ret_!();
+        };
+        assert_eq!(unit.by_ref().collect::<String>(), "x");
+        assert_eq!(unit.result(), Ok(()));
+
+        let mut echo = crate::generator!(|first: String| {
+            let mut received = vec![first];
+            while received.len() < 3 {
+                let next = yield_!(received.len());
+                received.push(next);
+            }
+            received.join(" ")
+        });
+        assert_eq!(echo.resume("a".to_string()), Some(1));
+        assert_eq!(echo.resume("b".to_string()), Some(2));
+        assert_eq!(echo.resume("c".to_string()), None);
+        assert_eq!(echo.result(), Ok("a b c".to_string()));
+    }
+
+    #[test]
+    fn channel_helpers_are_shared_by_generator_kinds() {
+        fn emit_header<'a>(chan: &mut impl GeneratorChannel<'a, Yield=String, Receive=()>, title: &str) {
+            chan.yield_val(format!("# {}", title));
+            chan.yield_all(std::iter::once(String::new()));
+        }
+        fn emit_footer(chan: &mut dyn GeneratorChannel<Yield=String, Receive=()>, lines: usize) {
+            chan.yield_batch(vec![String::new(), format!("{} lines", lines)]);
+        }
+        let boring = BoringGenerator::new(|chan| {
+            emit_header(chan, "boring");
+            emit_footer(chan, 0);
+        });
+        assert_eq!(boring.collect::<Vec<_>>(), vec!["# boring", "", "", "0 lines"]);
+
+        let mut boosted = BoostedGenerator::new(|chan| {
+            emit_header(chan, "boosted");
+            chan.yield_val("content".to_string());
+            emit_footer(chan, 1);
+            1
+        });
+        assert_eq!(boosted.by_ref().collect::<Vec<_>>(), vec!["# boosted", "", "content", "", "1 lines"]);
+        assert_eq!(boosted.result(), Ok(1));
+    }
+
+    #[test]
+    fn generators_receiving_defaults_are_iterated() {
+        let mut commands = BoostedGenerator::new_receiving(|chan, mut command: Option<u8>| {
+            let mut value = 0u8;
+            while value < 5 {
+                value = command.unwrap_or(value + 1);
+                command = chan.yield_val(value);
+            }
+            value
+        });
+        assert_eq!(commands.resume(Some(3)), Some(3));
+        let mut values = Vec::new();
+        for value in &mut commands {
+            values.push(value);
+        }
+        assert_eq!(values, vec![4, 5]);
+        assert!(commands.has_completed());
+        assert_eq!(commands.next(), None);
+        assert_eq!(commands.next(), None);
+        assert_eq!(commands.result(), Ok(5));
+    }
+
+    #[test]
+    fn generator_iterator_takes_inputs_from_stateful_source() {
+        let mut inputs = vec![1, 2, 3, 0, 5];
+        let mut drain = inputs.drain(..);
+        let mut sums = summing().create_iter(|| drain.next().unwrap());
+        assert_eq!(sums.by_ref().collect::<Vec<_>>(), vec![1, 3, 6]);
+        assert_eq!(sums.next(), None);
+        assert_eq!(sums.result(), Ok(6));
+        assert_eq!(drain.collect::<Vec<_>>(), vec![5]);
+
+        let mut inputs = vec![4, 4];
+        let mut source = inputs.drain(..);
+        let mut sums = summing().create_iter(move || source.next().unwrap_or(0));
+        assert_eq!(sums.next(), Some(4));
+        let mut gen = sums.into_inner();
+        assert_eq!(gen.resume(0), None);
+        assert_eq!(gen.result(), Ok(4));
+    }
+
+    #[test]
+    fn drained_generator_returns_result() {
+        assert_eq!(counting(0..5).drain(), Ok(5));
+        let mut partial = counting(0..5);
+        assert_eq!(partial.next(), Some(0));
+        let mut rest = Vec::new();
+        assert_eq!(partial.drain_with(|v| rest.push(v)), Ok(5));
+        assert_eq!(rest, vec![1, 2, 3, 4]);
+        assert_eq!(counting(0..5).map_yield(|v| v * 2).take_yields(2).drain(), Ok(2));
+        assert_eq!(BoringGenerator::new(|chan| chan.yield_val(1)).drain(), Ok(()));
+    }
+
+    #[test]
+    fn drain_returns_panic_of_generator() {
+        let mut seen = Vec::new();
+        let failing = BoostedGenerator::<i32, i32, ()>::new(|chan| {
+            chan.yield_all(0..2);
+            panic!("failed while drained")
+        });
+        let error = failing.drain_with(|v| seen.push(v)).unwrap_err();
+        assert_eq!(error, GeneratorError::Panicked { message: Some("failed while drained".to_string()) });
+        assert_eq!(seen, vec![0, 1]);
+
+        let failing = BoringGenerator::<i32, i32>::new_resulting(|_| panic!("failed right away"));
+        assert_eq!(failing.drain(), Err(GeneratorError::Panicked { message: Some("failed right away".to_string()) }));
+    }
+
+    #[test]
+    fn collected_values_come_with_result() {
+        let gen = BoostedGenerator::new(|chan| {
+            chan.yield_all(0..100);
+            "done"
+        }).with_size_hint(100, Some(100));
+        let (values, result): (Vec<_>, _) = gen.collect_with_result();
+        assert_eq!((values.len(), values.capacity()), (100, 100));
+        assert_eq!(result, Ok("done"));
+
+        let mut words = BoringGenerator::new_resulting(|chan| {
+            chan.yield_all("b a b c a".split(' '));
+            5
+        });
+        assert_eq!(words.next(), Some("b"));
+        let (unique, result): (HashSet<_>, _) = words.collect_with_result();
+        assert_eq!(unique, HashSet::from(["a", "b", "c"]));
+        assert_eq!(result, Ok(5));
+    }
+
+    #[test]
+    fn collecting_keeps_values_before_panic() {
+        let failing = BoostedGenerator::<i32, (), ()>::new(|chan| {
+            chan.yield_all(0..3);
+            panic!("failed while collected")
+        });
+        let (values, result): (Vec<_>, _) = failing.collect_with_result();
+        assert_eq!(values, vec![0, 1, 2]);
+        assert_eq!(result, Err(GeneratorError::Panicked { message: Some("failed while collected".to_string()) }));
+    }
+
+    #[test]
+    fn folded_generator_keeps_result() {
+        assert_eq!(counting(1..11).fold_yields(0, |sum, v| sum + v), (55, Ok(10)));
+        assert_eq!(counting(1..11).map_yield(|v| v % 2).fold_yields(0, |odd, v| odd + v), (5, Ok(10)));
+
+        let (sum, result) = counting(1..).try_fold_yields(0, |sum, v| if sum + v > 10 { ControlFlow::Break(sum) } else { ControlFlow::Continue(sum + v) });
+        assert_eq!(sum, ControlFlow::Break(10));
+        assert_eq!(result, Ok(5));
+        let (sum, result) = counting(1..5).try_fold_yields(0, |sum, v| ControlFlow::Continue(sum + v));
+        assert_eq!((sum, result), (ControlFlow::Continue(10), Ok(4)));
+
+        let uncancellable = BoringGenerator::new_resulting(|chan| {
+            chan.yield_all(0..);
+            "never"
+        });
+        let (first, result) = uncancellable.try_fold_yields(None, |_, v| ControlFlow::Break(Some(v)));
+        assert_eq!((first, result), (ControlFlow::Break(Some(0)), Err(GeneratorError::NotCompleted)));
+    }
+
+    #[test]
+    fn try_generator_result_carries_panic() {
+        let mut gen = BoostedGenerator::new_try(|chan| {
+            chan.yield_val(1);
+            chan.yield_val(2);
+            "x".parse::<i32>().map(|_| "parsed").expect("parse error")
+        });
+        assert_eq!((&mut gen).collect::<Vec<_>>(), vec![1, 2]);
+        let panic = gen.result().unwrap().unwrap_err();
+        assert!(panic.message.unwrap().starts_with("parse error"));
+    }
+
+    #[test]
+    fn generators_are_usable_after_caught_panic() {
+        // the routines may capture mutable references, so the generators are only unwind safe by assertion
+        let mut boosted = BoostedGenerator::new(|chan| {
+            chan.yield_val(1);
+            panic!("failed")
+        });
+        let mut boring = BoringGenerator::new(|chan| chan.yield_val(1));
+        assert!(catch_unwind(AssertUnwindSafe(|| for x in &mut boosted { assert_eq!(x, 1) })).is_err());
+        assert!(catch_unwind(AssertUnwindSafe(|| boring.by_ref().count())).is_ok());
+        assert!(boosted.has_completed());
+        assert_eq!(boosted.result(), Err(GeneratorError::Panicked { message: Some("failed".to_string()) }));
+        assert!(boring.has_completed());
+    }
+
+    #[test]
+    fn unwind_safe_generators_are_caught_without_assertion() {
+        let mut boosted = BoostedGenerator::new_unwind_safe(|chan| {
+            chan.yield_val(1);
+            panic!("failed")
+        });
+        let mut boring = BoringGenerator::new_unwind_safe(|chan| chan.yield_val(1));
+        let mut receiving = BoostedGenerator::<u32, u32, u32>::new_receiving_unwind_safe(|chan, first| chan.yield_val(first) * 2);
+        assert!(catch_unwind(move || for x in &mut boosted { assert_eq!(x, 1) }).is_err());
+        assert_eq!(catch_unwind(move || boring.by_ref().count()).ok(), Some(1));
+        let result = catch_unwind(move || {
+            assert_eq!(receiving.resume(3), Some(3));
+            assert_eq!(receiving.resume(4), None);
+            receiving.result()
+        });
+        assert_eq!(result.ok(), Some(Ok(8)));
+    }
+
+    #[test]
+    fn spawned_generator_feeds_receiver() {
+        let generator = unsafe {
+            SendGenerator::new(BoostedGenerator::new(|chan| {
+                chan.yield_all(0..100u32);
+                "sent"
+            }))
+        };
+        let (receiver, handle) = generator.spawn_onto_thread(4);
+        assert_eq!(receiver.iter().sum::<u32>(), 4950);
+        assert_eq!(handle.join(), Ok("sent"));
+    }
+
+    #[test]
+    fn dropped_receiver_cancels_spawned_generator() {
+        let generator = unsafe {
+            SendGenerator::new(BoostedGenerator::<u32, u32, ()>::new(|chan| {
+                let mut sent = 0;
+                while !chan.is_cancelled() {
+                    chan.yield_val(sent);
+                    sent += 1;
+                }
+                sent
+            }))
+        };
+        // without capacity each send waits for its value to be received, so the fourth one fails
+        let (receiver, handle) = generator.spawn_onto_thread(0);
+        assert_eq!(receiver.iter().take(3).collect::<Vec<_>>(), vec![0, 1, 2]);
+        drop(receiver);
+        assert_eq!(handle.join(), Ok(4));
+    }
+
+    #[test]
+    fn spawned_generator_panic_is_joined() {
+        let generator = unsafe {
+            SendGenerator::new(BoostedGenerator::<u32, (), ()>::new(|chan| {
+                chan.yield_val(1);
+                panic!("thread failed")
+            }))
+        };
+        let (receiver, handle) = generator.spawn_onto_thread(1);
+        assert_eq!(receiver.iter().collect::<Vec<_>>(), vec![1]);
+        assert_eq!(handle.join(), Err(GeneratorError::Panicked { message: Some("thread failed".to_string()) }));
+    }
+}
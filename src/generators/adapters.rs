@@ -0,0 +1,1458 @@
+//! Adapters created by [GeneratorExt](super::GeneratorExt) and the functions combining several generators, all of them keep the results of the adapted generators reachable
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::iter::FusedIterator;
+use std::panic::{AssertUnwindSafe, catch_unwind, resume_unwind};
+use std::rc::Rc;
+
+use super::{BoostedGenerator, Generator, GeneratorError, ResultingGenerator};
+
+/// Generator mapping the values yielded by another one, see [GeneratorExt::map_yield]
+pub struct MapYield<G, F>(G, F);
+
+/// Generator skipping the values yielded by another one which don't satisfy a predicate, see [GeneratorExt::filter_yield]
+pub struct FilterYield<G, P>(G, P);
+
+/// Generator yielding a limited number of values of another one, see [GeneratorExt::take_yields]
+/// The second field counts the values still to be yielded
+pub struct TakeYields<G>(G, usize);
+
+/// Generator yielding the values of another one while they satisfy a predicate, see [GeneratorExt::take_yields_while]
+/// The third field tells whether a value has been rejected
+pub struct TakeYieldsWhile<G, P>(G, P, bool);
+
+/// Generator pairing the values of two others, see [zip]
+/// The third field buffers a value of the first generator which has no partner since the second one completed, the fourth tells whether either one completed
+pub struct Zipped<A: Iterator, B>(A, B, Option<A::Item>, bool);
+
+/// Pairs the values of [a] and [b] resuming both in lockstep until either completes, the results of both are available by [Zipped::finish]
+/// [a] is resumed first on each step, its value is kept in case [b] completes instead of yielding a partner (see [Zipped::take_unpaired])
+pub fn zip<'a, A, B>(a: A, b: B) -> Zipped<A, B>
+    where A: Generator<'a, Receive=()> + Iterator<Item=<A as Generator<'a>>::Yield>, B: Generator<'a, Receive=()> + Iterator<Item=<B as Generator<'a>>::Yield> {
+    Zipped(a, b, None, false)
+}
+
+/// Generator yielding the values of one generator and then the ones of another, see [GeneratorExt::chain_gen]
+/// [R] is the return type of the first generator, which is kept while the second one runs
+/// The second field tells whether the chain has been cancelled, so the second generator is cancelled right after it has been created
+pub struct ChainGen<A, R, B, F>(ChainStage<A, R, B, F>, Cell<bool>);
+
+enum ChainStage<A, R, B, F> {
+    /// Running the first generator, the second one is created by the factory once the first one returned
+    First(A, F),
+    /// Running the second generator keeping the result of the first one
+    Second(R, B),
+    /// The first generator failed, so the second one has never been created
+    Failed(GeneratorError),
+}
+
+/// Generator yielding the values of the generators another one yields, see [GeneratorExt::flatten_gen]
+/// The second field is the inner generator currently run, the third counts the inner generators taken so far and the fourth is the position of the last one which panicked
+pub struct FlattenGen<G: Iterator>(G, Option<G::Item>, usize, Option<usize>);
+
+/// Generator pairing the values yielded by another one with their position, see [GeneratorExt::enumerate_yields]
+pub struct EnumerateYields<G>(G, usize);
+
+/// Generator mapping the values yielded by another one with access to a state, see [GeneratorExt::scan_yields]
+/// The fourth field tells whether the mapping function ended the yields
+pub struct ScanYields<G, S, F>(G, S, F, bool);
+
+/// Generator yielding the values of another one in batches, see [GeneratorExt::chunks]
+/// The third field tells whether the last batch has been yielded
+pub struct Chunks<G>(G, usize, bool);
+
+/// Generator feeding the values of one generator to a receiving one, see [pipe]
+/// The third field tells whether either generator completed
+pub struct Piped<'a, A: Iterator, Y2: 'static, R2: 'static>(A, BoostedGenerator<'a, Y2, R2, A::Item>, bool) where A::Item: 'a;
+
+/// Resumes [b] with each value [a] yields, yielding the values [b] yields in turn until either completes
+/// [a] is only resumed when [b] needs its next value, so no value is buffered in between. If [a] completes first, [b] is not resumed anymore (see [Piped::cancel_receiver]),
+/// if [b] completes first, the rest of [a] is abandoned
+pub fn pipe<'a, A, Y2: 'static, R2: 'static>(a: A, b: BoostedGenerator<'a, Y2, R2, A::Item>) -> Piped<'a, A, Y2, R2>
+    where A: Generator<'a, Receive=()> + Iterator<Item=<A as Generator<'a>>::Yield> {
+    Piped(a, b, false)
+}
+
+/// Generator yielding the values of several others in turn, see [round_robin]
+/// The second field is the position of the generator to be resumed next
+pub struct RoundRobin<G>(Vec<(G, SlotState)>, usize);
+
+/// One of the two handles sharing the values of a generator, see [GeneratorExt::tee]
+/// The second field tells which of both handles this is
+pub struct TeeHandle<G: Iterator>(Rc<RefCell<TeeBuffer<G>>>, usize);
+
+/// State shared by both [TeeHandle]s: the generator, which is gone once its result has been taken,
+/// the values the lagging handle has not taken yet and the handle they are buffered for
+struct TeeBuffer<G: Iterator>(Option<G>, VecDeque<G::Item>, usize);
+
+/// State of a generator interleaved by [RoundRobin]
+enum SlotState {
+    Live,
+    /// The generator returned or panicked, either way it keeps the way it completed for its result
+    Completed,
+}
+
+/// Resumes each generator of [generators] in turn skipping the completed ones until all of them completed
+/// A panic of a generator does not end the others but is reported as its result, see [RoundRobin::finish]
+pub fn round_robin<'a, G: ResultingGenerator<'a, Receive=()>>(generators: Vec<G>) -> RoundRobin<G> {
+    RoundRobin(generators.into_iter().map(|generator| (generator, SlotState::Live)).collect(), 0)
+}
+
+impl<G, F> MapYield<G, F> {
+    pub(crate) fn new(generator: G, f: F) -> Self {
+        MapYield(generator, f)
+    }
+}
+
+impl<'a, G: Generator<'a>, U: 'static, F: FnMut(G::Yield) -> U> Generator<'a> for MapYield<G, F> {
+    type Yield = U;
+    type Receive = G::Receive;
+
+    fn has_completed(&self) -> bool {
+        self.0.has_completed()
+    }
+
+    fn resume(&mut self, send: G::Receive) -> Option<U> {
+        self.0.resume(send).map(&mut self.1)
+    }
+
+    fn cancel(&self) {
+        self.0.cancel()
+    }
+}
+
+impl<'a, G: ResultingGenerator<'a>, U: 'static, F: FnMut(G::Yield) -> U> ResultingGenerator<'a> for MapYield<G, F> {
+    type Return = G::Return;
+
+    fn result(self) -> Result<G::Return, GeneratorError> {
+        self.0.result()
+    }
+
+    fn try_result(self) -> Result<G::Return, (Self, GeneratorError)> {
+        let MapYield(generator, f) = self;
+        generator.try_result().map_err(|(generator, e)| (MapYield(generator, f), e))
+    }
+}
+
+impl<G: Iterator, U, F: FnMut(G::Item) -> U> Iterator for MapYield<G, F> {
+    type Item = U;
+
+    fn next(&mut self) -> Option<U> {
+        self.0.next().map(&mut self.1)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<G, P> FilterYield<G, P> {
+    pub(crate) fn new(generator: G, pred: P) -> Self {
+        FilterYield(generator, pred)
+    }
+}
+
+impl<'a, G: Generator<'a>, P: FnMut(&G::Yield) -> bool> Generator<'a> for FilterYield<G, P> where G::Receive: Clone {
+    type Yield = G::Yield;
+    type Receive = G::Receive;
+
+    fn has_completed(&self) -> bool {
+        self.0.has_completed()
+    }
+
+    fn resume(&mut self, send: G::Receive) -> Option<G::Yield> {
+        loop {
+            let val = self.0.resume(send.clone())?;
+            if (self.1)(&val) {
+                return Some(val);
+            }
+        }
+    }
+
+    fn cancel(&self) {
+        self.0.cancel()
+    }
+}
+
+impl<'a, G: ResultingGenerator<'a>, P: FnMut(&G::Yield) -> bool> ResultingGenerator<'a> for FilterYield<G, P> where G::Receive: Clone {
+    type Return = G::Return;
+
+    fn result(self) -> Result<G::Return, GeneratorError> {
+        self.0.result()
+    }
+
+    fn try_result(self) -> Result<G::Return, (Self, GeneratorError)> {
+        let FilterYield(generator, pred) = self;
+        generator.try_result().map_err(|(generator, e)| (FilterYield(generator, pred), e))
+    }
+}
+
+impl<G: Iterator, P: FnMut(&G::Item) -> bool> Iterator for FilterYield<G, P> {
+    type Item = G::Item;
+
+    fn next(&mut self) -> Option<G::Item> {
+        let pred = &mut self.1;
+        self.0.find(|val| pred(val))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.0.size_hint().1)
+    }
+}
+
+impl<G> TakeYields<G> {
+    pub(crate) fn new(generator: G, n: usize) -> Self {
+        TakeYields(generator, n)
+    }
+}
+
+impl<'a, G: Generator<'a>> Generator<'a> for TakeYields<G> {
+    type Yield = G::Yield;
+    type Receive = G::Receive;
+
+    fn has_completed(&self) -> bool {
+        self.1 == 0 || self.0.has_completed()
+    }
+
+    fn resume(&mut self, send: G::Receive) -> Option<G::Yield> {
+        if self.1 == 0 {
+            return None;
+        }
+        let val = self.0.resume(send);
+        self.1 = if val.is_some() { self.1 - 1 } else { 0 };
+        val
+    }
+
+    fn cancel(&self) {
+        self.0.cancel()
+    }
+}
+
+impl<'a, G: ResultingGenerator<'a, Receive=()>> TakeYields<G> {
+    /// [Cancels](Generator::cancel) the generator and [drains](ResultingGenerator::drain) the values beyond the limit to return its result
+    /// A generator ignoring the cancellation is still drained completely, so an endless one doesn't return then
+    pub fn finish(self) -> Result<G::Return, GeneratorError> {
+        self.0.cancel();
+        self.0.drain()
+    }
+}
+
+impl<'a, G: ResultingGenerator<'a, Receive=()>> ResultingGenerator<'a> for TakeYields<G> {
+    type Return = G::Return;
+
+    /// Same as [TakeYields::finish]
+    fn result(self) -> Result<G::Return, GeneratorError> {
+        self.finish()
+    }
+
+    /// Unlike [result] the generator is not run to completion, so it is handed back with [GeneratorError::NotCompleted] while it is still running
+    fn try_result(self) -> Result<G::Return, (Self, GeneratorError)> {
+        let TakeYields(generator, limit) = self;
+        generator.try_result().map_err(|(generator, e)| (TakeYields(generator, limit), e))
+    }
+}
+
+impl<G: Iterator> Iterator for TakeYields<G> {
+    type Item = G::Item;
+
+    fn next(&mut self) -> Option<G::Item> {
+        if self.1 == 0 {
+            return None;
+        }
+        let val = self.0.next();
+        self.1 = if val.is_some() { self.1 - 1 } else { 0 };
+        val
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.0.size_hint();
+        (lower.min(self.1), Some(upper.map_or(self.1, |upper| upper.min(self.1))))
+    }
+}
+
+impl<G, P> TakeYieldsWhile<G, P> {
+    pub(crate) fn new(generator: G, pred: P) -> Self {
+        TakeYieldsWhile(generator, pred, false)
+    }
+}
+
+impl<'a, G: Generator<'a>, P: FnMut(&G::Yield) -> bool> Generator<'a> for TakeYieldsWhile<G, P> {
+    type Yield = G::Yield;
+    type Receive = G::Receive;
+
+    fn has_completed(&self) -> bool {
+        self.2 || self.0.has_completed()
+    }
+
+    fn resume(&mut self, send: G::Receive) -> Option<G::Yield> {
+        if self.2 {
+            return None;
+        }
+        let val = self.0.resume(send)?;
+        self.2 = !(self.1)(&val);
+        if self.2 { None } else { Some(val) }
+    }
+
+    fn cancel(&self) {
+        self.0.cancel()
+    }
+}
+
+impl<'a, G: ResultingGenerator<'a, Receive=()>, P: FnMut(&G::Yield) -> bool> TakeYieldsWhile<G, P> {
+    /// Like [TakeYields::finish] for the values from the rejected one on
+    pub fn finish(self) -> Result<G::Return, GeneratorError> {
+        self.0.cancel();
+        self.0.drain()
+    }
+}
+
+impl<'a, G: ResultingGenerator<'a, Receive=()>, P: FnMut(&G::Yield) -> bool> ResultingGenerator<'a> for TakeYieldsWhile<G, P> {
+    type Return = G::Return;
+
+    /// Same as [TakeYieldsWhile::finish]
+    fn result(self) -> Result<G::Return, GeneratorError> {
+        self.finish()
+    }
+
+    /// Doesn't run the rest of the generator, see [TakeYields::try_result]
+    fn try_result(self) -> Result<G::Return, (Self, GeneratorError)> {
+        let TakeYieldsWhile(generator, pred, rejected) = self;
+        generator.try_result().map_err(|(generator, e)| (TakeYieldsWhile(generator, pred, rejected), e))
+    }
+}
+
+impl<G: Iterator, P: FnMut(&G::Item) -> bool> Iterator for TakeYieldsWhile<G, P> {
+    type Item = G::Item;
+
+    fn next(&mut self) -> Option<G::Item> {
+        if self.2 {
+            return None;
+        }
+        let val = self.0.next()?;
+        self.2 = !(self.1)(&val);
+        if self.2 { None } else { Some(val) }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.2 { (0, Some(0)) } else { (0, self.0.size_hint().1) }
+    }
+}
+
+impl<'a, A, B> Zipped<A, B>
+    where A: Generator<'a, Receive=()> + Iterator<Item=<A as Generator<'a>>::Yield>, B: Generator<'a, Receive=()> + Iterator<Item=<B as Generator<'a>>::Yield> {
+    /// Hands out the value the first generator yielded in the step the second one completed in
+    pub fn take_unpaired(&mut self) -> Option<A::Item> {
+        self.2.take()
+    }
+}
+
+impl<'a, A, B> Zipped<A, B>
+    where A: ResultingGenerator<'a, Receive=()> + Iterator<Item=<A as Generator<'a>>::Yield>, B: ResultingGenerator<'a, Receive=()> + Iterator<Item=<B as Generator<'a>>::Yield> {
+    /// Drains the values of both generators which have not been paired, a panic of either one only costs its result, which is None then
+    pub fn finish(self) -> (Option<A::Return>, Option<B::Return>) {
+        (self.0.drain().ok(), self.1.drain().ok())
+    }
+}
+
+impl<'a, A, B> Generator<'a> for Zipped<A, B>
+    where A: Generator<'a, Receive=()> + Iterator<Item=<A as Generator<'a>>::Yield>, B: Generator<'a, Receive=()> + Iterator<Item=<B as Generator<'a>>::Yield> {
+    type Yield = (A::Yield, B::Yield);
+    type Receive = ();
+
+    /// A panic of either generator completes the pair as well
+    fn has_completed(&self) -> bool {
+        self.3 || self.0.has_completed() || self.1.has_completed()
+    }
+
+    fn resume(&mut self, _: ()) -> Option<Self::Yield> {
+        self.next()
+    }
+
+    fn cancel(&self) {
+        self.0.cancel();
+        self.1.cancel();
+    }
+}
+
+impl<'a, A, B> ResultingGenerator<'a> for Zipped<A, B>
+    where A: ResultingGenerator<'a, Receive=()> + Iterator<Item=<A as Generator<'a>>::Yield>, B: ResultingGenerator<'a, Receive=()> + Iterator<Item=<B as Generator<'a>>::Yield> {
+    type Return = (Option<A::Return>, Option<B::Return>);
+
+    /// Same as [Zipped::finish] once the pair has completed, which runs the rest of the generator that has not completed yet
+    fn result(self) -> Result<Self::Return, GeneratorError> {
+        if self.has_completed() { Ok(self.finish()) } else { Err(GeneratorError::NotCompleted) }
+    }
+
+    fn try_result(self) -> Result<Self::Return, (Self, GeneratorError)> {
+        if self.has_completed() { Ok(self.finish()) } else { Err((self, GeneratorError::NotCompleted)) }
+    }
+}
+
+impl<A: Iterator, B: Iterator> Iterator for Zipped<A, B> {
+    type Item = (A::Item, B::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.3 {
+            return None;
+        }
+        let pair = match self.0.next() {
+            Some(a) => match self.1.next() {
+                Some(b) => Some((a, b)),
+                None => {
+                    self.2 = Some(a);
+                    None
+                }
+            },
+            None => None
+        };
+        self.3 = pair.is_none();
+        pair
+    }
+}
+
+impl<A, R, B, F> ChainGen<A, R, B, F> {
+    pub(crate) fn new(a: A, factory: F) -> Self {
+        ChainGen(ChainStage::First(a, factory), Cell::new(false))
+    }
+}
+
+impl<'a, A, B, F> ChainGen<A, A::Return, B, F>
+    where A: ResultingGenerator<'a>, B: Generator<'a>, F: FnOnce() -> B {
+    /// Replaces the first generator which has completed by the second one
+    fn start_second(&mut self) {
+        // the placeholder is replaced right away
+        self.0 = match std::mem::replace(&mut self.0, ChainStage::Failed(GeneratorError::NotCompleted)) {
+            ChainStage::First(a, factory) => match a.result() {
+                Ok(r) => {
+                    let b = factory();
+                    if self.1.get() {
+                        b.cancel();
+                    }
+                    ChainStage::Second(r, b)
+                }
+                Err(e) => ChainStage::Failed(e),
+            },
+            stage => stage
+        }
+    }
+    /// Moves on from a first generator which completed without the chain noticing, i.e. by panicking while the chain resumed it
+    fn settle_first(&mut self) {
+        if let ChainStage::First(a, _) = &self.0 {
+            if a.has_completed() {
+                self.start_second();
+            }
+        }
+    }
+}
+
+impl<'a, A, B, F> Generator<'a> for ChainGen<A, A::Return, B, F>
+    where A: ResultingGenerator<'a, Receive=()>, B: Generator<'a, Yield=A::Yield, Receive=()>, F: FnOnce() -> B {
+    type Yield = A::Yield;
+    type Receive = ();
+
+    fn has_completed(&self) -> bool {
+        match &self.0 {
+            ChainStage::First(a, _) => a.has_completed(),
+            ChainStage::Second(_, b) => b.has_completed(),
+            ChainStage::Failed(_) => true,
+        }
+    }
+
+    fn resume(&mut self, _: ()) -> Option<A::Yield> {
+        loop {
+            match &mut self.0 {
+                ChainStage::First(a, _) => match a.resume(()) {
+                    Some(val) => return Some(val),
+                    None => self.start_second()
+                },
+                ChainStage::Second(_, b) => return b.resume(()),
+                ChainStage::Failed(_) => return None,
+            }
+        }
+    }
+
+    fn cancel(&self) {
+        self.1.set(true);
+        match &self.0 {
+            ChainStage::First(a, _) => a.cancel(),
+            ChainStage::Second(_, b) => b.cancel(),
+            ChainStage::Failed(_) => {}
+        }
+    }
+}
+
+impl<'a, A, B, F> ResultingGenerator<'a> for ChainGen<A, A::Return, B, F>
+    where A: ResultingGenerator<'a, Receive=()>, B: ResultingGenerator<'a, Yield=A::Yield, Receive=()>, F: FnOnce() -> B {
+    type Return = (A::Return, B::Return);
+
+    fn result(mut self) -> Result<Self::Return, GeneratorError> {
+        self.settle_first();
+        match self.0 {
+            ChainStage::First(..) => Err(GeneratorError::NotCompleted),
+            ChainStage::Second(r, b) => b.result().map(|rb| (r, rb)),
+            ChainStage::Failed(e) => Err(e),
+        }
+    }
+
+    fn try_result(mut self) -> Result<Self::Return, (Self, GeneratorError)> {
+        self.settle_first();
+        let ChainGen(stage, cancelled) = self;
+        match stage {
+            ChainStage::Second(r, b) => match b.try_result() {
+                Ok(rb) => Ok((r, rb)),
+                Err((b, e)) => Err((ChainGen(ChainStage::Second(r, b), cancelled), e)),
+            },
+            ChainStage::Failed(e) => Err((ChainGen(ChainStage::Failed(e.clone()), cancelled), e)),
+            first => Err((ChainGen(first, cancelled), GeneratorError::NotCompleted)),
+        }
+    }
+}
+
+impl<'a, A, R, B, F> Iterator for ChainGen<A, R, B, F>
+    where A: ResultingGenerator<'a, Receive=(), Return=R> + Iterator<Item=<A as Generator<'a>>::Yield>, B: Generator<'a, Yield=A::Yield, Receive=()>, F: FnOnce() -> B {
+    type Item = A::Item;
+
+    fn next(&mut self) -> Option<A::Item> {
+        self.resume(())
+    }
+}
+
+impl<'a, A, R, B, F> FusedIterator for ChainGen<A, R, B, F>
+    where A: ResultingGenerator<'a, Receive=(), Return=R> + Iterator<Item=<A as Generator<'a>>::Yield>, B: Generator<'a, Yield=A::Yield, Receive=()>, F: FnOnce() -> B {}
+
+impl<'a, G, I> Generator<'a> for FlattenGen<G>
+    where G: Generator<'a, Yield=I, Receive=()> + Iterator<Item=I>, I: Generator<'a, Receive=()> + Iterator<Item=<I as Generator<'a>>::Yield> {
+    type Yield = I::Yield;
+    type Receive = ();
+
+    fn has_completed(&self) -> bool {
+        self.1.is_none() && self.0.has_completed()
+    }
+
+    fn resume(&mut self, _: ()) -> Option<I::Yield> {
+        self.next()
+    }
+
+    /// Cancels the inner generator currently run along with the outer one
+    fn cancel(&self) {
+        if let Some(inner) = &self.1 {
+            inner.cancel();
+        }
+        self.0.cancel();
+    }
+}
+
+impl<G: Iterator> Iterator for FlattenGen<G> where G::Item: Iterator {
+    type Item = <G::Item as Iterator>::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(inner) = &mut self.1 {
+                match catch_unwind(AssertUnwindSafe(|| inner.next())) {
+                    Ok(Some(val)) => return Some(val),
+                    Ok(None) => self.1 = None,
+                    Err(payload) => {
+                        self.1 = None;
+                        self.3 = Some(self.2 - 1);
+                        resume_unwind(payload)
+                    }
+                }
+            }
+            self.1 = Some(self.0.next()?);
+            self.2 += 1;
+        }
+    }
+}
+
+impl<G: Iterator> FlattenGen<G> {
+    pub(crate) fn new(generator: G) -> Self {
+        FlattenGen(generator, None, 0, None)
+    }
+    /// Returns the position among the yielded generators of the inner generator which panicked last, if any
+    pub fn last_failed(&self) -> Option<usize> {
+        self.3
+    }
+}
+
+impl<G> EnumerateYields<G> {
+    pub(crate) fn new(generator: G) -> Self {
+        EnumerateYields(generator, 0)
+    }
+    /// Returns the number of values yielded so far, which is the position the next value is paired with
+    pub fn count_so_far(&self) -> usize {
+        self.1
+    }
+}
+
+impl<'a, G: Generator<'a>> Generator<'a> for EnumerateYields<G> {
+    type Yield = (usize, G::Yield);
+    type Receive = G::Receive;
+
+    fn has_completed(&self) -> bool {
+        self.0.has_completed()
+    }
+
+    fn resume(&mut self, send: G::Receive) -> Option<Self::Yield> {
+        let val = self.0.resume(send)?;
+        self.1 += 1;
+        Some((self.1 - 1, val))
+    }
+
+    fn cancel(&self) {
+        self.0.cancel()
+    }
+}
+
+impl<'a, G: ResultingGenerator<'a>> ResultingGenerator<'a> for EnumerateYields<G> {
+    type Return = G::Return;
+
+    fn result(self) -> Result<G::Return, GeneratorError> {
+        self.0.result()
+    }
+
+    fn try_result(self) -> Result<G::Return, (Self, GeneratorError)> {
+        let EnumerateYields(generator, count) = self;
+        generator.try_result().map_err(|(generator, e)| (EnumerateYields(generator, count), e))
+    }
+}
+
+impl<G: Iterator> Iterator for EnumerateYields<G> {
+    type Item = (usize, G::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let val = self.0.next()?;
+        self.1 += 1;
+        Some((self.1 - 1, val))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<G: FusedIterator> FusedIterator for EnumerateYields<G> {}
+
+impl<G, S, F> ScanYields<G, S, F> {
+    pub(crate) fn new(generator: G, state: S, f: F) -> Self {
+        ScanYields(generator, state, f, false)
+    }
+    /// Returns the current state
+    pub fn state(&self) -> &S {
+        &self.1
+    }
+    /// Drops the generator returning the state
+    pub fn into_state(self) -> S {
+        self.1
+    }
+}
+
+impl<'a, G: ResultingGenerator<'a, Receive=()>, S, F> ScanYields<G, S, F> {
+    /// Drains the values the mapping function has not been called for, returning the state along with the result
+    pub fn finish(self) -> (Result<G::Return, GeneratorError>, S) {
+        (self.0.drain(), self.1)
+    }
+}
+
+impl<'a, G: Generator<'a>, S, U: 'static, F: FnMut(&mut S, G::Yield) -> Option<U>> Generator<'a> for ScanYields<G, S, F> {
+    type Yield = U;
+    type Receive = G::Receive;
+
+    fn has_completed(&self) -> bool {
+        self.3 || self.0.has_completed()
+    }
+
+    fn resume(&mut self, send: G::Receive) -> Option<U> {
+        if self.3 {
+            return None;
+        }
+        let mapped = (self.2)(&mut self.1, self.0.resume(send)?);
+        self.3 = mapped.is_none();
+        mapped
+    }
+
+    fn cancel(&self) {
+        self.0.cancel()
+    }
+}
+
+impl<'a, G: ResultingGenerator<'a, Receive=()>, S, U: 'static, F: FnMut(&mut S, G::Yield) -> Option<U>> ResultingGenerator<'a> for ScanYields<G, S, F> {
+    type Return = G::Return;
+
+    /// Same as [ScanYields::finish] dropping the state
+    fn result(self) -> Result<G::Return, GeneratorError> {
+        self.finish().0
+    }
+
+    /// Doesn't run the rest of the generator, which is handed back along with the state while it is still running
+    fn try_result(self) -> Result<G::Return, (Self, GeneratorError)> {
+        let ScanYields(generator, state, f, stopped) = self;
+        generator.try_result().map_err(|(generator, e)| (ScanYields(generator, state, f, stopped), e))
+    }
+}
+
+impl<G: Iterator, S, U, F: FnMut(&mut S, G::Item) -> Option<U>> Iterator for ScanYields<G, S, F> {
+    type Item = U;
+
+    fn next(&mut self) -> Option<U> {
+        if self.3 {
+            return None;
+        }
+        let mapped = (self.2)(&mut self.1, self.0.next()?);
+        self.3 = mapped.is_none();
+        mapped
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.3 { (0, Some(0)) } else { (0, self.0.size_hint().1) }
+    }
+}
+
+impl<G> Chunks<G> {
+    pub(crate) fn new(generator: G, n: usize) -> Self {
+        Chunks(generator, n, false)
+    }
+}
+
+impl<'a, G: Generator<'a, Receive=()> + Iterator<Item=<G as Generator<'a>>::Yield>> Generator<'a> for Chunks<G> {
+    type Yield = Vec<G::Item>;
+    type Receive = ();
+
+    fn has_completed(&self) -> bool {
+        self.2
+    }
+
+    fn resume(&mut self, _: ()) -> Option<Vec<G::Item>> {
+        self.next()
+    }
+
+    fn cancel(&self) {
+        self.0.cancel()
+    }
+}
+
+impl<'a, G: ResultingGenerator<'a, Receive=()> + Iterator<Item=<G as Generator<'a>>::Yield>> ResultingGenerator<'a> for Chunks<G> {
+    type Return = G::Return;
+
+    fn result(self) -> Result<G::Return, GeneratorError> {
+        self.0.result()
+    }
+
+    fn try_result(self) -> Result<G::Return, (Self, GeneratorError)> {
+        let Chunks(generator, n, done) = self;
+        generator.try_result().map_err(|(generator, e)| (Chunks(generator, n, done), e))
+    }
+}
+
+impl<G: Iterator> Iterator for Chunks<G> {
+    type Item = Vec<G::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.2 {
+            return None;
+        }
+        let mut chunk = Vec::with_capacity(self.1);
+        chunk.extend(self.0.by_ref().take(self.1));
+        self.2 = chunk.is_empty();
+        if self.2 { None } else { Some(chunk) }
+    }
+}
+
+impl<'a, A, Y2: 'static, R2: 'static> Piped<'a, A, Y2, R2>
+    where A: ResultingGenerator<'a, Receive=()> + Iterator<Item=<A as Generator<'a>>::Yield> {
+    /// Returns the results of both generators, each of which is None if the generator has not completed or failed
+    pub fn finish(self) -> (Option<A::Return>, Option<R2>) {
+        (self.0.result().ok(), self.1.result().ok())
+    }
+    /// Recovers the result of the receiving generator after the source completed first by cancelling it and resuming it a last time with [last]
+    /// The receiving generator is expected to return once it observes the cancellation, if it yields another value instead its result is lost
+    pub fn cancel_receiver(mut self, last: A::Item) -> (Option<A::Return>, Option<R2>) {
+        if !self.1.has_completed() {
+            self.1.cancellation_token().cancel();
+            self.1.resume(last);
+        }
+        self.finish()
+    }
+}
+
+impl<'a, A, Y2: 'static, R2: 'static> Generator<'a> for Piped<'a, A, Y2, R2>
+    where A: Generator<'a, Receive=()> + Iterator<Item=<A as Generator<'a>>::Yield> {
+    type Yield = Y2;
+    type Receive = ();
+
+    /// A panic of either generator completes the pipe as well
+    fn has_completed(&self) -> bool {
+        self.2 || self.0.has_completed() || self.1.has_completed()
+    }
+
+    fn resume(&mut self, _: ()) -> Option<Y2> {
+        self.next()
+    }
+
+    /// Cancels both generators, unlike [Piped::cancel_receiver] the receiving one is not resumed to observe it
+    fn cancel(&self) {
+        self.0.cancel();
+        self.1.cancel();
+    }
+}
+
+impl<'a, A, Y2: 'static, R2: 'static> ResultingGenerator<'a> for Piped<'a, A, Y2, R2>
+    where A: ResultingGenerator<'a, Receive=()> + Iterator<Item=<A as Generator<'a>>::Yield> {
+    type Return = (Option<A::Return>, Option<R2>);
+
+    /// Same as [Piped::finish] once either generator has completed
+    fn result(self) -> Result<Self::Return, GeneratorError> {
+        if self.has_completed() { Ok(self.finish()) } else { Err(GeneratorError::NotCompleted) }
+    }
+
+    fn try_result(self) -> Result<Self::Return, (Self, GeneratorError)> {
+        if self.has_completed() { Ok(self.finish()) } else { Err((self, GeneratorError::NotCompleted)) }
+    }
+}
+
+impl<'a, A: Iterator, Y2: 'static, R2: 'static> Iterator for Piped<'a, A, Y2, R2> where A::Item: 'a {
+    type Item = Y2;
+
+    fn next(&mut self) -> Option<Y2> {
+        if self.2 {
+            return None;
+        }
+        let val = match self.0.next() {
+            Some(input) => self.1.resume(input),
+            None => None
+        };
+        self.2 = val.is_none();
+        val
+    }
+}
+
+impl<'a, A: Iterator, Y2: 'static, R2: 'static> FusedIterator for Piped<'a, A, Y2, R2> where A::Item: 'a {}
+
+impl<'a, G: ResultingGenerator<'a, Receive=()>> RoundRobin<G> {
+    /// Drains the generators which have not completed yet, returning the results of all in the order they have been passed to [round_robin]
+    pub fn finish(self) -> Vec<Result<G::Return, GeneratorError>> {
+        self.0.into_iter().map(|(generator, _)| generator.drain()).collect()
+    }
+}
+
+impl<'a, G: ResultingGenerator<'a, Receive=()> + Iterator<Item=<G as Generator<'a>>::Yield>> Generator<'a> for RoundRobin<G> {
+    type Yield = G::Item;
+    type Receive = ();
+
+    fn has_completed(&self) -> bool {
+        self.0.iter().all(|(_, state)| !matches!(state, SlotState::Live))
+    }
+
+    fn resume(&mut self, _: ()) -> Option<G::Item> {
+        self.next()
+    }
+
+    fn cancel(&self) {
+        for (generator, _) in &self.0 {
+            generator.cancel();
+        }
+    }
+}
+
+impl<'a, G: ResultingGenerator<'a, Receive=()> + Iterator<Item=<G as Generator<'a>>::Yield>> ResultingGenerator<'a> for RoundRobin<G> {
+    type Return = Vec<Result<G::Return, GeneratorError>>;
+
+    /// Same as [RoundRobin::finish] once all generators have completed
+    fn result(self) -> Result<Self::Return, GeneratorError> {
+        if self.has_completed() { Ok(self.finish()) } else { Err(GeneratorError::NotCompleted) }
+    }
+
+    fn try_result(self) -> Result<Self::Return, (Self, GeneratorError)> {
+        if self.has_completed() { Ok(self.finish()) } else { Err((self, GeneratorError::NotCompleted)) }
+    }
+}
+
+impl<G: Iterator> Iterator for RoundRobin<G> {
+    type Item = G::Item;
+
+    fn next(&mut self) -> Option<G::Item> {
+        for _ in 0..self.0.len() {
+            let position = self.1;
+            self.1 = (position + 1) % self.0.len();
+            let (generator, state) = &mut self.0[position];
+            if !matches!(state, SlotState::Live) {
+                continue;
+            }
+            match catch_unwind(AssertUnwindSafe(|| generator.next())) {
+                Ok(Some(val)) => return Some(val),
+                Ok(None) | Err(_) => *state = SlotState::Completed,
+            }
+        }
+        None
+    }
+}
+
+impl<G: Iterator> FusedIterator for RoundRobin<G> {}
+
+impl<G: Iterator> TeeHandle<G> {
+    /// Creates both handles sharing the values of [generator]
+    pub(crate) fn pair(generator: G) -> (Self, Self) {
+        let shared = Rc::new(RefCell::new(TeeBuffer(Some(generator), VecDeque::new(), 0)));
+        (TeeHandle(shared.clone(), 0), TeeHandle(shared, 1))
+    }
+    /// Returns the number of values the leading handle has taken but the lagging one has not yet
+    pub fn buffered_len(&self) -> usize {
+        self.0.borrow().1.len()
+    }
+    /// Returns the number of values buffered for this handle
+    fn own_buffered_len(&self) -> usize {
+        let tee = self.0.borrow();
+        if tee.2 == self.1 { tee.1.len() } else { 0 }
+    }
+}
+
+impl<'a, G: ResultingGenerator<'a, Receive=()> + Iterator<Item=<G as Generator<'a>>::Yield>> TeeHandle<G> {
+    /// Moves the result out of the generator once both handles are exhausted, leaving [GeneratorError::Taken] for the other handle
+    fn take_result(&self) -> Result<G::Return, GeneratorError> {
+        let mut tee = self.0.borrow_mut();
+        if !tee.1.is_empty() {
+            return Err(GeneratorError::NotCompleted);
+        }
+        match tee.0.take() {
+            None => Err(GeneratorError::Taken),
+            Some(generator) => generator.try_result().map_err(|(generator, e)| {
+                tee.0 = Some(generator);
+                e
+            })
+        }
+    }
+}
+
+impl<'a, G: Generator<'a, Receive=()> + Iterator<Item=<G as Generator<'a>>::Yield>> Generator<'a> for TeeHandle<G> where G::Item: Clone {
+    type Yield = G::Yield;
+    type Receive = ();
+
+    /// Values still buffered for this handle are handed out, even if the generator has completed
+    fn has_completed(&self) -> bool {
+        self.own_buffered_len() == 0 && self.0.borrow().0.as_ref().is_none_or(|generator| generator.has_completed())
+    }
+
+    fn resume(&mut self, _: ()) -> Option<G::Yield> {
+        self.next()
+    }
+
+    /// Cancels the shared generator, so the other handle observes the cancellation as well
+    fn cancel(&self) {
+        if let Some(generator) = &self.0.borrow().0 {
+            generator.cancel();
+        }
+    }
+}
+
+impl<'a, G: ResultingGenerator<'a, Receive=()> + Iterator<Item=<G as Generator<'a>>::Yield>> ResultingGenerator<'a> for TeeHandle<G> where G::Item: Clone {
+    type Return = G::Return;
+
+    /// The result can only be taken by one of the handles once the generator has completed and both handles took all values
+    fn result(self) -> Result<G::Return, GeneratorError> {
+        self.take_result()
+    }
+
+    fn try_result(self) -> Result<G::Return, (Self, GeneratorError)> {
+        let result = self.take_result();
+        result.map_err(|e| (self, e))
+    }
+}
+
+impl<G: Iterator> Iterator for TeeHandle<G> where G::Item: Clone {
+    type Item = G::Item;
+
+    fn next(&mut self) -> Option<G::Item> {
+        // nothing has to be buffered once the other handle has been dropped
+        let shared = Rc::strong_count(&self.0) > 1;
+        let mut tee = self.0.borrow_mut();
+        if tee.2 == self.1 {
+            if let Some(val) = tee.1.pop_front() {
+                return Some(val);
+            }
+        }
+        let val = tee.0.as_mut()?.next()?;
+        if shared {
+            tee.2 = 1 - self.1;
+            tee.1.push_back(val.clone());
+        }
+        Some(val)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let own = self.own_buffered_len();
+        match &self.0.borrow().0 {
+            Some(generator) => {
+                let (lower, upper) = generator.size_hint();
+                (lower.saturating_add(own), upper.and_then(|upper| upper.checked_add(own)))
+            }
+            None => (own, Some(own))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+    use std::rc::Rc;
+
+    use crate::generators::{BoostedGenerator, BoringGenerator, Generator, GeneratorChannel, GeneratorError, GeneratorExt, IteratorExt, ResultingGenerator};
+    use crate::generators::tests::{counting, summing};
+
+    #[test]
+    fn mapped_generator_keeps_result() {
+        let mut mapped = counting(0..12).map_yield(|v| v.to_string());
+        assert_eq!(mapped.resume(()), Some("0".to_string()));
+        assert!(!mapped.has_completed());
+        assert_eq!(mapped.by_ref().last(), Some("11".to_string()));
+        assert!(mapped.has_completed());
+        assert_eq!(mapped.next(), None);
+        assert_eq!(mapped.result(), Ok(12));
+
+        let mut receiving = BoostedGenerator::new_receiving(|chan, mut v: i32| {
+            while v > 0 {
+                v = chan.yield_val(v);
+            }
+        }).map_yield(|v| v * 2);
+        assert_eq!(receiving.resume(3), Some(6));
+        assert_eq!(receiving.resume(0), None);
+        assert_eq!(receiving.try_result().ok(), Some(()));
+    }
+
+    #[test]
+    fn filtered_generator_keeps_result() {
+        let mut none = BoostedGenerator::new(|chan| {
+            chan.yield_all(0..10);
+            "done"
+        }).filter_yield(|_| false);
+        assert_eq!(none.next(), None);
+        assert!(none.has_completed());
+        assert_eq!(none.result(), Ok("done"));
+
+        let mut even = BoringGenerator::new_resulting(|chan| {
+            chan.yield_all(0..10);
+            10
+        }).filter_yield(|v| v % 2 == 0);
+        assert_eq!(even.by_ref().collect::<Vec<_>>(), vec![0, 2, 4, 6, 8]);
+        assert_eq!(even.result(), Ok(10));
+    }
+
+    #[test]
+    fn filtered_receiving_generator_gets_input_again_on_skips() {
+        let mut received = Vec::new();
+        let mut gen = BoostedGenerator::new_receiving(|chan, mut input: String| {
+            let mut count = 0;
+            while !input.is_empty() {
+                count += 1;
+                input = chan.yield_val(format!("{} {}", input, count));
+                received.push(input.clone());
+            }
+            count
+        }).filter_yield(|output| !output.ends_with('3'));
+        assert_eq!(gen.resume("a".to_string()), Some("a 1".to_string()));
+        assert_eq!(gen.resume("b".to_string()), Some("b 2".to_string()));
+        assert_eq!(gen.resume("c".to_string()), Some("c 4".to_string()));
+        assert_eq!(gen.resume(String::new()), None);
+        assert_eq!(gen.result(), Ok(4));
+        assert_eq!(received, vec!["b", "c", "c", ""]);
+    }
+
+    #[test]
+    fn taken_generator_is_finished_for_result() {
+        let mut first = counting(0..10).take_yields(3);
+        assert_eq!(first.by_ref().collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert!(first.has_completed());
+        assert_eq!(first.next(), None);
+        assert_eq!(first.finish(), Ok(3));
+
+        let mut running = counting(0..10).take_yields(3);
+        assert_eq!(running.by_ref().count(), 3);
+        let (running, error) = running.try_result().err().unwrap();
+        assert_eq!(error, GeneratorError::NotCompleted);
+        assert_eq!(running.finish(), Ok(3));
+
+        let mut short = counting(0..2).take_yields(3);
+        assert_eq!(short.by_ref().collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(short.result(), Ok(2));
+
+        let mut small = counting(0..10).take_yields_while(|v| *v < 4);
+        assert_eq!(small.by_ref().collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+        assert!(small.has_completed());
+        assert_eq!(small.finish(), Ok(5));
+
+        let mut rejecting = counting(0..10).take_yields_while(|v| *v < 2);
+        assert_eq!(rejecting.by_ref().count(), 2);
+        let (rejecting, error) = rejecting.try_result().err().unwrap();
+        assert_eq!(error, GeneratorError::NotCompleted);
+        assert_eq!(rejecting.result(), Ok(3));
+
+        let mut all = counting(0..3).take_yields_while(|_| true);
+        assert_eq!(all.by_ref().collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(all.try_result().ok(), Some(3));
+    }
+
+    #[test]
+    fn endless_generator_is_cancelled_by_finish() {
+        let mut taken = counting(0..).take_yields(3);
+        assert_eq!(taken.by_ref().count(), 3);
+        assert_eq!(taken.finish(), Ok(3));
+        let mut small = counting(0..).take_yields_while(|v| *v < 5);
+        assert_eq!(small.by_ref().count(), 5);
+        assert_eq!(small.result(), Ok(6));
+    }
+
+    #[test]
+    fn zipped_generators_pair_values_and_results() {
+        let mut longer_first = super::zip(counting(0..3), counting(10..12));
+        assert_eq!(longer_first.by_ref().collect::<Vec<_>>(), vec![(0, 10), (1, 11)]);
+        assert!(longer_first.has_completed());
+        assert_eq!(longer_first.next(), None);
+        assert_eq!(longer_first.take_unpaired(), Some(2));
+        assert_eq!(longer_first.finish(), (Some(3), Some(2)));
+
+        let mut shorter_first = super::zip(counting(0..1), counting(10..13));
+        assert_eq!(shorter_first.by_ref().count(), 1);
+        assert_eq!(shorter_first.take_unpaired(), None);
+        assert_eq!(shorter_first.result(), Ok((Some(1), Some(3))));
+
+        let mut running = super::zip(counting(0..2), counting(10..12));
+        assert!(running.next().is_some());
+        let (mut running, error) = running.try_result().err().unwrap();
+        assert_eq!(error, GeneratorError::NotCompleted);
+        assert_eq!(running.by_ref().count(), 1);
+        assert_eq!(running.try_result().ok(), Some((Some(2), Some(2))));
+
+        let failing = BoostedGenerator::<i32, usize, ()>::new(|_| panic!("sensor failed"));
+        let zipped = super::zip(counting(0..2), failing);
+        assert_eq!(zipped.finish(), (Some(2), None));
+
+        let failing = BoostedGenerator::<i32, usize, ()>::new(|_| panic!("sensor failed"));
+        let mut zipped = super::zip(failing, counting(10..12));
+        assert!(catch_unwind(AssertUnwindSafe(|| zipped.next())).is_err());
+        assert!(zipped.has_completed());
+        assert_eq!(zipped.result(), Ok((None, Some(2))));
+    }
+
+    #[test]
+    fn chained_generators_keep_both_results() {
+        let created = Rc::new(Cell::new(false));
+        let created_by_factory = created.clone();
+        let mut chained = counting(0..2).chain_gen(counting(2..3)).chain_gen_with(move || {
+            created_by_factory.set(true);
+            counting(3..6)
+        });
+        assert_eq!(chained.by_ref().take(3).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert!(!created.get());
+        assert_eq!(chained.by_ref().collect::<Vec<_>>(), vec![3, 4, 5]);
+        assert!(chained.has_completed());
+        assert_eq!(chained.next(), None);
+        assert_eq!(chained.result(), Ok(((2, 1), 3)));
+
+        let failing = BoostedGenerator::<i32, i32, ()>::new(|chan| {
+            chan.yield_val(0);
+            panic!("first failed")
+        });
+        let mut chained = failing.chain_gen_with(|| -> BoostedGenerator<i32, i32, ()> { unreachable!("second created") });
+        assert_eq!(chained.next(), Some(0));
+        assert!(catch_unwind(AssertUnwindSafe(|| chained.next())).is_err());
+        assert!(chained.has_completed());
+        let failure = GeneratorError::Panicked { message: Some("first failed".to_string()) };
+        let (mut chained, error) = chained.try_result().err().unwrap();
+        assert_eq!(error, failure);
+        assert_eq!(chained.next(), None);
+        assert!(chained.has_completed());
+        assert_eq!(chained.result(), Err(failure));
+    }
+
+    #[test]
+    fn flattened_generator_runs_inner_generators_in_order() {
+        let files = BoostedGenerator::new(|chan| {
+            for lines in [vec!["a", "b"], vec![], vec!["c"], vec![]] {
+                chan.yield_val(BoringGenerator::new(move |file| file.yield_all(lines.into_iter())));
+            }
+            4
+        });
+        let mut lines = files.flatten_gen();
+        assert_eq!(lines.by_ref().collect::<Vec<_>>(), vec!["a", "b", "c"]);
+        assert!(lines.has_completed());
+        assert_eq!(lines.next(), None);
+
+        let empty = BoringGenerator::new(|chan| chan.yield_all((0..3).map(|_| BoringGenerator::<i32>::new(|_| ()))));
+        assert_eq!(empty.flatten_gen().count(), 0);
+    }
+
+    #[test]
+    fn flattened_generator_tells_which_inner_generator_panicked() {
+        let files = BoringGenerator::new(|chan| {
+            chan.yield_val(BoringGenerator::new(|file| file.yield_val(1)));
+            chan.yield_val(BoringGenerator::new(|file| {
+                file.yield_val(2);
+                panic!("unreadable file")
+            }));
+            chan.yield_val(BoringGenerator::new(|file| file.yield_val(3)));
+        });
+        let mut values = files.flatten_gen();
+        assert_eq!(values.next(), Some(1));
+        assert_eq!(values.next(), Some(2));
+        assert_eq!(values.last_failed(), None);
+        let payload = catch_unwind(AssertUnwindSafe(|| values.next())).unwrap_err();
+        // the backtrace feature appends the backtrace of the panic site to the message
+        assert_eq!(payload.downcast_ref::<String>().and_then(|message| message.lines().next()), Some("Coroutine panicked: unreadable file"));
+        assert_eq!(values.last_failed(), Some(1));
+        assert_eq!(values.next(), Some(3));
+        assert_eq!(values.next(), None);
+    }
+
+    #[test]
+    fn enumerated_generator_counts_yields() {
+        let mut gen = BoostedGenerator::new(|chan| {
+            chan.yield_all("ab".chars());
+            "done"
+        }).enumerate_yields();
+        assert_eq!(gen.count_so_far(), 0);
+        assert_eq!(gen.next(), Some((0, 'a')));
+        assert_eq!(gen.count_so_far(), 1);
+        assert_eq!(gen.by_ref().collect::<Vec<_>>(), vec![(1, 'b')]);
+        assert_eq!(gen.count_so_far(), 2);
+        assert!(gen.has_completed());
+        assert_eq!(gen.result(), Ok("done"));
+
+        let mut receiving = BoostedGenerator::new_receiving(|chan, mut v: i32| {
+            while v > 0 {
+                v = chan.yield_val(v * 10);
+            }
+            v
+        }).enumerate_yields();
+        assert_eq!(receiving.resume(1), Some((0, 10)));
+        assert_eq!(receiving.resume(2), Some((1, 20)));
+        assert_eq!(receiving.resume(-3), None);
+        assert_eq!(receiving.count_so_far(), 2);
+        assert_eq!(receiving.try_result().ok(), Some(-3));
+    }
+
+    #[test]
+    fn scanned_generator_keeps_state_and_result() {
+        let mut sums = counting(1..6).scan_yields(0, |sum, v| {
+            *sum += v;
+            Some(*sum)
+        });
+        assert_eq!(sums.by_ref().collect::<Vec<_>>(), vec![1, 3, 6, 10, 15]);
+        assert_eq!(*sums.state(), 15);
+        assert_eq!(sums.finish(), (Ok(5), 15));
+
+        let mut below = counting(1..6).scan_yields(0, |sum, v| {
+            *sum += v;
+            if *sum < 5 { Some(*sum) } else { None }
+        });
+        assert_eq!(below.by_ref().collect::<Vec<_>>(), vec![1, 3]);
+        assert!(below.has_completed());
+        assert_eq!(below.next(), None);
+        assert_eq!(below.finish(), (Ok(5), 6));
+
+        let mut first = counting(1..6).scan_yields(0, |_, v| if v < 2 { Some(v) } else { None });
+        assert_eq!(first.by_ref().count(), 1);
+        let (first, error) = first.try_result().err().unwrap();
+        assert_eq!(error, GeneratorError::NotCompleted);
+        assert_eq!(first.finish(), (Ok(5), 0));
+
+        let mut state = counting(1..6).scan_yields(Vec::new(), |seen, v| {
+            seen.push(v);
+            Some(v)
+        });
+        state.next();
+        assert_eq!(state.into_state(), vec![1]);
+    }
+
+    #[test]
+    fn chunks_keep_last_partial_batch() {
+        let mut batches = BoostedGenerator::new(|chan| {
+            chan.yield_all(0..10);
+            "done"
+        }).chunks(3);
+        let lengths = batches.by_ref().map(|batch| batch.len()).collect::<Vec<_>>();
+        assert_eq!(lengths, vec![3, 3, 3, 1]);
+        assert!(batches.has_completed());
+        assert_eq!(batches.next(), None);
+        assert_eq!(batches.result(), Ok("done"));
+
+        let mut exact = BoringGenerator::new(|chan| chan.yield_all(0..4)).chunks(2);
+        assert_eq!(exact.by_ref().collect::<Vec<_>>(), vec![vec![0, 1], vec![2, 3]]);
+        assert_eq!(exact.result(), Ok(()));
+    }
+
+    #[test]
+    #[should_panic(expected = "chunk size must not be 0")]
+    fn chunks_of_zero_panic() {
+        BoringGenerator::new(|chan| chan.yield_val(1)).chunks(0);
+    }
+
+    #[test]
+    fn round_robin_interleaves_until_all_completed() {
+        let failing = BoostedGenerator::new(|chan| {
+            chan.yield_val(20);
+            panic!("fetcher c failed")
+        });
+        let mut pages = super::round_robin(vec![counting(0..3), counting(10..11), failing]);
+        assert_eq!(pages.by_ref().collect::<Vec<_>>(), vec![0, 10, 20, 1, 2]);
+        assert!(pages.has_completed());
+        assert_eq!(pages.next(), None);
+        assert_eq!(pages.finish(), vec![Ok(3), Ok(1), Err(GeneratorError::Panicked { message: Some("fetcher c failed".to_string()) })]);
+
+        let mut running = super::round_robin(vec![counting(0..2), counting(10..11)]);
+        assert_eq!(running.next(), Some(0));
+        let (mut running, error) = running.try_result().err().unwrap();
+        assert_eq!(error, GeneratorError::NotCompleted);
+        assert_eq!(running.by_ref().count(), 2);
+        assert_eq!(running.try_result().ok(), Some(vec![Ok(2), Ok(1)]));
+
+        let empty = super::round_robin(Vec::<BoostedGenerator<i32, (), ()>>::new());
+        assert_eq!(empty.result(), Ok(vec![]));
+    }
+
+    #[test]
+    fn tee_handles_share_values_consumed_interleaved() {
+        let mut gen_resumes = 0;
+        let (mut left, mut right) = BoostedGenerator::new(|chan| {
+            for i in 0..4 {
+                chan.yield_val(i.to_string());
+            }
+            "done"
+        }).map_yield(|v| {
+            gen_resumes += 1;
+            v
+        }).tee();
+        assert_eq!(left.next(), Some("0".to_string()));
+        assert_eq!(left.buffered_len(), 1);
+        assert_eq!(right.next(), Some("0".to_string()));
+        assert_eq!(right.next(), Some("1".to_string()));
+        assert_eq!(right.next(), Some("2".to_string()));
+        assert_eq!(right.buffered_len(), 2);
+        assert_eq!(left.by_ref().collect::<Vec<_>>(), vec!["1", "2", "3"]);
+        assert_eq!(left.buffered_len(), 1);
+        assert!(left.has_completed() && !right.has_completed());
+        let (left, error) = left.try_result().err().unwrap();
+        assert_eq!(error, GeneratorError::NotCompleted);
+        assert_eq!(right.by_ref().collect::<Vec<_>>(), vec!["3"]);
+        assert_eq!(right.buffered_len(), 0);
+        assert_eq!(right.result(), Ok("done"));
+        assert_eq!(left.result(), Err(GeneratorError::Taken));
+        assert_eq!(gen_resumes, 4);
+    }
+
+    #[test]
+    fn tee_buffers_everything_for_skewed_handle() {
+        let (mut leading, mut lagging) = BoringGenerator::new_resulting(|chan| {
+            chan.yield_all(0..100);
+            100
+        }).tee();
+        assert_eq!(leading.by_ref().sum::<i32>(), 4950);
+        assert_eq!(leading.buffered_len(), 100);
+        assert_eq!(lagging.size_hint(), (100, Some(100)));
+        assert_eq!(lagging.by_ref().take(50).count(), 50);
+        assert_eq!(lagging.buffered_len(), 50);
+        assert_eq!(lagging.by_ref().sum::<i32>(), 3725);
+        assert_eq!(leading.result(), Ok(100));
+
+        let (mut single, other) = BoringGenerator::new(|chan| chan.yield_all(0..3)).tee();
+        drop(other);
+        assert_eq!(single.by_ref().count(), 3);
+        assert_eq!(single.buffered_len(), 0);
+        assert_eq!(single.result(), Ok(()));
+    }
+
+    #[test]
+    fn piped_stages_pass_values_one_at_a_time() {
+        let in_flight = Rc::new(Cell::new(0));
+        let produced = in_flight.clone();
+        let numbers = BoostedGenerator::new(move |chan| {
+            for i in 1..=4 {
+                produced.set(produced.get() + 1);
+                assert_eq!(produced.get(), 1, "value produced before the previous one has been consumed");
+                chan.yield_val(i);
+            }
+            "numbers done"
+        });
+        let consumed = in_flight.clone();
+        let doubled = BoostedGenerator::<i32, (), i32>::new_receiving(move |chan, mut v| {
+            loop {
+                consumed.set(consumed.get() - 1);
+                v = chan.yield_val(v * 2);
+            }
+        });
+        let mut total = 0;
+        let labels = BoostedGenerator::new_receiving(|chan, mut v: i32| {
+            while v < 8 {
+                total += v;
+                v = chan.yield_val(format!("#{}", v));
+            }
+            total
+        });
+        let mut piped = super::pipe(super::pipe(numbers, doubled), labels);
+        assert_eq!(piped.by_ref().collect::<Vec<_>>(), vec!["#2", "#4", "#6"]);
+        assert!(piped.has_completed());
+        assert_eq!(piped.next(), None);
+        assert_eq!(piped.finish(), (None, Some(12)));
+    }
+
+    #[test]
+    fn piped_receiver_is_cancelled_after_source_completed() {
+        let mut piped = super::pipe(counting(1..3), summing());
+        assert_eq!(piped.by_ref().collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!(piped.cancel_receiver(5), (Some(2), Some(3)));
+
+        let mut piped = super::pipe(counting(1..3), summing());
+        assert_eq!(piped.next(), Some(1));
+        let (mut piped, error) = piped.try_result().err().unwrap();
+        assert_eq!(error, GeneratorError::NotCompleted);
+        assert_eq!(piped.by_ref().count(), 1);
+        assert_eq!(piped.result(), Ok((Some(2), None)));
+
+        let mut empty = super::pipe(counting(0..0), summing());
+        assert_eq!(empty.next(), None);
+        assert_eq!(empty.cancel_receiver(7), (Some(0), Some(0)));
+    }
+
+    #[test]
+    fn combined_generators_forward_cancellation() {
+        let mut chained = counting(0..).chain_gen_with(|| counting(0..));
+        assert_eq!(chained.next(), Some(0));
+        chained.cancel();
+        assert_eq!(chained.next(), None);
+        assert_eq!(chained.result(), Ok((1, 0)));
+
+        let mut zipped = super::zip(counting(0..), counting(0..));
+        assert_eq!(zipped.next(), Some((0, 0)));
+        zipped.cancel();
+        assert_eq!(zipped.next(), None);
+        assert_eq!(zipped.result(), Ok((Some(1), Some(1))));
+
+        let mut flattened = BoostedGenerator::new(move |chan| {
+            while !chan.is_cancelled() {
+                chan.yield_val(counting(0..));
+            }
+        }).flatten_gen();
+        assert_eq!(flattened.next(), Some(0));
+        flattened.cancel();
+        assert_eq!(flattened.next(), None);
+        assert!(flattened.has_completed());
+
+        let mut interleaved = super::round_robin(vec![counting(0..), counting(0..)]);
+        assert_eq!(interleaved.by_ref().take(2).collect::<Vec<_>>(), vec![0, 0]);
+        interleaved.cancel();
+        assert_eq!(interleaved.next(), None);
+        assert_eq!(interleaved.result(), Ok(vec![Ok(1), Ok(1)]));
+
+        let mut piped = super::pipe((1..).into_generator(), summing());
+        assert_eq!(piped.next(), Some(1));
+        piped.cancel();
+        assert_eq!(piped.next(), None);
+        assert_eq!(piped.result(), Ok((None, Some(1))));
+
+        let (mut left, mut right) = counting(0..).tee();
+        assert_eq!(left.next(), Some(0));
+        right.cancel();
+        assert_eq!(left.next(), None);
+        assert_eq!(right.by_ref().collect::<Vec<_>>(), vec![0]);
+        assert_eq!(right.result(), Ok(1));
+    }
+}
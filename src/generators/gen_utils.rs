@@ -0,0 +1,82 @@
+//! Generators computing their values by closures, the counterparts of [std::iter::from_fn], [std::iter::successors] and [std::iter::repeat_with]
+//! They are [IterGenerator]s, so they run right in the resuming context without allocating a callstack
+
+use std::iter::{RepeatWith, Successors};
+
+use super::IterGenerator;
+
+/// Iterator behind [unfold] handing the state to the closure on each call
+/// The fields are the state and the closure
+pub struct Unfold<S, F>(S, F);
+
+/// Yields the values [f] computes from [state] until it returns None
+pub fn unfold<S, T, F: FnMut(&mut S) -> Option<T>>(state: S, f: F) -> IterGenerator<Unfold<S, F>> {
+    IterGenerator::new(Unfold(state, f))
+}
+
+/// Yields [first] and each value [f] computes from the one yielded before until it returns None, see [std::iter::successors]
+pub fn successors<T, F: FnMut(&T) -> Option<T>>(first: Option<T>, f: F) -> IterGenerator<Successors<T, F>> {
+    IterGenerator::new(std::iter::successors(first, f))
+}
+
+/// Yields the values [f] returns endlessly, see [std::iter::repeat_with]
+pub fn repeat_with<T, F: FnMut() -> T>(f: F) -> IterGenerator<RepeatWith<F>> {
+    IterGenerator::new(std::iter::repeat_with(f))
+}
+
+impl<S, T, F: FnMut(&mut S) -> Option<T>> Iterator for Unfold<S, F> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        (self.1)(&mut self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generators::{BoostedGenerator, Generator, GeneratorChannel, GeneratorExt, ResultingGenerator};
+
+    use super::{repeat_with, successors, unfold};
+
+    #[test]
+    fn unfold_matches_from_fn() {
+        let mut state = (0u64, 1u64);
+        let expected = std::iter::from_fn(move || {
+            state = (state.1, state.0 + state.1);
+            if state.0 < 1000 { Some(state.0) } else { None }
+        });
+        let fibonacci = unfold((0u64, 1u64), |state| {
+            *state = (state.1, state.0 + state.1);
+            if state.0 < 1000 { Some(state.0) } else { None }
+        });
+        assert!(fibonacci.eq(expected));
+    }
+
+    #[test]
+    fn successors_match_std() {
+        let mut powers = successors(Some(1u32), |n| n.checked_mul(10));
+        assert!(powers.by_ref().eq(std::iter::successors(Some(1u32), |n| n.checked_mul(10))));
+        assert!(powers.has_completed());
+        assert_eq!(powers.result(), Ok(()));
+        assert!(successors(None, |n: &u32| Some(n + 1)).eq(std::iter::successors(None, |n: &u32| Some(n + 1))));
+    }
+
+    #[test]
+    fn repeat_with_matches_std() {
+        let mut counter = 0;
+        let mut generated = 0;
+        let expected: Vec<_> = std::iter::repeat_with(|| { counter += 2; counter }).take(5).collect();
+        let values: Vec<_> = repeat_with(|| { generated += 2; generated }).take_yields(5).collect();
+        assert_eq!(values, expected);
+    }
+
+    #[test]
+    fn utilities_are_yielded_from() {
+        let mut gen = BoostedGenerator::new(|chan| {
+            chan.yield_from(successors(Some(1), |n| if *n < 3 { Some(n + 1) } else { None }))?;
+            chan.yield_from(unfold(3, |n| if *n > 0 { *n -= 1; Some(*n * 10) } else { None }))
+        });
+        assert_eq!(gen.by_ref().collect::<Vec<_>>(), vec![1, 2, 3, 20, 10, 0]);
+        assert_eq!(gen.result(), Ok(Ok(())));
+    }
+}
@@ -0,0 +1,320 @@
+use std::io::{self, BufRead, Read, Write};
+use std::mem;
+
+use crate::generators::{BoostedGenerator, Generator, GeneratorChannel, GeneratorError, ResultingGenerator};
+
+/// Reader serving the byte chunks a generator yields, see [crate::generators::GeneratorExt::into_reader]
+/// The fields are the generator, the current chunk, the position of the next byte to read within it and whether the generator completed or panicked
+pub struct GeneratorReader<G>(G, Vec<u8>, usize, bool);
+
+/// Writer sending the written bytes in chunks to a receiving generator, see [crate::generators::GeneratorExt::into_writer]
+/// The fields are the generator, the bytes not sent yet and the size of the chunks sent
+pub struct GeneratorWriter<G>(G, Vec<u8>, usize);
+
+/// Yields the lines [reader] reads lazily without their line endings, the first read error (including invalid UTF-8) ends the generator and is returned
+pub fn lines_of<'a>(reader: impl BufRead + 'a) -> BoostedGenerator<'a, String, io::Result<()>, ()> {
+    BoostedGenerator::new(move |chan| chan.yield_all_results(reader.lines()))
+}
+
+/// Yields the tokens [reader] reads lazily separated by [delim], which is not part of them, the first read error ends the generator and is returned
+pub fn split_of<'a>(reader: impl BufRead + 'a, delim: u8) -> BoostedGenerator<'a, Vec<u8>, io::Result<()>, ()> {
+    BoostedGenerator::new(move |chan| chan.yield_all_results(reader.split(delim)))
+}
+
+impl<G> GeneratorReader<G> {
+    pub(crate) fn new(generator: G) -> Self {
+        GeneratorReader(generator, Vec::new(), 0, false)
+    }
+    /// Hands back the wrapped generator, the bytes of the current chunk not read yet are lost
+    pub fn into_inner(self) -> G {
+        self.0
+    }
+}
+
+impl<'a, G: ResultingGenerator<'a>> GeneratorReader<G> {
+    /// Returns the result of the generator once the reader reached its end, a panic which ended reading is reported like [ResultingGenerator::result] does
+    pub fn into_result(self) -> Result<G::Return, GeneratorError> {
+        self.0.result()
+    }
+}
+
+impl<'a, G: Generator<'a, Yield=Vec<u8>, Receive=()>> Read for GeneratorReader<G> {
+    /// Serves the bytes of the current chunk, the generator is only resumed once all of them have been read
+    /// A panic of the generator is returned as error of kind [io::ErrorKind::Other] carrying the panic message, reads after it return 0
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        while self.2 == self.1.len() {
+            if self.3 {
+                return Ok(0);
+            }
+            match self.0.resume_caught(()) {
+                Ok(Some(chunk)) => {
+                    self.1 = chunk;
+                    self.2 = 0;
+                }
+                Ok(None) => self.3 = true,
+                Err(e) => {
+                    self.3 = true;
+                    return Err(failure(e));
+                }
+            }
+        }
+        let len = buf.len().min(self.1.len() - self.2);
+        buf[..len].copy_from_slice(&self.1[self.2..self.2 + len]);
+        self.2 += len;
+        Ok(len)
+    }
+}
+
+impl<G> GeneratorWriter<G> {
+    pub(crate) fn new(generator: G, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunk size must not be 0");
+        GeneratorWriter(generator, Vec::with_capacity(chunk_size), chunk_size)
+    }
+}
+
+impl<'a, G: Generator<'a, Receive=Vec<u8>>> GeneratorWriter<G> {
+    /// Sends the bytes not sent yet followed by an empty chunk marking the end of the data and returns the result of the generator
+    /// A generator which doesn't return on the empty chunk is reported as [GeneratorError::NotCompleted]
+    pub fn finish(mut self) -> Result<G::Return, GeneratorError> where G: ResultingGenerator<'a> {
+        if self.flush().is_ok() {
+            let _ = self.send(Vec::new());
+        }
+        self.0.result()
+    }
+
+    fn send(&mut self, chunk: Vec<u8>) -> io::Result<()> {
+        if self.0.has_completed() {
+            return Err(io::Error::new(io::ErrorKind::BrokenPipe, "generator has completed"));
+        }
+        self.0.resume_caught(chunk).map(|_| ()).map_err(failure)
+    }
+}
+
+/// Turns the failure of a generator into an error of kind [io::ErrorKind::Other] carrying the panic message if there is one
+fn failure(error: GeneratorError) -> io::Error {
+    match error {
+        GeneratorError::Panicked { message: Some(message) } => io::Error::other(message),
+        e => io::Error::other(e),
+    }
+}
+
+impl<'a, G: Generator<'a, Receive=Vec<u8>>> Write for GeneratorWriter<G> {
+    /// Buffers [buf] resuming the generator with each chunk filled up, writing to a completed generator fails with [io::ErrorKind::BrokenPipe]
+    /// A panic of the generator is returned as error of kind [io::ErrorKind::Other], whereby the bytes of the chunk it panicked on count as not written
+    /// Once a chunk has been accepted the bytes sent so far are returned instead of a failure on a later chunk, the next write reports the completion
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut written = 0;
+        while !self.0.has_completed() {
+            let rest = &buf[written..];
+            if self.1.len() + rest.len() < self.2 {
+                self.1.extend_from_slice(rest);
+                return Ok(buf.len());
+            }
+            let filling = &rest[..self.2 - self.1.len()];
+            let mut chunk = mem::replace(&mut self.1, Vec::with_capacity(self.2));
+            chunk.extend_from_slice(filling);
+            match self.0.resume_caught(chunk) {
+                Ok(_) => written += filling.len(),
+                Err(e) if written == 0 => return Err(failure(e)),
+                Err(_) => return Ok(written),
+            }
+        }
+        if written == 0 {
+            return Err(io::Error::new(io::ErrorKind::BrokenPipe, "generator has completed"));
+        }
+        Ok(written)
+    }
+
+    /// Resumes the generator with the bytes buffered so far even if they don't fill a chunk, nothing is sent if there are none
+    fn flush(&mut self) -> io::Result<()> {
+        if self.1.is_empty() {
+            return Ok(());
+        }
+        let chunk = mem::take(&mut self.1);
+        self.send(chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, BufRead, BufReader, Cursor, Read, Write};
+
+    use crate::generators::{BoostedGenerator, GeneratorChannel, GeneratorError, GeneratorExt, ResultingGenerator};
+
+    use super::{lines_of, split_of};
+
+    /// Reads its content and fails afterwards
+    struct FailingReader(&'static [u8]);
+
+    impl Read for FailingReader {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.0.is_empty() {
+                return Err(io::Error::new(io::ErrorKind::ConnectionReset, "connection reset"));
+            }
+            let len = buf.len().min(self.0.len());
+            buf[..len].copy_from_slice(&self.0[..len]);
+            self.0 = &self.0[len..];
+            Ok(len)
+        }
+    }
+
+    /// Consumes the chunks sent until the empty one, returning their count, the number of bytes and their sum
+    fn summarize() -> BoostedGenerator<'static, (), (usize, usize, u64), Vec<u8>> {
+        BoostedGenerator::new_receiving(|chan, mut chunk: Vec<u8>| {
+            let mut summary = (0, 0, 0);
+            while !chunk.is_empty() {
+                summary.0 += 1;
+                summary.1 += chunk.len();
+                summary.2 += chunk.iter().map(|&b| b as u64).sum::<u64>();
+                chunk = chan.yield_val(());
+            }
+            summary
+        })
+    }
+
+    #[test]
+    fn chunks_are_read_to_end() {
+        let reader = BoostedGenerator::new(|chan| {
+            let mut checksum = 0u32;
+            for chunk in [&b"hello "[..], b"", b"generated ", b"world"] {
+                checksum += chunk.iter().map(|&b| b as u32).sum::<u32>();
+                chan.yield_val(chunk.to_vec());
+            }
+            checksum
+        }).into_reader();
+        let mut buffered = BufReader::with_capacity(4, reader);
+        let mut content = Vec::new();
+        buffered.read_to_end(&mut content).unwrap();
+        assert_eq!(content, b"hello generated world");
+        let checksum = content.iter().map(|&b| b as u32).sum::<u32>();
+        assert_eq!(buffered.into_inner().into_result(), Ok(checksum));
+    }
+
+    #[test]
+    fn bytes_are_read_in_chunks() {
+        let reader = BoostedGenerator::new(|chan| {
+            chan.yield_all("first\nsecond\n".bytes());
+            2
+        }).into_byte_reader();
+        let mut buffered = BufReader::new(reader);
+        let lines = buffered.by_ref().lines().collect::<io::Result<Vec<_>>>().unwrap();
+        assert_eq!(lines, vec!["first", "second"]);
+        assert_eq!(buffered.into_inner().into_result(), Ok(2));
+    }
+
+    #[test]
+    fn panic_is_read_as_error() {
+        let reader = BoostedGenerator::<Vec<u8>, (), ()>::new(|chan| {
+            chan.yield_val(b"partial".to_vec());
+            panic!("corrupt input")
+        }).into_reader();
+        let mut buffered = BufReader::new(reader);
+        let mut content = Vec::new();
+        let error = buffered.read_to_end(&mut content).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::Other);
+        assert_eq!(error.to_string(), "corrupt input");
+        assert_eq!(content, b"partial");
+        assert_eq!(buffered.read(&mut [0; 4]).unwrap(), 0);
+        assert_eq!(buffered.into_inner().into_result(), Err(GeneratorError::Panicked { message: Some("corrupt input".to_string()) }));
+    }
+
+    #[test]
+    fn megabytes_are_written_in_chunks() {
+        let data: Vec<u8> = (0..3_000_000u32).map(|i| (i % 251) as u8).collect();
+        let mut writer = summarize().into_writer_with_chunk_size(64 * 1024);
+        for piece in data.chunks(1000) {
+            writer.write_all(piece).unwrap();
+        }
+        let sum = data.iter().map(|&b| b as u64).sum::<u64>();
+        // 45 full chunks and the partial one sent by finish
+        assert_eq!(writer.finish(), Ok((46, 3_000_000, sum)));
+    }
+
+    #[test]
+    fn flush_sends_partial_chunk() {
+        let mut writer = BoostedGenerator::new_receiving(|chan, mut chunk: Vec<u8>| {
+            let mut lengths = Vec::new();
+            while !chunk.is_empty() {
+                lengths.push(chunk.len());
+                chunk = chan.yield_val(());
+            }
+            lengths
+        }).into_writer();
+        writer.write_all(b"0123456789").unwrap();
+        writer.flush().unwrap();
+        writer.flush().unwrap();
+        writer.write_all(b"01234").unwrap();
+        assert_eq!(writer.finish(), Ok(vec![10, 5]));
+    }
+
+    #[test]
+    fn write_after_completion_is_broken_pipe() {
+        let mut writer = BoostedGenerator::<(), usize, Vec<u8>>::new_receiving(|_, chunk| chunk.len()).into_writer_with_chunk_size(4);
+        writer.write_all(b"abcd").unwrap();
+        assert_eq!(writer.write(b"e").unwrap_err().kind(), io::ErrorKind::BrokenPipe);
+        assert_eq!(writer.finish(), Ok(4));
+    }
+
+    #[test]
+    fn completion_within_write_returns_bytes_sent() {
+        let mut writer = BoostedGenerator::<(), usize, Vec<u8>>::new_receiving(|chan, first| first.len() + chan.yield_val(()).len()).into_writer_with_chunk_size(4);
+        writer.write_all(b"ab").unwrap();
+        assert_eq!(writer.write(b"cdefghijkl").unwrap(), 6);
+        assert_eq!(writer.write(b"ijkl").unwrap_err().kind(), io::ErrorKind::BrokenPipe);
+        assert_eq!(writer.finish(), Ok(8));
+    }
+
+    #[test]
+    fn panic_within_write_returns_bytes_sent() {
+        let mut writer = BoostedGenerator::<(), (), Vec<u8>>::new_receiving(|chan, _| {
+            chan.yield_val(());
+            panic!("disk full")
+        }).into_writer_with_chunk_size(4);
+        assert_eq!(writer.write(b"abcdefgh").unwrap(), 4);
+        assert_eq!(writer.write(b"efgh").unwrap_err().kind(), io::ErrorKind::BrokenPipe);
+        assert_eq!(writer.finish(), Err(GeneratorError::Panicked { message: Some("disk full".to_string()) }));
+    }
+
+    #[test]
+    fn panic_is_written_as_error() {
+        let mut writer = BoostedGenerator::<(), (), Vec<u8>>::new_receiving(|_, _| panic!("disk full")).into_writer_with_chunk_size(4);
+        let error = writer.write_all(b"abcd").unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::Other);
+        assert_eq!(error.to_string(), "disk full");
+        assert_eq!(writer.finish(), Err(GeneratorError::Panicked { message: Some("disk full".to_string()) }));
+    }
+
+    #[test]
+    fn lines_are_yielded_lazily() {
+        let mut lines = lines_of(Cursor::new("first\r\nsecond\n\nlast"));
+        assert_eq!(lines.next().as_deref(), Some("first"));
+        assert_eq!(lines.by_ref().collect::<Vec<_>>(), vec!["second", "", "last"]);
+        assert!(lines.result().unwrap().is_ok());
+    }
+
+    #[test]
+    fn invalid_utf8_line_ends_generator() {
+        let mut lines = lines_of(Cursor::new(b"valid\n\xff\xfe\nnot reached".to_vec()));
+        assert_eq!(lines.by_ref().collect::<Vec<_>>(), vec!["valid"]);
+        assert_eq!(lines.result().unwrap().unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn read_error_is_returned() {
+        let tokens = split_of(BufReader::with_capacity(3, FailingReader(b"a,bc,def")), b',');
+        let mut values = Vec::new();
+        let error = tokens.drain_with(|token| values.push(token)).unwrap().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::ConnectionReset);
+        assert_eq!(values, vec![b"a".to_vec(), b"bc".to_vec()]);
+    }
+
+    #[test]
+    fn tokens_are_split_by_delimiter() {
+        let mut tokens = split_of(Cursor::new(b"a b\xff  c"), b' ');
+        assert_eq!(tokens.by_ref().collect::<Vec<_>>(), vec![b"a".to_vec(), b"b\xff".to_vec(), Vec::new(), b"c".to_vec()]);
+        assert!(tokens.result().unwrap().is_ok());
+    }
+}
@@ -1,4 +1,28 @@
-mod transfer;
+#![cfg_attr(feature = "nightly", feature(coroutine_trait))]
+pub mod transfer;
+mod stack;
+#[cfg(unix)]
+mod mapped;
+pub mod raw;
 pub mod coroutines;
 pub mod generators;
+pub mod io;
 mod utils;
+mod trace;
+mod backtrace;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(all(unix, feature = "stack-overflow-recovery"))]
+mod overflow;
+#[cfg(feature = "nightly")]
+mod nightly;
+#[cfg(feature = "futures")]
+mod stream;
+#[cfg(feature = "rayon")]
+mod parallel;
+
+pub use coroutines::{Coroutine, CoroutineChannel, CoroutineError, ResumeResult, StackFactory};
+pub use generators::{BoostedGenerator, BoringGenerator, Generator, GeneratorChannel, GeneratorExt, ResultingGenerator};
+pub use raw::{RawChannel, RawCoroutine};
+#[cfg(feature = "nightly")]
+pub use nightly::StdCoroutine;
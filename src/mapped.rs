@@ -0,0 +1,98 @@
+use std::io;
+use std::ops::Deref;
+use std::os::raw::{c_int, c_void};
+use std::ptr::null_mut;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use std::sync::OnceLock;
+
+use context::stack::{Stack, StackError};
+
+use crate::stack::page_size;
+use crate::trace;
+
+/// Callstack mapped by this crate rather than by the context crate, which allows to choose the size of its guard region
+/// The guard pages lie right below the bottom of the stack and are part of the same mapping, which is released on drop
+pub(crate) struct MappedStack {
+    mapping: *mut c_void,
+    len: usize,
+    guard: usize,
+    stack: Stack,
+}
+
+impl MappedStack {
+    /// Maps a stack of at least [size] bytes with [guard_pages] inaccessible pages below its bottom
+    pub(crate) fn new(size: usize, guard_pages: usize) -> Result<Self, StackError> {
+        Self::with_flags(size, guard_pages, 0)
+    }
+
+    /// Like [new] but passes [flags] to mmap in addition to the flags of an anonymous private mapping
+    /// A huge page mapping is rounded up to the huge page size and gets no guard pages, as only whole huge pages could be protected within it
+    /// Falls back to a mapping without [flags] with the guard pages if the kernel refuses them (e.g. as no huge pages are available), which is traced as warning
+    pub(crate) fn with_flags(size: usize, guard_pages: usize, flags: c_int) -> Result<Self, StackError> {
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        let (mapped_size, mapped_guard) = if flags & libc::MAP_HUGETLB != 0 {
+            let huge_page = huge_page_size();
+            (size.max(huge_page).div_ceil(huge_page) * huge_page, 0)
+        } else {
+            (size, guard_pages)
+        };
+        #[cfg(not(any(target_os = "linux", target_os = "android")))]
+        let (mapped_size, mapped_guard) = (size, guard_pages);
+        match Self::map(mapped_size, mapped_guard, flags) {
+            Err(error) if flags != 0 => {
+                trace::warn(&format!("mapping stack with flags {:#x} failed ({}), falling back to normal pages", flags, error));
+                Self::map(size, guard_pages, 0)
+            }
+            result => result
+        }
+    }
+
+    fn map(size: usize, guard_pages: usize, flags: c_int) -> Result<Self, StackError> {
+        let page = page_size();
+        let size = size.max(page).div_ceil(page) * page;
+        let guard = guard_pages * page;
+        let len = size + guard;
+        unsafe {
+            let mapping = libc::mmap(null_mut(), len, libc::PROT_READ | libc::PROT_WRITE, libc::MAP_PRIVATE | libc::MAP_ANON | flags, -1, 0);
+            if mapping == libc::MAP_FAILED {
+                return Err(StackError::IoError(io::Error::last_os_error()));
+            }
+            if guard > 0 && libc::mprotect(mapping, guard, libc::PROT_NONE) != 0 {
+                let error = io::Error::last_os_error();
+                libc::munmap(mapping, len);
+                return Err(StackError::IoError(error));
+            }
+            let stack = Stack::new((mapping as usize + len) as *mut c_void, (mapping as usize + guard) as *mut c_void);
+            Ok(Self { mapping, len, guard, stack })
+        }
+    }
+
+    /// Returns the size of the guard region below the stack in bytes
+    pub(crate) fn guard_size(&self) -> usize {
+        self.guard
+    }
+}
+
+/// Returns the size of the default huge pages as /proc/meminfo tells it, 2 MiB if it can't be read
+/// The file is only parsed by the first call, the size doesn't change while the process runs
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(crate) fn huge_page_size() -> usize {
+    static HUGE_PAGE_SIZE: OnceLock<usize> = OnceLock::new();
+    *HUGE_PAGE_SIZE.get_or_init(|| std::fs::read_to_string("/proc/meminfo").ok()
+        .and_then(|info| info.lines().find_map(|line| line.strip_prefix("Hugepagesize:")?.trim().strip_suffix("kB")?.trim().parse::<usize>().ok()))
+        .map_or(2 * 1024 * 1024, |kib| kib * 1024))
+}
+
+impl Deref for MappedStack {
+    type Target = Stack;
+
+    fn deref(&self) -> &Stack {
+        &self.stack
+    }
+}
+
+impl Drop for MappedStack {
+    fn drop(&mut self) {
+        unsafe { libc::munmap(self.mapping, self.len) };
+    }
+}
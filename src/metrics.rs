@@ -0,0 +1,20 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Context switches performed by all coroutine contexts of the process
+static CONTEXT_SWITCHES: AtomicU64 = AtomicU64::new(0);
+
+/// Returns how many context switches all coroutine contexts of the process have performed since start or the last [reset]
+/// Switches into a context and back out of it count separately, so a single resume of a coroutine counts twice
+pub fn context_switches() -> u64 {
+    CONTEXT_SWITCHES.load(Ordering::Relaxed)
+}
+
+/// Sets the context switch counter back to zero, e.g. in between benchmark runs
+pub fn reset() {
+    CONTEXT_SWITCHES.store(0, Ordering::Relaxed)
+}
+
+#[inline]
+pub(crate) fn count_switch() {
+    CONTEXT_SWITCHES.fetch_add(1, Ordering::Relaxed);
+}
@@ -0,0 +1,140 @@
+use std::ops::{Coroutine as StdCoroutineTrait, CoroutineState};
+use std::pin::Pin;
+
+use crate::coroutines::{Coroutine, ResumeResult};
+use crate::generators::{BoostedGenerator, Generator, GeneratorError};
+
+/// Makes a [Coroutine] usable through the unstable std::ops::Coroutine trait, so code can be written generic over both implementations
+/// Resuming it after completion panics, like resuming a completed [Coroutine] does
+pub struct StdCoroutine<'a, Yield: 'static, Return: 'static, Receive: 'a>(Coroutine<'a, Yield, Return, Receive>);
+
+impl<'a, Yield: 'static, Return: 'static, Receive: 'a> StdCoroutine<'a, Yield, Return, Receive> {
+    /// Wraps [coroutine] which may have been resumed already
+    pub fn new(coroutine: Coroutine<'a, Yield, Return, Receive>) -> Self {
+        Self(coroutine)
+    }
+    /// Hands back the wrapped coroutine
+    pub fn into_inner(self) -> Coroutine<'a, Yield, Return, Receive> {
+        self.0
+    }
+}
+
+/// The wrapped coroutine is never pinned structurally, buffered yields are moved out freely
+impl<'a, Yield: 'static, Return: 'static, Receive: 'a> Unpin for StdCoroutine<'a, Yield, Return, Receive> {}
+
+impl<'a, Yield: 'static, Return: 'static, Receive: 'a> From<Coroutine<'a, Yield, Return, Receive>> for StdCoroutine<'a, Yield, Return, Receive> {
+    fn from(coroutine: Coroutine<'a, Yield, Return, Receive>) -> Self {
+        Self::new(coroutine)
+    }
+}
+
+impl<'a, Yield: 'static, Return: 'static, Receive: 'a> StdCoroutineTrait<Receive> for StdCoroutine<'a, Yield, Return, Receive> {
+    type Yield = Yield;
+    type Return = Return;
+
+    /// The coroutine context lives on its own callstack, so the wrapper does not need to stay in place and pinning it is a no-op
+    fn resume(self: Pin<&mut Self>, arg: Receive) -> CoroutineState<Yield, Return> {
+        match self.get_mut().0.resume(arg) {
+            ResumeResult::Yield(y) => CoroutineState::Yielded(y),
+            ResumeResult::Return(r) => CoroutineState::Complete(r),
+        }
+    }
+}
+
+/// Neither the yielded values nor the result are pinned structurally, the generating function runs on a callstack of its own
+impl<'a, Y: 'static, Ret: 'static, Rec: 'a> Unpin for BoostedGenerator<'a, Y, Ret, Rec> {}
+
+/// Makes a [BoostedGenerator] usable through the unstable std::ops::Coroutine trait, whereby its result or the reason there is none is the return value
+/// Resuming it after completion panics, like resuming a completed [StdCoroutine] does
+impl<'a, Y: 'static, Ret: 'static, Rec: 'a> StdCoroutineTrait<Rec> for BoostedGenerator<'a, Y, Ret, Rec> {
+    type Yield = Y;
+    type Return = Result<Ret, GeneratorError>;
+
+    /// The coroutine context of the generator lives on its own callstack, so pinning the generator is a no-op
+    fn resume(self: Pin<&mut Self>, arg: Rec) -> CoroutineState<Y, Result<Ret, GeneratorError>> {
+        let generator = self.get_mut();
+        assert!(!generator.has_completed(), "tried to resume completed generator");
+        match generator.resume_checked(arg) {
+            Ok(Some(y)) => CoroutineState::Yielded(y),
+            Ok(None) => CoroutineState::Complete(generator.take_result().ok_or(GeneratorError::Taken)),
+            Err(e) => CoroutineState::Complete(Err(GeneratorError::from(e))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::{Coroutine as StdCoroutineTrait, CoroutineState};
+    use std::pin::Pin;
+
+    use crate::coroutines::Coroutine;
+    use crate::generators::{BoostedGenerator, GeneratorChannel, GeneratorError};
+
+    use super::StdCoroutine;
+
+    /// Drives any std coroutine receiving i32 until it completes and collects its yields
+    fn drive<R, C: StdCoroutineTrait<i32, Yield=i32, Return=R> + Unpin>(mut co: C) -> (Vec<i32>, R) {
+        let mut yields = Vec::new();
+        let mut send = 0;
+        loop {
+            match Pin::new(&mut co).resume(send) {
+                CoroutineState::Yielded(y) => {
+                    yields.push(y);
+                    send = y;
+                }
+                CoroutineState::Complete(r) => return (yields, r),
+            }
+        }
+    }
+
+    #[test]
+    fn drives_through_std_trait() {
+        let co = Coroutine::new(|chan, mut i: i32| {
+            while i < 3 {
+                i = chan.suspend(i + 1);
+            }
+            "done"
+        });
+        assert_eq!(drive(StdCoroutine::from(co)), (vec![1, 2, 3], "done"));
+    }
+
+    #[test]
+    #[should_panic(expected = "tried to send to non-running context")]
+    fn resume_after_completion_panics() {
+        let mut co = StdCoroutine::new(Coroutine::<(), (), ()>::new(|_, _| ()));
+        assert!(matches!(Pin::new(&mut co).resume(()), CoroutineState::Complete(())));
+        let _ = Pin::new(&mut co).resume(());
+    }
+
+    #[test]
+    fn drives_generator_through_std_trait() {
+        let gen = BoostedGenerator::new_receiving(|chan, mut i: i32| {
+            while i < 3 {
+                i = chan.yield_val(i + 1);
+            }
+            "done"
+        });
+        assert_eq!(drive(gen), (vec![1, 2, 3], Ok("done")));
+    }
+
+    #[test]
+    fn generator_panic_is_returned() {
+        let mut gen = BoostedGenerator::<i32, (), ()>::new(|chan| {
+            chan.yield_val(1);
+            panic!("failed")
+        });
+        assert!(matches!(Pin::new(&mut gen).resume(()), CoroutineState::Yielded(1)));
+        match Pin::new(&mut gen).resume(()) {
+            CoroutineState::Complete(result) => assert_eq!(result, Err(GeneratorError::Panicked { message: Some("failed".to_string()) })),
+            CoroutineState::Yielded(_) => panic!("failed generator yielded"),
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "tried to resume completed generator")]
+    fn resume_of_completed_generator_panics() {
+        let mut gen = BoostedGenerator::<(), (), ()>::new(|_| ());
+        assert!(matches!(Pin::new(&mut gen).resume(()), CoroutineState::Complete(Ok(()))));
+        let _ = Pin::new(&mut gen).resume(());
+    }
+}
@@ -0,0 +1,147 @@
+use std::cell::Cell;
+use std::mem::{zeroed, MaybeUninit};
+use std::os::raw::{c_int, c_void};
+use std::ptr::{addr_of, addr_of_mut, null_mut};
+use std::sync::Once;
+
+use crate::stack::OwnedStack;
+use libc::{sigaction, siginfo_t, stack_t};
+
+/// Size of the alternate signal stack the fault handler runs on, in case the thread does not have one yet
+const ALT_STACK_SIZE: usize = 64 * 1024;
+
+/// Signals a guard page hit is reported with (macOS raises SIGBUS instead of SIGSEGV)
+const HANDLED_SIGNALS: [c_int; 2] = [libc::SIGSEGV, libc::SIGBUS];
+
+/// Describes the coroutine context currently executing on this thread
+/// The fault handler uses the guard page range to decide whether a fault is an overflow of this context
+/// and completes the context by calling [dispose] with the coroutine sides transfer in that case
+#[derive(Clone, Copy)]
+struct ActiveContext {
+    guard: (usize, usize),
+    transfer: *mut (),
+    dispose: unsafe fn(*mut ()) -> !,
+}
+
+thread_local! {
+    /// Guard page of the stack the invocation context is about to resume, picked up by the resumed context
+    static RESUMING: Cell<Option<(usize, usize)>> = const { Cell::new(None) };
+    /// The coroutine context executing right now (if any)
+    static ACTIVE: Cell<Option<ActiveContext>> = const { Cell::new(None) };
+    /// Alternate stack the fault handler runs on, since the faulting stack is exhausted
+    static ALT_STACK: AltStack = AltStack::install();
+}
+
+static INSTALL_HANDLER: Once = Once::new();
+static mut PREVIOUS_ACTIONS: [MaybeUninit<sigaction>; 2] = [MaybeUninit::uninit(), MaybeUninit::uninit()];
+
+/// Marks the resumption of a coroutine context from the invocation side
+/// Announces the guard page of the resumed stack and restores the context that has been active before on drop,
+/// i.e. as soon as the resumed context suspended again (which also makes nested coroutines work)
+pub(crate) struct ResumeScope(Option<ActiveContext>);
+
+impl ResumeScope {
+    /// Prepares resuming a context running on [stack], whose guard region lies right below its bottom
+    pub(crate) fn enter(stack: &OwnedStack) -> Self {
+        INSTALL_HANDLER.call_once(install_handler);
+        ALT_STACK.with(|_| ());
+        let bottom = stack.bottom() as usize;
+        RESUMING.with(|resuming| resuming.set(Some((bottom - stack.guard_size(), bottom))));
+        Self(ACTIVE.with(|active| active.get()))
+    }
+}
+
+impl Drop for ResumeScope {
+    fn drop(&mut self) {
+        let previous = self.0;
+        ACTIVE.with(|active| active.set(previous));
+    }
+}
+
+/// Called by a coroutine context each time it gained execution control
+/// Registers the context as the active one using the guard page announced by the resuming [ResumeScope]
+pub(crate) fn activate(transfer: *mut (), dispose: unsafe fn(*mut ()) -> !) {
+    if let Some(guard) = RESUMING.with(|resuming| resuming.take()) {
+        ACTIVE.with(|active| active.set(Some(ActiveContext { guard, transfer, dispose })));
+    }
+}
+
+/// Owns an alternate signal stack allocated for the current thread
+/// Stays empty if the thread already had one (e.g. set up by std for its own overflow detection)
+struct AltStack(Option<*mut c_void>);
+
+impl AltStack {
+    fn install() -> Self {
+        unsafe {
+            let mut current: stack_t = zeroed();
+            libc::sigaltstack(null_mut(), &mut current);
+            if current.ss_flags & libc::SS_DISABLE == 0 {
+                return Self(None);
+            }
+            let memory = libc::mmap(null_mut(), ALT_STACK_SIZE, libc::PROT_READ | libc::PROT_WRITE,
+                                    libc::MAP_PRIVATE | libc::MAP_ANON, -1, 0);
+            if memory == libc::MAP_FAILED {
+                return Self(None);
+            }
+            let mut alt_stack: stack_t = zeroed();
+            alt_stack.ss_sp = memory;
+            alt_stack.ss_size = ALT_STACK_SIZE;
+            libc::sigaltstack(&alt_stack, null_mut());
+            Self(Some(memory))
+        }
+    }
+}
+
+impl Drop for AltStack {
+    /// Disables the alternate stack before releasing its memory
+    fn drop(&mut self) {
+        if let Some(memory) = self.0 {
+            unsafe {
+                let mut disabled: stack_t = zeroed();
+                disabled.ss_flags = libc::SS_DISABLE;
+                libc::sigaltstack(&disabled, null_mut());
+                libc::munmap(memory, ALT_STACK_SIZE);
+            }
+        }
+    }
+}
+
+/// Installs [handle_fault] for all handled signals remembering the previous actions
+fn install_handler() {
+    unsafe {
+        for (i, signal) in HANDLED_SIGNALS.iter().enumerate() {
+            let mut action: sigaction = zeroed();
+            action.sa_sigaction = handle_fault as extern "C" fn(c_int, *mut siginfo_t, *mut c_void) as usize;
+            action.sa_flags = libc::SA_SIGINFO | libc::SA_ONSTACK | libc::SA_NODEFER;
+            libc::sigemptyset(&mut action.sa_mask);
+            libc::sigaction(*signal, &action, (*addr_of_mut!(PREVIOUS_ACTIONS))[i].as_mut_ptr());
+        }
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+unsafe fn fault_address(info: *const siginfo_t) -> usize {
+    (*info).si_addr() as usize
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+unsafe fn fault_address(info: *const siginfo_t) -> usize {
+    (*info).si_addr as usize
+}
+
+/// Signal handler completing the active coroutine context in case the fault hit its guard page
+/// The faulting context is never resumed (which means destructors of values on its stack won't run) but switches to its invocation context for good
+/// Other faults are not ours to handle, so the previous action is restored and the faulting instruction is executed again
+extern "C" fn handle_fault(signal: c_int, info: *mut siginfo_t, _context: *mut c_void) {
+    unsafe {
+        let address = fault_address(info);
+        if let Some(active) = ACTIVE.try_with(|active| active.get()).ok().flatten() {
+            if address >= active.guard.0 && address < active.guard.1 {
+                (active.dispose)(active.transfer)
+            }
+        }
+        if let Some(i) = HANDLED_SIGNALS.iter().position(|s| *s == signal) {
+            libc::sigaction(signal, (*addr_of!(PREVIOUS_ACTIONS))[i].as_ptr(), null_mut());
+        }
+    }
+}
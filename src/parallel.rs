@@ -0,0 +1,94 @@
+use crossbeam_channel::IntoIter;
+use rayon::iter::plumbing::UnindexedConsumer;
+use rayon::iter::{IterBridge, ParallelBridge, ParallelIterator};
+
+use crate::generators::{ResultHandle, ResultingGenerator};
+
+/// Parallel iterator over the values of a generator driven by a thread of its own, see [crate::generators::GeneratorExt::par_bridge_buffered]
+/// The values are handed over by a bounded channel, so the generator runs at most its capacity ahead of the rayon tasks consuming them
+pub struct ParYields<Y: Send>(IterBridge<IntoIter<Y>>);
+
+impl<Y: Send + 'static> ParYields<Y> {
+    pub(crate) fn spawn<'a, G>(generator: G, buffer: usize) -> (Self, ResultHandle<G::Return>)
+        where G: ResultingGenerator<'a, Yield=Y, Receive=()> + Send + 'static, G::Return: Send {
+        let (sender, receiver) = crossbeam_channel::bounded(buffer);
+        // sending fails once the parallel iterator has been dropped, which cancels the generator
+        let handle = ResultHandle::spawn(generator, move |val| sender.send(val).is_ok());
+        (ParYields(receiver.into_iter().par_bridge()), handle)
+    }
+}
+
+impl<Y: Send> ParallelIterator for ParYields<Y> {
+    type Item = Y;
+
+    fn drive_unindexed<C: UnindexedConsumer<Y>>(self, consumer: C) -> C::Result {
+        self.0.drive_unindexed(consumer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    use rayon::iter::ParallelIterator;
+
+    use crate::generators::{BoostedGenerator, GeneratorChannel, GeneratorError, GeneratorExt, SendGenerator};
+
+    fn hash(val: u64) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        val.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn numbers(count: u64) -> SendGenerator<BoostedGenerator<'static, u64, u64, ()>> {
+        unsafe {
+            SendGenerator::new(BoostedGenerator::new(move |chan| {
+                chan.yield_all(0..count);
+                count
+            }))
+        }
+    }
+
+    #[test]
+    fn yields_are_hashed_in_parallel() {
+        let (yields, handle) = numbers(10_000).par_bridge_buffered(64);
+        let mut parallel: Vec<u64> = yields.map(hash).collect();
+        parallel.sort_unstable();
+        assert_eq!(handle.join(), Ok(10_000));
+
+        let mut sequential: Vec<u64> = numbers(10_000).map(hash).collect();
+        sequential.sort_unstable();
+        assert_eq!(parallel, sequential);
+    }
+
+    #[test]
+    fn panic_is_reported_by_handle() {
+        let generator = unsafe {
+            SendGenerator::new(BoostedGenerator::<u64, (), ()>::new(|chan| {
+                chan.yield_all(0..100);
+                panic!("driver failed")
+            }))
+        };
+        let (yields, handle) = generator.par_bridge_buffered(8);
+        assert_eq!(yields.count(), 100);
+        assert_eq!(handle.join(), Err(GeneratorError::Panicked { message: Some("driver failed".to_string()) }));
+    }
+
+    #[test]
+    fn dropping_iterator_cancels_generator() {
+        let generator = unsafe {
+            SendGenerator::new(BoostedGenerator::<u64, u64, ()>::new(|chan| {
+                let mut sent = 0;
+                while !chan.is_cancelled() {
+                    chan.yield_val(sent);
+                    sent += 1;
+                }
+                sent
+            }))
+        };
+        let (yields, handle) = generator.par_bridge_buffered(4);
+        drop(yields);
+        assert!(handle.join().unwrap() <= 5);
+    }
+}
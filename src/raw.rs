@@ -0,0 +1,199 @@
+//! Bare context switching without the yield/return/drop protocol of [Coroutine](crate::coroutines::Coroutine), e.g. to embed contexts into a custom scheduler
+//! [Coroutine](crate::coroutines::Coroutine) itself is built on top of this module
+use std::hint::black_box;
+use std::mem::ManuallyDrop;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::process::abort;
+use std::ptr;
+
+use context::{ContextFn, Transfer};
+
+use crate::stack::{OwnedStack, StackFactory};
+use crate::transfer::{ExchangingTransfer, RawExchangeState};
+
+/// Handle on the invoking side of a context running an entry function on its own callstack
+/// Each [switch] moves a value of type [Send] into the context and blocks until the context switches back handing over a value of type [Receive]
+///
+/// # Safety
+/// The API itself is safe, but it deliberately lacks everything [Coroutine](crate::coroutines::Coroutine) adds on top:
+/// - there is no unwinding: dropping a handle whose entry has not returned yet LEAKS the callstack including everything living on it,
+///   i.e. destructors of values on the stack never run (the memory is not released either, since pinned values may live there)
+/// - a panic escaping the entry function aborts the process, as it can't unwind beyond the bottom of the context
+/// - switching to a context whose entry has returned panics (see [is_finished])
+///
+/// The handle may be moved in between switches, the context finds it by the address sent along with each switch
+///
+/// ```
+/// use rusterators::coroutines::StackFactory;
+/// use rusterators::raw::RawCoroutine;
+///
+/// let mut pong = RawCoroutine::<&str, usize>::spawn(StackFactory::default_stack(), |chan, mut ball| {
+///     let mut hits = 0;
+///     while ball == "ping" {
+///         hits += 1;
+///         ball = chan.switch(hits);
+///     }
+///     hits
+/// });
+/// assert_eq!(pong.switch("ping"), 1);
+/// assert_eq!(pong.switch("ping"), 2);
+/// assert_eq!(pong.switch("stop"), 2);
+/// assert!(pong.is_finished());
+/// ```
+pub struct RawCoroutine<'a, Send, Receive>(ExchangingTransfer<'a, Send, Receive>, Option<OwnedStack>);
+
+/// Handle on the context side handed to the entry function of a [RawCoroutine]
+/// [switch] hands a value of type [Send] to the invoking side and blocks until it switches back handing over a value of type [Receive]
+/// The second field is the lowest address of the stack the context currently runs on
+pub struct RawChannel<'a, Send, Receive>(ExchangingTransfer<'a, Send, Receive>, usize);
+
+impl<'a, Send, Receive> RawCoroutine<'a, Send, Receive> {
+    /// Builds a callstack by [stack_factory] and starts a context on it which waits for the first [switch]
+    /// [entry] is called with the value sent by that switch, its result is handed over by the switch following its last [RawChannel::switch]
+    pub fn spawn<F>(stack_factory: StackFactory, entry: F) -> Self where F: FnOnce(&mut RawChannel<Receive, Send>, Send) -> Receive + 'a {
+        Self::spawn_context(stack_factory, run_raw_context::<Send, Receive, F>, entry)
+    }
+    /// Starts a context running [context_fn] on a callstack built by [stack_factory] and transfers [initial] to it
+    /// [context_fn] is expected to accept the transfer by [RawChannel::accept] and to suspend right away
+    pub(crate) fn spawn_context<V>(stack_factory: StackFactory, context_fn: ContextFn, initial: V) -> Self {
+        let stack = stack_factory.build();
+        let limit = stack.bottom() as usize;
+        let (transfer, stack) = ExchangingTransfer::init_context_sending(stack, context_fn, (initial, limit));
+        Self(transfer, Some(stack))
+    }
+    /// Moves [payload] into the context and waits for it to switch back
+    /// Panics if the entry function has already returned
+    pub fn switch(&mut self, payload: Send) -> Receive {
+        self.0.yield_with(payload)
+    }
+    /// Queries whether the entry function has returned, after which the context is gone
+    pub fn is_finished(&self) -> bool {
+        !self.0.can_send()
+    }
+    /// Returns the callstack the context runs on
+    pub fn stack(&self) -> &OwnedStack {
+        self.1.as_ref().expect("stack has been taken")
+    }
+    /// Hands back the callstack once the entry function has returned, so it can be reused (see [StackFactory::of_stack])
+    /// Returns None and leaks the stack like dropping does if the context is still suspended
+    pub fn into_stack(mut self) -> Option<OwnedStack> {
+        if self.is_finished() { self.1.take() } else { None }
+    }
+    /// Takes the handle apart in between switches without leaking the callstack, see [ExchangingTransfer::into_raw]
+    pub(crate) fn into_raw_parts(self) -> (Transfer, RawExchangeState, Option<OwnedStack>) {
+        let mut this = ManuallyDrop::new(self);
+        let stack = this.1.take();
+        // the transfer is moved out once, the handle is forgotten without dropping it
+        let transfer = unsafe { ptr::read(&this.0) };
+        let (raw, state) = transfer.into_raw();
+        (raw, state, stack)
+    }
+    /// Rebuilds a handle taken apart by [into_raw_parts]
+    ///
+    /// # Safety
+    /// See [ExchangingTransfer::from_raw], [stack] must be the one the context runs on
+    pub(crate) unsafe fn from_raw_parts(transfer: Transfer, state: RawExchangeState, stack: OwnedStack) -> Self {
+        Self(ExchangingTransfer::from_raw(transfer, state), Some(stack))
+    }
+}
+
+impl<'a, Send, Receive> Drop for RawCoroutine<'a, Send, Receive> {
+    /// Leaks the callstack of a suspended context, since releasing it would skip the destructors of values living on it
+    fn drop(&mut self) {
+        if !self.is_finished() {
+            std::mem::forget(self.1.take());
+        }
+    }
+}
+
+impl<'a, Send, Receive> RawChannel<'a, Send, Receive> {
+    /// Hands [payload] to the invoking side and waits for it to switch back
+    pub fn switch(&mut self, payload: Send) -> Receive {
+        self.0.yield_with(payload)
+    }
+    /// Returns roughly how many bytes are left on the stack the context currently runs on
+    pub fn remaining_stack(&self) -> usize {
+        stack_pointer().saturating_sub(self.1)
+    }
+    /// Replaces the lowest address of the current stack, e.g. while running on another stack segment, and returns the previous one
+    #[cfg(feature = "segmented-stacks")]
+    pub(crate) fn replace_stack_limit(&mut self, limit: usize) -> usize {
+        std::mem::replace(&mut self.1, limit)
+    }
+    /// Picks up the value transferred by [RawCoroutine::spawn_context] within a freshly started context
+    ///
+    /// # Safety
+    /// [raw_transfer] must be the one the context function has been started with by [RawCoroutine::spawn_context] passing a [V]
+    pub(crate) unsafe fn accept<V>(raw_transfer: Transfer) -> (Self, V) {
+        let (transfer, (initial, limit)) = ExchangingTransfer::create_receiving(raw_transfer);
+        (Self(transfer, limit), initial)
+    }
+    /// Switches back without a value, which is only valid right after [accept]
+    pub(crate) fn suspend(&mut self) -> Receive {
+        self.0.suspend()
+    }
+    /// Hands [payload] to the invoking side and leaves the context for good
+    pub(crate) fn dispose_with(&mut self, payload: Send) -> ! {
+        self.0.dispose_with(payload)
+    }
+}
+
+/// Approximates the current stack pointer by the address of a local
+#[inline(never)]
+fn stack_pointer() -> usize {
+    let marker = 0u8;
+    black_box(&marker) as *const u8 as usize
+}
+
+/// "Bootstrap" function of contexts started by [RawCoroutine::spawn]
+/// Receives the entry function, waits for the first switch and finally hands over the result of the entry function
+extern "C" fn run_raw_context<Send, Receive, F>(raw_transfer: Transfer) -> !
+    where F: FnOnce(&mut RawChannel<Receive, Send>, Send) -> Receive {
+    // started by spawn passing the entry function
+    let (mut channel, entry) = unsafe { RawChannel::<Receive, Send>::accept::<F>(raw_transfer) };
+    let initial = channel.suspend();
+    match catch_unwind(AssertUnwindSafe(|| entry(&mut channel, initial))) {
+        Ok(result) => channel.dispose_with(result),
+        Err(_) => abort()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stack::StackFactory;
+    use super::RawCoroutine;
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn raw_coroutines_nest() {
+        let mut outer = RawCoroutine::<u32, u32>::spawn(StackFactory::default_stack(), |chan, first| {
+            let mut inner = RawCoroutine::<u32, u32>::spawn(StackFactory::default_stack(), |chan, v| chan.switch(v * 2) * 3);
+            let doubled = inner.switch(first);
+            let tripled = inner.switch(chan.switch(doubled));
+            assert!(inner.is_finished());
+            tripled
+        });
+        assert_eq!(outer.switch(5), 10);
+        assert_eq!(outer.switch(7), 21);
+        assert!(outer.is_finished());
+        assert!(outer.into_stack().is_some());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    #[should_panic(expected = "exchange protocol violated on yield")]
+    fn switch_to_finished_context_panics() {
+        let mut co = RawCoroutine::<(), ()>::spawn(StackFactory::default_stack(), |_, _| ());
+        co.switch(());
+        co.switch(());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn remaining_stack_is_within_stack() {
+        let mut co = RawCoroutine::<(), usize>::spawn(StackFactory::of_size(64 * 1024), |chan, _| chan.remaining_stack());
+        let size = co.stack().len();
+        let remaining = co.switch(());
+        assert!(remaining > 0 && remaining < size);
+    }
+}
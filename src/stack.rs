@@ -0,0 +1,525 @@
+use std::ffi::c_void;
+use std::fmt::{self, Debug, Formatter};
+use std::io;
+use std::ops::Deref;
+#[cfg(unix)]
+use std::os::raw::c_int;
+use std::ptr::{write_bytes, write_volatile};
+use std::slice;
+use std::sync::Arc;
+
+use context::stack::{FixedSizeStack, ProtectedFixedSizeStack, Stack, StackError};
+
+#[cfg(unix)]
+use crate::mapped::MappedStack;
+
+/// Byte pattern the stacks of measuring factories are filled with before any context runs on them
+const STACK_PAINT: u8 = 0xA5;
+
+/// Alignment the top and bottom of stacks built over caller provided buffers are rounded to, as expected by the context switch
+const BUFFER_ALIGNMENT: usize = 16;
+
+/// Byte pattern the stacks of completed coroutines are overwritten with (see debug-stack-poison feature)
+#[cfg(feature = "debug-stack-poison")]
+pub(crate) const STACK_POISON: u8 = 0xDD;
+
+/// Builds the callstack for a coroutine context as soon as it is actually needed
+/// The second field states whether built stacks get painted in order to measure their high-water mark later on,
+/// the third one whether their pages get faulted in right away (see [StackFactory::pre_touch])
+pub struct StackFactory(Box<dyn FnOnce()->OwnedStack>, bool, bool);
+
+/// Allocates the callstacks of coroutine contexts, e.g. out of a pre-reserved arena instead of mapping each stack on its own
+/// A stack allocated by an allocator passed to [StackFactory::with_allocator] is handed back to [deallocate] as soon as the coroutine owning it is dropped
+pub trait StackAllocator {
+    /// Allocates a stack of at least [size] bytes
+    fn allocate(&self, size: usize) -> Result<OwnedStack, StackError>;
+    /// Takes back a stack handed out by [allocate]
+    fn deallocate(&self, stack: OwnedStack);
+}
+
+/// Allocates every stack on its own protected by a guard page, like [StackFactory::of_size] does
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultStackAllocator;
+
+/// Keeps the callstacks of completed coroutines (see [Coroutine::recycle_into] and [Coroutine::keep_stack]) to hand them out again by [factory],
+/// so coroutines created in a loop don't map a fresh stack each time
+/// Holds at most [max_idle] stacks, stacks smaller than [stack_size] are not taken
+pub struct StackPool {
+    stack_size: usize,
+    max_idle: usize,
+    idle: Vec<OwnedStack>,
+    misses: usize,
+}
+
+/// Callstack owned by a coroutine context
+/// The memory is either protected by a guard page below its bottom, protected by several guard pages (see [StackFactory::of_size_with_guard]),
+/// unprotected (see [StackFactory::unprotected]) or managed by a [StackAllocator]
+/// or consists of a caller provided buffer (see [StackFactory::from_buffer])
+/// The second field is the allocator the stack is returned to on drop, if any
+pub struct OwnedStack(Option<StackMemory>, Option<Arc<dyn StackAllocator>>);
+
+/// The kinds of memory an [OwnedStack] may consist of
+enum StackMemory {
+    Protected(ProtectedFixedSizeStack),
+    Unprotected(FixedSizeStack),
+    #[cfg(unix)]
+    Mapped(MappedStack),
+    Raw(Stack),
+    Buffer(BufferStack),
+}
+
+/// Stack built over a boxed buffer, which is released along with it
+/// The buffer is held as raw pointer, since contexts write to it through the stack while it is owned
+struct BufferStack {
+    stack: Stack,
+    buffer: *mut [u8],
+}
+
+impl StackFactory {
+    fn new<F:FnOnce()->OwnedStack+'static>(builder:F) -> Self {
+        Self(Box::new(builder), false, false)
+    }
+
+    pub fn default_stack() -> Self {
+        Self::new(|| ProtectedFixedSizeStack::default().into())
+    }
+
+    pub fn of_size(stack_size:usize) -> Self {
+        Self::new(move || ProtectedFixedSizeStack::new(stack_size).unwrap().into())
+    }
+
+    /// Like [of_size] but without a guard page, which saves a mapping (and the kernel time spent on it) per stack
+    /// This trades safety for throughput: a context overflowing such a stack silently overwrites whatever memory lies below it
+    pub fn unprotected(stack_size:usize) -> Self {
+        Self::new(move || FixedSizeStack::new(stack_size).unwrap().into())
+    }
+
+    /// Like [of_size] but with [guard_pages] inaccessible pages below the stack instead of a single one (unix only)
+    /// Functions with large frames may skip a single guard page and write to whatever memory lies below it, a larger guard region catches those as well
+    /// Panics on building the stack if it can't be mapped
+    #[cfg(unix)]
+    pub fn of_size_with_guard(stack_size:usize, guard_pages:usize) -> Self {
+        Self::new(move || OwnedStack(Some(StackMemory::Mapped(MappedStack::new(stack_size, guard_pages).unwrap())), None))
+    }
+
+    /// Like [of_size] but maps the stack by this crate passing [flags] to mmap additionally (unix only),
+    /// e.g. MAP_POPULATE to fault in all pages up front or MAP_HUGETLB to back a large stack with huge pages.
+    /// A huge page stack is rounded up to the huge page size and has no guard page, as a single page can't be protected within a huge page mapping
+    /// If the kernel refuses [flags], the stack is mapped with normal pages and a guard page instead, which [OwnedStack::guard_size] tells apart (and a warning is traced)
+    /// Panics on building the stack if it can't be mapped at all
+    #[cfg(unix)]
+    pub fn with_mmap_flags(stack_size:usize, flags:c_int) -> Self {
+        Self::new(move || OwnedStack(Some(StackMemory::Mapped(MappedStack::with_flags(stack_size, 1, flags).unwrap())), None))
+    }
+
+    /// Builds the stack over caller provided memory, e.g. out of an arena registered up front, instead of mapping it
+    /// [buffer] is rounded to the alignment expected by the context switch and is refused by an error telling its usable size if it holds less than [Stack::min_size] bytes after that
+    /// The stack has no guard page: a context overflowing it overwrites whatever memory lies below the buffer
+    pub fn from_buffer(buffer: &'static mut [u8]) -> Result<Self, StackError> {
+        let stack = buffer_stack(buffer)?;
+        // the buffer is borrowed exclusively for the rest of the program
+        Ok(Self::of_stack(unsafe { OwnedStack::from_raw(stack) }))
+    }
+
+    /// Like [from_buffer] but taking ownership of [buffer], which is released along with the stack
+    pub fn from_boxed_buffer(buffer: Box<[u8]>) -> Result<Self, StackError> {
+        let buffer = Box::into_raw(buffer);
+        match buffer_stack(unsafe { &mut *buffer }) {
+            Ok(stack) => Ok(Self::of_stack(OwnedStack(Some(StackMemory::Buffer(BufferStack { stack, buffer })), None))),
+            Err(e) => {
+                drop(unsafe { Box::from_raw(buffer) });
+                Err(e)
+            }
+        }
+    }
+
+    /// Allocates a stack of [stack_size] bytes by [allocator], which gets it back once the coroutine completes or is dropped (see [Coroutine::keep_stack] for reusing it instead)
+    /// Panics on building the stack if the allocation fails
+    pub fn with_allocator(allocator: Arc<dyn StackAllocator>, stack_size:usize) -> Self {
+        Self::new(move || {
+            let mut stack = allocator.allocate(stack_size).unwrap();
+            stack.1 = Some(allocator);
+            stack
+        })
+    }
+
+    /// Like [of_size] but paints the whole stack with a known pattern before it is used, so the deepest byte the context has written can be found later on
+    /// Painting touches every page of the stack, therefore this is meant for tuning stack sizes rather than for production use
+    pub fn of_size_measured(stack_size:usize) -> Self {
+        Self(Self::of_size(stack_size).0, true, false)
+    }
+
+    /// Like [of_size] but faults in every page of the stack right after allocating it by writing a byte per page,
+    /// so the page faults are paid on construction instead of during the first deep excursion of the context
+    pub fn of_size_populated(stack_size:usize) -> Self {
+        Self::of_size(stack_size).pre_touch()
+    }
+
+    /// Makes stacks built by this factory fault in every page right after allocation, see [of_size_populated]
+    /// On linux, [with_mmap_flags] passing MAP_POPULATE has the same effect without touching the pages one by one
+    pub fn pre_touch(mut self) -> Self {
+        self.2 = true;
+        self
+    }
+
+    /// Queries whether stacks built by this factory are painted for high-water mark measurement
+    pub fn is_measured(&self) -> bool {
+        self.1
+    }
+
+    /// Hands out an already allocated stack, e.g. one which has been left by a completed coroutine context
+    pub fn of_stack(stack: impl Into<OwnedStack>) -> Self {
+        let stack = stack.into();
+        Self::new(move || stack)
+    }
+
+    pub fn build(self) -> OwnedStack {
+        let stack = (self.0)();
+        if self.1 {
+            unsafe { write_bytes(stack.bottom() as *mut u8, STACK_PAINT, stack.len()) };
+        } else if self.2 {
+            touch_pages(&stack);
+        }
+        stack
+    }
+}
+
+impl StackPool {
+    /// Creates an empty pool handing out stacks of [stack_size] bytes, which keeps up to 64 idle stacks
+    pub fn new(stack_size: usize) -> Self {
+        Self::with_max_idle(stack_size, 64)
+    }
+
+    /// Creates an empty pool handing out stacks of [stack_size] bytes, which keeps up to [max_idle] idle stacks
+    pub fn with_max_idle(stack_size: usize, max_idle: usize) -> Self {
+        Self { stack_size, max_idle, idle: Vec::new(), misses: 0 }
+    }
+
+    /// Returns a factory handing out an idle stack, or a fresh protected one of the pools stack size if there is none
+    pub fn factory(&mut self) -> StackFactory {
+        match self.idle.pop() {
+            Some(stack) => StackFactory::of_stack(stack),
+            None => {
+                self.misses += 1;
+                StackFactory::of_size(self.stack_size)
+            }
+        }
+    }
+
+    /// Takes back [stack] to hand it out again, unless it is too small or the pool is full
+    pub fn push(&mut self, stack: OwnedStack) {
+        if stack.len() >= self.stack_size && self.idle.len() < self.max_idle {
+            self.idle.push(stack);
+        }
+    }
+
+    /// Returns the number of idle stacks
+    pub fn len(&self) -> usize {
+        self.idle.len()
+    }
+
+    /// Queries whether there is no idle stack
+    pub fn is_empty(&self) -> bool {
+        self.idle.is_empty()
+    }
+
+    /// Returns how often [factory] had to fall back to a fresh stack
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+}
+
+impl StackAllocator for DefaultStackAllocator {
+    fn allocate(&self, size: usize) -> Result<OwnedStack, StackError> {
+        ProtectedFixedSizeStack::new(size).map(OwnedStack::from)
+    }
+
+    fn deallocate(&self, stack: OwnedStack) {
+        drop(stack)
+    }
+}
+
+impl OwnedStack {
+    /// Wraps memory managed by a [StackAllocator], which is not released when the returned stack is dropped
+    ///
+    /// # Safety
+    /// [stack] must describe memory which is valid to run a context on and is not used otherwise until the allocator gets it back
+    pub unsafe fn from_raw(stack: Stack) -> Self {
+        Self(Some(StackMemory::Raw(stack)), None)
+    }
+
+    /// Returns the size of the inaccessible region right below the bottom of the stack in bytes, which is 0 for unprotected and raw stacks
+    pub fn guard_size(&self) -> usize {
+        match self.memory() {
+            StackMemory::Protected(_) => page_size(),
+            StackMemory::Unprotected(_) | StackMemory::Raw(_) | StackMemory::Buffer(_) => 0,
+            #[cfg(unix)]
+            StackMemory::Mapped(stack) => stack.guard_size(),
+        }
+    }
+
+    fn memory(&self) -> &StackMemory {
+        self.0.as_ref().expect("stack has been handed back to its allocator")
+    }
+}
+
+impl Deref for OwnedStack {
+    type Target = Stack;
+
+    fn deref(&self) -> &Stack {
+        match self.memory() {
+            StackMemory::Protected(stack) => stack,
+            StackMemory::Unprotected(stack) => stack,
+            #[cfg(unix)]
+            StackMemory::Mapped(stack) => stack,
+            StackMemory::Raw(stack) => stack,
+            StackMemory::Buffer(buffer) => &buffer.stack,
+        }
+    }
+}
+
+impl Drop for OwnedStack {
+    /// Hands the stack back to the allocator it has been allocated by, if any
+    fn drop(&mut self) {
+        if let Some(allocator) = self.1.take() {
+            allocator.deallocate(Self(self.0.take(), None))
+        }
+    }
+}
+
+impl Debug for OwnedStack {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let kind = match self.memory() {
+            StackMemory::Protected(_) => "Protected",
+            StackMemory::Unprotected(_) => "Unprotected",
+            #[cfg(unix)]
+            StackMemory::Mapped(_) => "Mapped",
+            StackMemory::Raw(_) => "Raw",
+            StackMemory::Buffer(_) => "Buffer",
+        };
+        f.debug_struct("OwnedStack").field("kind", &kind).field("stack", self.deref()).finish()
+    }
+}
+
+impl Drop for BufferStack {
+    fn drop(&mut self) {
+        // the context running on the stack is gone along with the OwnedStack owning it
+        drop(unsafe { Box::from_raw(self.buffer) })
+    }
+}
+
+impl From<ProtectedFixedSizeStack> for OwnedStack {
+    fn from(stack: ProtectedFixedSizeStack) -> Self {
+        Self(Some(StackMemory::Protected(stack)), None)
+    }
+}
+
+impl From<FixedSizeStack> for OwnedStack {
+    fn from(stack: FixedSizeStack) -> Self {
+        Self(Some(StackMemory::Unprotected(stack)), None)
+    }
+}
+
+/// Describes the largest region of [buffer] whose top and bottom are aligned to [BUFFER_ALIGNMENT] as stack
+/// Fails if that region is smaller than [Stack::min_size]
+fn buffer_stack(buffer: &mut [u8]) -> Result<Stack, StackError> {
+    let start = buffer.as_mut_ptr() as usize;
+    let bottom = (start + BUFFER_ALIGNMENT - 1) & !(BUFFER_ALIGNMENT - 1);
+    let top = (start + buffer.len()) & !(BUFFER_ALIGNMENT - 1);
+    let size = top.saturating_sub(bottom);
+    if size < Stack::min_size() {
+        let message = format!("stack buffer of {} bytes holds {} aligned bytes, less than the minimum stack size of {} bytes", buffer.len(), size, Stack::min_size());
+        return Err(StackError::IoError(io::Error::new(io::ErrorKind::InvalidInput, message)));
+    }
+    let base = buffer.as_mut_ptr();
+    Ok(unsafe { Stack::new(base.add(top - start) as *mut c_void, base.add(bottom - start) as *mut c_void) })
+}
+
+/// Returns the size of a memory page, context only exposes it as the minimum stack size it allows on every platform
+pub(crate) fn page_size() -> usize {
+    Stack::min_size()
+}
+
+/// Writes a byte to every page of [stack], which makes the kernel back all of them right away
+/// No context runs on the stack yet, so overwriting a byte per page does no harm
+fn touch_pages(stack: &Stack) {
+    let bottom = stack.bottom() as *mut u8;
+    for offset in (0..stack.len()).step_by(page_size()) {
+        unsafe { write_volatile(bottom.add(offset), 0) };
+    }
+}
+
+/// Overwrites the whole [stack] with [STACK_POISON], which must not be used by any context anymore
+#[cfg(feature = "debug-stack-poison")]
+pub(crate) fn poison_stack(stack: &Stack) {
+    unsafe { write_bytes(stack.bottom() as *mut u8, STACK_POISON, stack.len()) };
+}
+
+/// Determines how many bytes of a stack painted by a measuring [StackFactory] have been used at most by searching the lowest byte differing from the paint
+/// Since stacks grow downwards, everything above that byte counts as used
+pub(crate) fn stack_high_water_mark(stack: &Stack) -> usize {
+    let memory = unsafe { slice::from_raw_parts(stack.bottom() as *const u8, stack.len()) };
+    memory.iter()
+        .position(|b| *b != STACK_PAINT)
+        .map_or(0, |untouched| stack.len() - untouched)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use context::stack::StackError;
+
+    use super::{page_size, DefaultStackAllocator, OwnedStack, StackAllocator, StackFactory, StackPool};
+    use crate::coroutines::Coroutine;
+    #[cfg(target_os = "linux")]
+    use crate::mapped::huge_page_size;
+    use crate::generators::{BoostedGenerator, GeneratorChannel};
+
+    /// Allocates by the default allocator counting the stacks currently allocated
+    #[derive(Default)]
+    struct CountingAllocator(AtomicUsize);
+
+    impl StackAllocator for CountingAllocator {
+        fn allocate(&self, size: usize) -> Result<OwnedStack, StackError> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            DefaultStackAllocator.allocate(size)
+        }
+
+        fn deallocate(&self, stack: OwnedStack) {
+            self.0.fetch_sub(1, Ordering::SeqCst);
+            DefaultStackAllocator.deallocate(stack)
+        }
+    }
+
+    #[test]
+    fn allocator_gets_stack_back_on_completion() {
+        let allocator = Arc::new(CountingAllocator::default());
+        let mut co = Coroutine::<(), (), ()>::new_with_factory(StackFactory::with_allocator(allocator.clone(), 32 * 1024), |chan, _| chan.suspend(()));
+        assert_eq!(allocator.0.load(Ordering::SeqCst), 0);
+        co.resume(());
+        assert_eq!(allocator.0.load(Ordering::SeqCst), 1);
+        co.resume(());
+        assert!(co.is_completed());
+        assert_eq!(allocator.0.load(Ordering::SeqCst), 0);
+
+        let mut dropped = Coroutine::<(), (), ()>::new_with_factory(StackFactory::with_allocator(allocator.clone(), 32 * 1024), |chan, _| chan.suspend(()));
+        dropped.resume(());
+        drop(dropped);
+        assert_eq!(allocator.0.load(Ordering::SeqCst), 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn guarded_stack_reports_guard_region() {
+        let page = page_size();
+        let stack = StackFactory::of_size_with_guard(64 * 1024, 4).build();
+        assert_eq!(stack.guard_size(), 4 * page);
+        assert!(stack.len() >= 64 * 1024);
+        assert_eq!(StackFactory::default_stack().build().guard_size(), page);
+        assert_eq!(StackFactory::unprotected(64 * 1024).build().guard_size(), 0);
+
+        let mut co = Coroutine::<(), usize, ()>::new_with_factory(StackFactory::of_size_with_guard(64 * 1024, 4), |chan, _| {
+            chan.suspend(());
+            7
+        });
+        co.resume(());
+        let guard = co.guard_range().unwrap();
+        assert_eq!(guard.len(), 4 * page);
+        assert_eq!(guard.end, co.stack_range().unwrap().start);
+        assert_eq!(co.resume(()).unwrap_return(), 7);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn populated_stack_is_resident() {
+        let stack = StackFactory::with_mmap_flags(256 * 1024, libc::MAP_POPULATE).build();
+        assert!(stack.guard_size() > 0);
+        let page = page_size();
+        let mut resident = vec![0u8; stack.len() / page];
+        assert_eq!(unsafe { libc::mincore(stack.bottom(), stack.len(), resident.as_mut_ptr()) }, 0);
+        assert!(resident.iter().all(|page| page & 1 == 1));
+
+        let mut co = Coroutine::<(), usize, ()>::new_in(stack, |_, _| 3);
+        assert_eq!(co.resume(()).unwrap_return(), 3);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn pre_touched_stack_is_resident() {
+        let stack = StackFactory::of_size_populated(256 * 1024).build();
+        let page = page_size();
+        let mut resident = vec![0u8; stack.len() / page];
+        assert_eq!(unsafe { libc::mincore(stack.bottom(), stack.len(), resident.as_mut_ptr()) }, 0);
+        assert!(resident.iter().all(|page| page & 1 == 1));
+
+        let mut co = Coroutine::<(), usize, ()>::new_in(stack, |_, _| 4);
+        assert_eq!(co.resume(()).unwrap_return(), 4);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn huge_page_stack_has_no_guard_page_unless_refused() {
+        let stack = StackFactory::with_mmap_flags(64 * 1024, libc::MAP_HUGETLB).build();
+        if stack.guard_size() == 0 {
+            assert_eq!(stack.len() % huge_page_size(), 0);
+        } else {
+            // no huge pages are reserved, so the stack has been mapped with normal ones
+            assert_eq!(stack.guard_size(), page_size());
+        }
+        let mut co = Coroutine::<(), usize, ()>::new_in(stack, |_, _| 5);
+        assert_eq!(co.resume(()).unwrap_return(), 5);
+    }
+
+    #[test]
+    fn generator_runs_on_boxed_buffer() {
+        let factory = StackFactory::from_boxed_buffer(vec![0u8; 64 * 1024].into_boxed_slice()).unwrap();
+        let gen = BoostedGenerator::new_with_factory(factory, |chan| {
+            chan.yield_all(0..10);
+            "done"
+        });
+        assert_eq!(gen.sum::<i32>(), 45);
+    }
+
+    #[test]
+    fn stack_is_aligned_within_static_buffer() {
+        let buffer = Box::leak(vec![0u8; 64 * 1024 + 7].into_boxed_slice());
+        let range = buffer.as_ptr_range();
+        let (start, end) = (range.start as usize, range.end as usize);
+        let stack = StackFactory::from_buffer(&mut buffer[3..]).unwrap().build();
+        assert_eq!(stack.top() as usize % 16, 0);
+        assert_eq!(stack.bottom() as usize % 16, 0);
+        assert!(stack.bottom() as usize >= start + 3 && (stack.top() as usize) <= end);
+        let mut co = Coroutine::<(), usize, ()>::new_in(stack, |_, _| 6);
+        assert_eq!(co.resume(()).unwrap_return(), 6);
+    }
+
+    #[test]
+    fn small_buffer_is_refused() {
+        let error = StackFactory::from_boxed_buffer(vec![0u8; 64].into_boxed_slice()).err().unwrap();
+        assert!(error.to_string().contains("less than the minimum stack size"), "{}", error);
+    }
+
+    #[test]
+    fn pool_hands_out_recycled_stacks() {
+        let mut pool = StackPool::with_max_idle(32 * 1024, 1);
+        let mut range = None;
+        for _ in 0..3 {
+            let mut co = Coroutine::<(), (), ()>::new_with_factory(pool.factory(), |chan, _| chan.suspend(())).keep_stack(true);
+            co.resume(());
+            let current = co.stack_range();
+            assert_eq!(*range.get_or_insert(current.clone()), current);
+            co.resume(());
+            co.recycle_into(&mut pool);
+            assert_eq!(pool.len(), 1);
+        }
+        assert_eq!(pool.misses(), 1);
+
+        pool.push(StackFactory::of_size(32 * 1024).build());
+        assert_eq!(pool.len(), 1, "a full pool drops further stacks");
+        let mut small = StackPool::new(64 * 1024);
+        small.push(StackFactory::of_size(32 * 1024).build());
+        assert!(small.is_empty());
+    }
+}
@@ -0,0 +1,88 @@
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::{FusedStream, Stream};
+
+use crate::generators::{GeneratorError, ResultingGenerator};
+
+/// Stream handing out the values of a generator, see [crate::generators::GeneratorExt::into_stream]
+/// The second field tells whether the generator completed or panicked, which ends the stream
+pub struct StreamAdapter<G>(G, bool);
+
+impl<G> StreamAdapter<G> {
+    pub(crate) fn new(generator: G) -> Self {
+        Self(generator, false)
+    }
+    /// Hands back the wrapped generator
+    pub fn into_inner(self) -> G {
+        self.0
+    }
+}
+
+impl<'a, G: ResultingGenerator<'a, Receive=()>> StreamAdapter<G> {
+    /// Returns the result of the generator, a panic which ended the stream is reported like [ResultingGenerator::result] does
+    pub fn into_result(self) -> Result<G::Return, GeneratorError> {
+        self.0.result()
+    }
+}
+
+/// The wrapped generator is never pinned structurally, its coroutine context lives on a callstack of its own anyway
+impl<G> Unpin for StreamAdapter<G> {}
+
+impl<G: Iterator> Stream for StreamAdapter<G> {
+    type Item = G::Item;
+
+    /// Resumes the generator right away, since the generator never waits for anything but being resumed
+    fn poll_next(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<G::Item>> {
+        let this = self.get_mut();
+        if this.1 {
+            return Poll::Ready(None);
+        }
+        let generator = &mut this.0;
+        let next = catch_unwind(AssertUnwindSafe(|| generator.next())).unwrap_or(None);
+        this.1 = next.is_none();
+        Poll::Ready(next)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.1 { (0, Some(0)) } else { self.0.size_hint() }
+    }
+}
+
+impl<G: Iterator> FusedStream for StreamAdapter<G> {
+    fn is_terminated(&self) -> bool {
+        self.1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+    use futures::StreamExt;
+
+    use crate::generators::{BoostedGenerator, GeneratorChannel, GeneratorError, GeneratorExt};
+
+    #[test]
+    fn generator_values_are_streamed() {
+        let mut stream = BoostedGenerator::new(|chan| {
+            chan.yield_all(0..5);
+            Ok::<_, String>(())
+        }).into_stream();
+        let values = block_on((&mut stream).map(|v| v * 2).collect::<Vec<_>>());
+        assert_eq!(values, vec![0, 2, 4, 6, 8]);
+        assert_eq!(block_on(stream.next()), None);
+        assert_eq!(stream.into_result(), Ok(Ok(())));
+    }
+
+    #[test]
+    fn panic_ends_stream() {
+        let mut stream = BoostedGenerator::<i32, (), ()>::new(|chan| {
+            chan.yield_val(1);
+            panic!("stream failed")
+        }).into_stream();
+        assert_eq!(block_on((&mut stream).collect::<Vec<_>>()), vec![1]);
+        assert_eq!(block_on(stream.next()), None);
+        assert_eq!(stream.into_result(), Err(GeneratorError::Panicked { message: Some("stream failed".to_string()) }));
+    }
+}
@@ -0,0 +1,79 @@
+#[cfg(feature = "tracing")]
+use std::time::Instant;
+
+/// Number of context switches into a single coroutine, numbering the switches reported by [SwitchScope]
+/// Zero sized if the tracing feature is disabled
+#[cfg(feature = "tracing")]
+pub(crate) struct SwitchCounter(u64);
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct SwitchCounter;
+
+impl SwitchCounter {
+    #[inline]
+    pub(crate) fn new() -> Self {
+        #[cfg(feature = "tracing")]
+        return SwitchCounter(0);
+        #[cfg(not(feature = "tracing"))]
+        return SwitchCounter;
+    }
+}
+
+/// Covers a switch from the invocation context into a coroutine context until the coroutine suspends again
+/// Enters a span carrying the coroutines name and the switch number, so events reported by the coroutine context via [coroutine_event] are attributed to it,
+/// and reports how long the coroutine has been running on drop
+#[cfg(feature = "tracing")]
+pub(crate) struct SwitchScope {
+    _span: tracing::span::EnteredSpan,
+    started: Instant,
+}
+
+#[cfg(feature = "tracing")]
+impl SwitchScope {
+    /// Counts the switch and enters its span, [kind] tells whether the coroutine gets resumed or is about to be dropped
+    pub(crate) fn enter(counter: &mut SwitchCounter, name: Option<&str>, kind: &'static str) -> Self {
+        counter.0 += 1;
+        let span = tracing::trace_span!("coroutine", name = name.unwrap_or("<unnamed>"), switch = counter.0, kind);
+        Self { _span: span.entered(), started: Instant::now() }
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl Drop for SwitchScope {
+    fn drop(&mut self) {
+        tracing::trace!(elapsed_ns = self.started.elapsed().as_nanos() as u64, "coroutine suspended");
+    }
+}
+
+/// Reports a switch initiated by the coroutine context, [kind] being one of yield, return, panic or drop
+#[cfg(feature = "tracing")]
+pub(crate) fn coroutine_event(kind: &'static str) {
+    tracing::trace!(kind, "coroutine switch");
+}
+
+/// Stands in for the tracing [SwitchScope] if the tracing feature is disabled
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct SwitchScope;
+
+#[cfg(not(feature = "tracing"))]
+impl SwitchScope {
+    #[inline]
+    pub(crate) fn enter(_counter: &mut SwitchCounter, _name: Option<&str>, _kind: &'static str) -> Self {
+        SwitchScope
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+#[inline]
+pub(crate) fn coroutine_event(_kind: &'static str) {}
+
+/// Reports a condition the crate recovered from but the user should know about, e.g. a stack mapped without the requested flags
+/// Nothing is reported if the tracing feature is disabled, the outcome has to be observable by the API anyway
+#[cfg(feature = "tracing")]
+pub(crate) fn warn(message: &str) {
+    tracing::warn!("{}", message);
+}
+
+#[cfg(not(feature = "tracing"))]
+#[inline]
+pub(crate) fn warn(_message: &str) {}
@@ -1,114 +1,164 @@
-use std::mem::{transmute, take};
+use std::fmt::{self, Display, Formatter};
+use std::marker::PhantomData;
+use std::mem::{self, ManuallyDrop, MaybeUninit};
+use std::ptr::{self, NonNull};
 
 use context::{Transfer, Context, ContextFn};
 
 use crate::utils::SelfUpdating;
-use context::stack::{ProtectedFixedSizeStack};
+use crate::stack::OwnedStack;
 
-pub struct StackFactory(Box<dyn FnOnce()->ProtectedFixedSizeStack>);
+/// Encodes a reference to a slot as usize for transfer
+/// The provenance of the pointer is exposed, so it can be picked up again by [with_exposed] on the other side of the Transfer.data boundary
+fn expose<T>(slot: &mut T) -> usize {
+    NonNull::from(slot).as_ptr().expose_provenance()
+}
 
-impl StackFactory {
-    fn new<F:FnOnce()->ProtectedFixedSizeStack+'static>(builder:F) -> Self {
-        Self(Box::new(builder))
-    }
+/// Reconstructs a pointer to a slot from a usize made by [expose]
+/// Panics on a null pointer, which is never made by [expose]
+fn with_exposed<T>(p: usize) -> NonNull<T> {
+    NonNull::new(ptr::with_exposed_provenance_mut(p)).expect("null exchange slot pointer")
+}
 
-    pub fn default_stack() -> Self {
-        Self::new(|| ProtectedFixedSizeStack::default())
-    }
+/// Slot holding a value which is moved to another context exactly once, by the other context taking it through the published pointer
+/// As the owner can't empty the slot, it is always full when published. The owner never drops the value, which is taken over by [take_published] instead
+struct SendSlot<V>(ManuallyDrop<V>);
 
-    #[allow(dead_code)]
-    pub fn of_size(stack_size:usize) -> Self {
-        Self::new(move || ProtectedFixedSizeStack::new(stack_size).unwrap())
+impl<V> SendSlot<V> {
+    fn new(val: V) -> Self {
+        Self(ManuallyDrop::new(val))
     }
-
-    pub fn build(self) -> ProtectedFixedSizeStack {
-        (self.0)()
+    /// Encodes a pointer to this slot for transfer
+    fn publish(&mut self) -> usize {
+        expose(self)
+    }
+    /// Moves the value out of the slot published as [p]
+    ///
+    /// # Safety
+    /// [p] must have been made by [publish] of a slot which has not been moved since and whose value has not been taken yet
+    unsafe fn take_published(p: usize) -> V {
+        ManuallyDrop::take(&mut with_exposed::<Self>(p).as_mut().0)
     }
 }
 
-/// Container technically quite simular to Option but with special purpose to hold a value that can be moved out exactly once (also semanticly)
-/// It is thought to move data between two callstacks by having a known mutable reference for this container where the value is passed to before execution control is switched
-/// Resuming execution can than move the value by returning it from yield/suspense call leaving the container at the "swap place" being emtpy variant
-#[derive(Debug)]
-enum ValueExchangeContainer<V> {
-    Value(V),
-    Empty,
-}
-
-impl<V> From<V> for ValueExchangeContainer<V> {
-    fn from(v: V) -> Self {
-        ValueExchangeContainer::prepare_exchange(v)
-    }
+/// Slot published empty to another context, which moves a value into it (see [ExchangeContainerRef]) before switching back
+/// It is the only slot a pointer may be published to while empty
+/// The protocol fills and drains it exactly once per switch, so it does not track whether it holds a value,
+/// except for debug builds, which check every fill and drain and offer the checked [try_receive] and [peek] next to the unchecked [take]
+/// A value left in the slot when it is dropped is leaked
+struct ReceiveSlot<V> {
+    value: MaybeUninit<V>,
+    #[cfg(debug_assertions)]
+    filled: bool,
 }
 
-impl<V> Default for ValueExchangeContainer<V> {
-    /// Defining Empty variant as default value
-    /// Needed to use std::mem::take
+impl<V> Default for ReceiveSlot<V> {
     fn default() -> Self {
-        Self::Empty
+        Self {
+            value: MaybeUninit::uninit(),
+            #[cfg(debug_assertions)]
+            filled: false,
+        }
     }
 }
 
-impl<V> ValueExchangeContainer<V> {
-    /// Wrap a value V in a ValueExchangeContainer
-    fn prepare_exchange(val: V) -> Self {
-        Self::Value(val)
+impl<V> ReceiveSlot<V> {
+    /// Queries whether a value has been written and not been taken yet, which is only tracked by debug builds
+    #[cfg(debug_assertions)]
+    fn is_filled(&self) -> bool {
+        self.peek().is_some()
+    }
+    /// Moves [val] into the slot
+    ///
+    /// # Safety
+    /// The slot must be empty, otherwise its value is leaked
+    unsafe fn write(&mut self, val: V) {
+        #[cfg(debug_assertions)]
+        {
+            assert!(!self.filled, "exchange slot filled twice");
+            self.filled = true;
+        }
+        self.value.write(val);
+    }
+    /// Moves the value out of the slot
+    ///
+    /// # Safety
+    /// The slot must have been filled by [write] since the last take
+    unsafe fn take(&mut self) -> V {
+        #[cfg(debug_assertions)]
+        {
+            assert!(self.filled, "exchange slot drained while empty");
+            self.filled = false;
+        }
+        self.value.assume_init_read()
     }
-
-    /// Queries whether containers value is still available or has already been moved
-    #[allow(dead_code)]
-    fn has_content(&self) -> bool {
-        match self {
-            Self::Value(_) => true,
-            Self::Empty => false
+    /// Moves the value out of the slot if it has been filled, which is only tracked by debug builds
+    #[cfg(debug_assertions)]
+    fn try_receive(&mut self) -> Option<V> {
+        if self.filled {
+            Some(unsafe { self.take() })
+        } else {
+            None
         }
     }
-    /// Move value out of container by returning the value and changing containers value to variant empty
-    /// Panics if container is already empty
-    fn receive_content(&mut self) -> V {
-        match take(self) {
-            Self::Value(v) => v,
-            Self::Empty => panic!("No content to receive")
+    /// Borrows the value of the slot if it has been filled, which is only tracked by debug builds
+    #[cfg(debug_assertions)]
+    fn peek(&self) -> Option<&V> {
+        if self.filled {
+            Some(unsafe { self.value.assume_init_ref() })
+        } else {
+            None
         }
     }
-    /// Encodes a reference to this container as usize for transfer
-    fn make_pointer(&self) -> usize {
-        unsafe { transmute::<*const Self, usize>(self as *const Self) }
+    /// Encodes a pointer to this slot for transfer
+    fn publish(&mut self) -> usize {
+        expose(self)
     }
-    /// Reconstructs a mutable reference to a Container from a usize pointer
-    fn of_pointer<'a>(p: usize) -> &'a mut Self {
-        unsafe {
-            &mut *transmute::<usize, *mut Self>(p)
-        }
+    /// Creates a slot holding [val]
+    #[cfg(test)]
+    fn filled_with(val: V) -> Self {
+        let mut slot = Self::default();
+        unsafe { slot.write(val) };
+        slot
     }
 }
 
-/// Decorator around a mutable Container ref providing trans-callcontext access
-/// While ValueExchangeContainer itself is kind of "immutable", i.e. should only used for one move and each value should be packed in a fresh instance
-/// the ref is thought to be mutable and reference may be updated
-struct ExchangeContainerRef<'a, V>(&'a mut ValueExchangeContainer<V>);
+/// Decorator around a pointer to the [ReceiveSlot] of another context providing trans-callcontext access
+/// The reference is thought to be mutable and may be updated to the slot published by the next switch
+/// It holds a pointer rather than a mutable reference, since the other context accesses the slot as well in between the switches,
+/// and only borrows the slot while accessing it
+struct ExchangeContainerRef<'a, V>(NonNull<ReceiveSlot<V>>, PhantomData<&'a mut ReceiveSlot<V>>);
 
 impl<'a, V> ExchangeContainerRef<'a, V> {
-    /// create from mutable reference (e.g. by ValueExchangeContainer::of_pointer)
-    fn new(container: &'a mut ValueExchangeContainer<V>) -> Self {
-        Self(container)
+    /// create from mutable reference
+    #[cfg(test)]
+    fn new(slot: &'a mut ReceiveSlot<V>) -> Self {
+        Self(NonNull::from(slot), PhantomData)
     }
     /// create from usize pointer
     fn of_pointer(p: usize) -> Self {
-        Self::new(ValueExchangeContainer::of_pointer(p))
-    }
-
-    /// Sends a value into the referenced Container
-    /// Either writes a new created container to the reference or panics if given container is not empty
-    fn send_value(&mut self, val: V) {
-        match self.0 {
-            ValueExchangeContainer::Value(_) => panic!("tried to write to non-empty container"),
-            ValueExchangeContainer::Empty => { *self.0 = ValueExchangeContainer::prepare_exchange(val); }
+        Self(with_exposed(p), PhantomData)
+    }
+    /// Borrows the referenced slot
+    fn slot(&mut self) -> &mut ReceiveSlot<V> {
+        // the other context does not access its slot until it is resumed, which requires this borrow to end
+        unsafe { self.0.as_mut() }
+    }
+    /// Moves [val] into the referenced slot
+    /// Debug builds hand [val] back if the slot has not been emptied yet, release builds rely on the protocol draining the slot on every switch
+    fn try_send_value(&mut self, val: V) -> Result<(), V> {
+        let slot = self.slot();
+        #[cfg(debug_assertions)]
+        if slot.is_filled() {
+            return Err(val);
         }
+        // the other context drains its slot right after being resumed, so it is empty whenever this context runs
+        unsafe { slot.write(val) };
+        Ok(())
     }
     /// Updates the holded reference to new pointer
     fn receive_ref(&mut self, p: usize) {
-        self.0 =  ValueExchangeContainer::of_pointer(p);
+        self.0 = with_exposed(p);
     }
 }
 
@@ -118,176 +168,461 @@ impl<'a, V> From<usize> for ExchangeContainerRef<'a, V> {
     }
 }
 
+/// Value an [ExchangingTransfer] failed to send, handed back together with the reason
+#[derive(Debug)]
+pub struct SendError<V> {
+    pub value: V,
+    pub reason: &'static str,
+}
+
+impl<V> Display for SendError<V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to send: {}", self.reason)
+    }
+}
+
+/// Container moving up to [N] values of type [V] with a single switch, e.g. as message type of an [ExchangingTransfer]
+/// The sending side fills it by [ExchangingTransfer::yield_buffered] and only switches once it is full,
+/// the receiving side drains it value by value by [ExchangingTransfer::drain] and only switches once it is empty
+/// Values left in the container when it is dropped are dropped along with it
+pub struct RingExchange<V, const N: usize> {
+    values: [MaybeUninit<V>; N],
+    head: usize,
+    len: usize,
+}
+
+impl<V, const N: usize> RingExchange<V, N> {
+    /// Creates an empty container
+    /// Panics if [N] is 0, as such a container could never hand over a value
+    pub fn new() -> Self {
+        assert!(N > 0, "ring exchange must hold at least one value");
+        Self { values: std::array::from_fn(|_| MaybeUninit::uninit()), head: 0, len: 0 }
+    }
+    /// Appends [val], or hands it back if the container is full
+    pub fn push(&mut self, val: V) -> Result<(), V> {
+        if self.is_full() {
+            return Err(val);
+        }
+        self.values[(self.head + self.len) % N].write(val);
+        self.len += 1;
+        Ok(())
+    }
+    /// Takes the oldest value, or returns None if the container is empty
+    pub fn pop(&mut self) -> Option<V> {
+        if self.is_empty() {
+            return None;
+        }
+        // the slot at head is initialized as the container is not empty, head is moved on so it is not read again
+        let val = unsafe { self.values[self.head].assume_init_read() };
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+        Some(val)
+    }
+    /// Returns the number of values buffered
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Queries whether no value is buffered, so [pop] returns None
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Queries whether [N] values are buffered, so [push] hands back its value
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+}
+
+impl<V, const N: usize> Default for RingExchange<V, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V, const N: usize> Drop for RingExchange<V, N> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+    }
+}
+
+/// State of the exchange protocol, tracked in debug builds only to report violations naming the broken invariant
+#[cfg(debug_assertions)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProtocolState {
+    /// The receiving slot of the other context is not known yet, which only allows to suspend
+    Unconnected,
+    /// The receiving slot of the other context is known, so values may be sent
+    Connected,
+    /// The other context has switched back with a null pointer, which only happens on dispose, so it must not be resumed anymore
+    PeerDisposed,
+}
+
+/// State of an [ExchangingTransfer] besides its raw transfer, handed out by [ExchangingTransfer::into_raw] to rebuild it by [ExchangingTransfer::from_raw]
+/// Holds the address of the receive slot of the other context, which stays valid as long as that context is suspended
+#[derive(Debug)]
+pub struct RawExchangeState {
+    send_target: usize,
+    #[cfg(debug_assertions)]
+    protocol: ProtocolState,
+}
+
 /// Wraps the context libs raw transfer type which allows to exchange pointer adding the possibility to move input and output values between callstacks
 /// Therefore it has two additional attributes:
-/// - one field allocating a ValueExchangeContainer in which another context may transfer input values of type ReceiveMessage
-/// - one containing an optional ContainerRef to the receiving field of an ExchangingTransfer in the opposite context in which output values can be moved
+/// - one field allocating a [ReceiveSlot] in which another context may transfer input values of type ReceiveMessage
+/// - one containing an optional ContainerRef to the receiving slot of an ExchangingTransfer in the opposite context in which output values can be moved
 ///
 /// The interface only offers complete control cycle methods (maybe send data -> switch context and wait for resume -> read received data) and encapsulates this behaviour on the lowest possible level
+///
+/// Values are moved through the slots even if they would fit into the data word of the raw transfer (e.g. unit resume values),
+/// since that word is taken by the address of the receive slot which is re-sent on every switch and tells a disposed context by 0.
+/// There is no fast path inlining small values either: the messages of [Coroutine](crate::coroutines::Coroutine) carry batches and panic payloads,
+/// so they never fit a word, and a word sized value would leave no room to tell a disposed context
+///
+/// Debug builds validate the protocol on every switch and panic naming the violated invariant and the current [ProtocolState]
 pub struct ExchangingTransfer<'a, SendMessage, ReceiveMessage> {
     pointer_transfer: SelfUpdating<Transfer>,
-    receive_container: ValueExchangeContainer<ReceiveMessage>,
+    receive_slot: ReceiveSlot<ReceiveMessage>,
     send_ref: Option<ExchangeContainerRef<'a, SendMessage>>,
+    #[cfg(debug_assertions)]
+    protocol: ProtocolState,
 }
 
 impl<'a, Send, Receive> ExchangingTransfer<'a, Send, Receive> {
-    /// Creates an ExchangingTransfer out of a raw transfer which pointer does not belong to another ExchangeContainer reference
+    /// Creates an ExchangingTransfer out of a raw transfer which pointer does not belong to a receive slot of another context
     /// In this case no output can be send on first suspense, since the destination is unknown and therefore only suspense call(which does not send) is valid
     pub(super) fn create_without_send(pointer_transfer: Transfer) -> Self {
         Self {
             pointer_transfer: pointer_transfer.into(),
-            receive_container: ValueExchangeContainer::default(),
+            receive_slot: ReceiveSlot::default(),
             send_ref: None,
+            #[cfg(debug_assertions)]
+            protocol: ProtocolState::Unconnected,
         }
     }
-    /// Creates an ExchangingTransfer by a raw transfer already containing a valid ref to another ExchangeContainer
+    /// Creates an ExchangingTransfer by a raw transfer already containing a valid ref to a receive slot of another context
     /// This instance will be able to send output on first suspense (and might have to, depending on higher level semantics)
     pub(super) fn create_with_send(pointer_transfer: Transfer) -> Self {
         let current_data = pointer_transfer.data;
         Self {
             pointer_transfer: pointer_transfer.into(),
-            receive_container: ValueExchangeContainer::default(),
+            receive_slot: ReceiveSlot::default(),
             send_ref: Some(ExchangeContainerRef::of_pointer(current_data)),
+            #[cfg(debug_assertions)]
+            protocol: ProtocolState::Connected,
         }
     }
 
     /// Creates an ExchangingTransfer out of a raw transfer using the initial transfer pointer to resolve a different value and there creates an ExTansfer without sending capability on first suspense (see create_without_send)
-    pub(super) fn create_receiving<V>(pointer_transfer: Transfer) -> (Self, V) {
-        let receive = ValueExchangeContainer::of_pointer(pointer_transfer.data).receive_content();
+    ///
+    /// # Safety
+    /// The data of [pointer_transfer] must be the pointer to a [SendSlot] holding a [V], like the one published by [init_context_sending] to the started context
+    pub(super) unsafe fn create_receiving<V>(pointer_transfer: Transfer) -> (Self, V) {
+        let receive = SendSlot::take_published(pointer_transfer.data);
         (Self::create_without_send(pointer_transfer), receive)
     }
 
-    /// Creates an ExchangingTransfer by creating a raw transfer first on top of a stack builded by given [stack_factory] pointing to  [context_fn]
-    /// Transfers [initial] using pointer to ValueExchangeContainer and suspends execution control to created context
-    /// Returns tupel of created ExchangingTransfer and builded stack after resume
-    pub(super) fn init_context_sending<V>(stack_factory:StackFactory,context_fn:ContextFn,initial:V) -> (Self, ProtectedFixedSizeStack) {
-        let stack=stack_factory.build();
+    /// Creates an ExchangingTransfer by creating a raw transfer first on top of given [stack] pointing to  [context_fn]
+    /// Transfers [initial] using pointer to a [SendSlot] and suspends execution control to created context, which has to take it by [create_receiving]
+    /// Returns tupel of created ExchangingTransfer and the stack after resume
+    pub(super) fn init_context_sending<V>(stack:OwnedStack,context_fn:ContextFn,initial:V) -> (Self, OwnedStack) {
+        let mut initial = SendSlot::new(initial);
+        count_switch();
         let transfer=unsafe {
             Transfer::new(Context::new(&stack, context_fn), 0)
-                .context.resume(ValueExchangeContainer::prepare_exchange(initial).make_pointer())
+                .context.resume(initial.publish())
         };
         (Self::create_with_send(transfer), stack)
     }
 
     /// Sends given value [val] to connected callcontext and resumes it's execution expecting to never come back
-    /// Therefore a nullpointer is transferred for current Input ExchangeContainer reference (as no input should occur ever again)
+    /// Therefore a nullpointer is transferred for the current receive slot (as no input should occur ever again)
     /// Panics if this context is resumed ever again
     pub(super) fn dispose_with(&mut self, val: Send) -> ! {
-        self.send(val);
-        self.pointer_transfer.update(|t| unsafe { t.context.resume(0) });
-        panic!("Resumed co-context after dispose")
+        #[cfg(debug_assertions)]
+        self.assert_protocol(self.protocol == ProtocolState::Connected, "dispose", "the receiving slot of the other context must be known");
+        self.send(val, "dispose");
+        count_switch();
+        self.pointer_transfer.consume(|t| {
+            unsafe { t.context.resume(0) };
+            panic!("Resumed co-context after dispose")
+        })
     }
 
     /// Sends given value [val] to connected callcontext and resumes it's execution expecting that current callcontext is resumed later
-    /// Therefore a reference to the current input container field is send as pointer and - after resuming - expects this container to be filled and therefore returns it's content
-    /// Panics if no ref for output is known or input container is empty after resume
+    /// Therefore a reference to the current receive slot is send as pointer and - after resuming - expects this slot to be filled and therefore returns it's content
+    /// Panics if no ref for output is known or receive slot is empty after resume
     pub(super) fn yield_with(&mut self, val: Send) -> Receive {
-        self.send(val);
+        #[cfg(debug_assertions)]
+        self.assert_protocol(self.protocol == ProtocolState::Connected, "yield", "the receiving slot of the other context must be known");
+        self.send(val, "yield");
         self.suspend()
     }
 
-    /// Writes [val] to current ExchangeContainerRef or panics in case the ref is unknown
-    fn send(&mut self, val: Send) {
+    /// Writes [val] to current ExchangeContainerRef or panics naming the protocol [step] in case it can't be sent
+    fn send(&mut self, val: Send, step: &'static str) {
+        if let Err(SendError { value, reason }) = self.try_send(val) {
+            // dropped before panicking, so a destructor panicking won't abort the process by panicking while unwinding
+            drop(value);
+            panic!("exchange protocol violated on {}: failed to send: {}", step, reason)
+        }
+    }
+    /// Writes [val] to current ExchangeContainerRef, hands it back in case the ref is unknown or (checked by debug builds only) the slot has not been emptied yet
+    pub(super) fn try_send(&mut self, val: Send) -> Result<(), SendError<Send>> {
         match &mut self.send_ref {
-            Some(send_ref) => send_ref.send_value(val),
-            None => panic!("invalid exchange state for sending")
-        };
+            Some(send_ref) => send_ref.try_send_value(val)
+                .map_err(|value| SendError { value, reason: "receiving slot is not empty" }),
+            None => Err(SendError { value: val, reason: "no receiving slot known" })
+        }
+    }
+    /// Queries whether a receiving slot of the other context is known, which is not the case after it has been disposed
+    pub(super) fn can_send(&self) -> bool {
+        self.send_ref.is_some()
     }
     /// like [yield_with] but without sending a value
+    /// The address of the receive slot is sent on every switch, so the owner of this transfer may be moved (e.g. to another thread) in between switches
+    /// as long as it stays in place while the other context runs
+    /// The other context is expected to send a value on every switch back, which is only checked by debug builds
     pub(super) fn suspend(&mut self) -> Receive {
-        let receive_container_pointer = self.receive_container.make_pointer();
-        self.pointer_transfer.update(|t| unsafe { t.context.resume(receive_container_pointer) });
-        if self.pointer_transfer.data != 0 {
-            self.send_ref = Some(self.send_ref.take().map(|mut s| {
-                s.receive_ref(self.pointer_transfer.data);
-                s
-            }).unwrap_or_else(|| ExchangeContainerRef::of_pointer(self.pointer_transfer.data)));
+        #[cfg(debug_assertions)]
+        {
+            self.assert_protocol(self.protocol != ProtocolState::PeerDisposed, "suspend", "the other context has been disposed and must not be resumed");
+            self.assert_protocol(!self.receive_slot.is_filled(), "suspend", "the receive slot must be empty before suspending");
+        }
+        let receive_slot_pointer = self.receive_slot.publish();
+        count_switch();
+        let data = self.pointer_transfer.returning_update(|t| {
+            let resumed = unsafe { t.context.resume(receive_slot_pointer) };
+            let data = resumed.data;
+            (resumed, data)
+        });
+        debug_assert_eq!(self.receive_slot.publish(), receive_slot_pointer, "exchanging transfer moved while the other context was running");
+        if data != 0 {
+            match &mut self.send_ref {
+                Some(send_ref) => send_ref.receive_ref(data),
+                None => self.send_ref = Some(ExchangeContainerRef::of_pointer(data)),
+            }
         } else {
             self.send_ref = None;
         }
-        self.receive_container.receive_content()
+        #[cfg(debug_assertions)]
+        {
+            self.protocol = if data != 0 { ProtocolState::Connected } else { ProtocolState::PeerDisposed };
+            match self.receive_slot.try_receive() {
+                Some(val) => return val,
+                None => self.assert_protocol(false, "resume", "resumed without a value; did the other side dispose?"),
+            }
+        }
+        // every switch back to this context hands over a value, including the one disposing the other context
+        unsafe { self.receive_slot.take() }
+    }
+    /// Takes the transfer apart in between switches, e.g. to park the suspended other context in the run queue of another runtime
+    /// The receive slot is not part of the result: it is empty in between switches and its address is sent anew by the next switch
+    pub fn into_raw(self) -> (Transfer, RawExchangeState) {
+        #[cfg(debug_assertions)]
+        self.assert_protocol(!self.receive_slot.is_filled(), "into_raw", "the receive slot must be empty in between switches");
+        let state = RawExchangeState {
+            send_target: self.send_ref.as_ref().map_or(0, |send_ref| send_ref.0.as_ptr().expose_provenance()),
+            #[cfg(debug_assertions)]
+            protocol: self.protocol,
+        };
+        (self.pointer_transfer.unwrap(), state)
+    }
+    /// Rebuilds a transfer taken apart by [into_raw]
+    ///
+    /// # Safety
+    /// [pointer_transfer] and [state] must have been handed out together by [into_raw] of a transfer with the same message types,
+    /// and the other context must not have been resumed since, as the receive slot referenced by [state] is only valid while it is suspended.
+    /// The other context may only be resumed by the rebuilt transfer afterwards
+    pub unsafe fn from_raw(pointer_transfer: Transfer, state: RawExchangeState) -> Self {
+        Self {
+            pointer_transfer: pointer_transfer.into(),
+            receive_slot: ReceiveSlot::default(),
+            send_ref: if state.send_target != 0 { Some(ExchangeContainerRef::of_pointer(state.send_target)) } else { None },
+            #[cfg(debug_assertions)]
+            protocol: state.protocol,
+        }
+    }
+    /// Panics naming the protocol [step] and the violated [invariant] together with the current state unless the invariant [holds]
+    #[cfg(debug_assertions)]
+    fn assert_protocol(&self, holds: bool, step: &str, invariant: &str) {
+        if !holds {
+            panic!("exchange protocol violated on {}: {} (state: {:?})", step, invariant, self.protocol)
+        }
     }
 }
 
+impl<'a, V, const N: usize, Receive> ExchangingTransfer<'a, RingExchange<V, N>, Receive> {
+    /// Buffers [val] in [ring] and switches only once the ring is full, handing over all buffered values at once
+    /// A ring which is full already (e.g. as it has been filled by [RingExchange::push]) is handed over before [val] is buffered in the emptied one
+    /// Returns the value received by the switch, or None if [val] has just been buffered
+    pub fn yield_buffered(&mut self, ring: &mut RingExchange<V, N>, val: V) -> Option<Receive> {
+        if let Err(val) = ring.push(val) {
+            let received = self.yield_with(mem::take(ring));
+            // the new ring is empty and holds at least one value
+            let _ = ring.push(val);
+            return Some(received);
+        }
+        if ring.is_full() {
+            Some(self.yield_with(mem::take(ring)))
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, Send, V, const N: usize> ExchangingTransfer<'a, Send, RingExchange<V, N>> {
+    /// Takes the next value out of [ring], which is replaced by the next ring received by switching with a value made by [request] once it is empty
+    /// Returns None once the ring is empty and the other context has been disposed, so no further values will follow
+    pub fn drain(&mut self, ring: &mut RingExchange<V, N>, mut request: impl FnMut() -> Send) -> Option<V> {
+        loop {
+            if let Some(val) = ring.pop() {
+                return Some(val);
+            }
+            if !self.can_send() {
+                return None;
+            }
+            *ring = self.yield_with(request());
+        }
+    }
+}
+
+/// Counts a context switch for [crate::metrics], which is a no-op unless the metrics feature is enabled
+#[inline]
+fn count_switch() {
+    #[cfg(feature = "metrics")]
+    crate::metrics::count_switch();
+}
+
 #[cfg(test)]
 mod tests {
+    use std::cell::Cell;
+
     use context::{Context, ContextFn, Transfer};
     use context::stack::ProtectedFixedSizeStack;
-    use super::ValueExchangeContainer;
+    use super::{ReceiveSlot, RingExchange, SendSlot};
     use crate::transfer::{ExchangeContainerRef, ExchangingTransfer};
 
     #[test]
-    fn exchange_container_prepare() {
-        let container = ValueExchangeContainer::prepare_exchange(1);
-        if let ValueExchangeContainer::Value(content) = container {
-            assert_eq!(content, 1)
-        } else {
-            panic!("value should exists")
-        }
+    fn send_slot_taken_by_pointer() {
+        let mut slot = SendSlot::new(1);
+        assert_eq!(unsafe { SendSlot::<i32>::take_published(slot.publish()) }, 1);
     }
 
     #[test]
-    fn exchange_container_has_content_correct_result() {
-        let container = ValueExchangeContainer::prepare_exchange(1);
-        assert_eq!(container.has_content(), true);
-        let container = ValueExchangeContainer::<usize>::Empty;
-        assert_eq!(container.has_content(), false);
+    fn send_slot_value_is_dropped_only_by_taker() {
+        use std::rc::Rc;
+        let value = Rc::new(());
+        let taken = {
+            let mut slot = SendSlot::new(value.clone());
+            unsafe { SendSlot::<Rc<()>>::take_published(slot.publish()) }
+        };
+        assert_eq!(Rc::strong_count(&value), 2);
+        drop(taken);
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+
+    /// Takes the value out of a slot which is known to be filled
+    fn take<V>(slot: &mut ReceiveSlot<V>) -> V {
+        unsafe { slot.take() }
+    }
+
+    /// Asserts that [slot] is empty, which is only tracked by debug builds
+    fn assert_empty<V>(_slot: &ReceiveSlot<V>) {
+        #[cfg(debug_assertions)]
+        assert!(!_slot.is_filled());
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn receive_slot_is_filled_correct_result() {
+        let slot = ReceiveSlot::filled_with(1);
+        assert_eq!(slot.is_filled(), true);
+        let slot = ReceiveSlot::<usize>::default();
+        assert_eq!(slot.is_filled(), false);
     }
 
     #[test]
-    fn exchange_container_receive_content() {
-        let mut container = ValueExchangeContainer::prepare_exchange(1);
-        assert_eq!(container.receive_content(), 1);
-        assert_eq!(container.has_content(), false);
+    fn receive_slot_take() {
+        let mut slot = ReceiveSlot::filled_with(1);
+        assert_eq!(take(&mut slot), 1);
+        assert_empty(&slot);
     }
 
+    #[cfg(debug_assertions)]
     #[test]
-    fn exchange_container_dup_by_pointer() {
-        let exchange_container = ValueExchangeContainer::prepare_exchange(1);
-        let dup_container = ValueExchangeContainer::<i32>::of_pointer(exchange_container.make_pointer());
-        assert_eq!(dup_container.receive_content(), 1)
+    fn receive_slot_try_receive_and_peek() {
+        let mut slot = ReceiveSlot::filled_with(1);
+        assert_eq!(slot.peek(), Some(&1));
+        assert_eq!(slot.try_receive(), Some(1));
+        assert_eq!(slot.peek(), None);
+        assert_eq!(slot.try_receive(), None);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "exchange slot drained while empty")]
+    fn receive_slot_take_empty_panics() {
+        take(&mut ReceiveSlot::<i32>::default());
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "exchange slot filled twice")]
+    fn receive_slot_write_filled_panics() {
+        let mut slot = ReceiveSlot::filled_with(1);
+        unsafe { slot.write(2) };
+    }
+
+    #[test]
+    fn receive_slot_moves_value_once() {
+        use std::rc::Rc;
+        let value = Rc::new(());
+        let mut slot = ReceiveSlot::filled_with(value.clone());
+        assert_eq!(Rc::strong_count(&value), 2);
+        drop(take(&mut slot));
+        assert_eq!(Rc::strong_count(&value), 1);
     }
 
     #[test]
     fn exchange_ref_new() {
-        let mut container = ValueExchangeContainer::prepare_exchange(1);
-        let container_ref = ExchangeContainerRef::new(&mut container);
-        assert_eq!(container_ref.0.receive_content(), 1);
-        let mut container = ValueExchangeContainer::<i32>::Empty;
-        let container_ref = ExchangeContainerRef::new(&mut container);
-        assert_eq!(container_ref.0.has_content(), false);
+        let mut slot = ReceiveSlot::filled_with(1);
+        let mut slot_ref = ExchangeContainerRef::new(&mut slot);
+        assert_eq!(take(slot_ref.slot()), 1);
+        let mut slot = ReceiveSlot::<i32>::default();
+        let mut slot_ref = ExchangeContainerRef::new(&mut slot);
+        assert_empty(slot_ref.slot());
     }
 
     #[test]
     fn exchange_ref_of_pointer() {
-        let container = ValueExchangeContainer::prepare_exchange(1);
-        let container_ref = ExchangeContainerRef::<i32>::of_pointer(container.make_pointer());
-        assert_eq!(container_ref.0.receive_content(), 1);
-        let container = ValueExchangeContainer::<i32>::Empty;
-        let container_ref = ExchangeContainerRef::<i32>::of_pointer(container.make_pointer());
-        assert_eq!(container_ref.0.has_content(), false)
+        let mut slot = ReceiveSlot::filled_with(1);
+        let mut slot_ref = ExchangeContainerRef::<i32>::of_pointer(slot.publish());
+        assert_eq!(take(slot_ref.slot()), 1);
+        let mut slot = ReceiveSlot::<i32>::default();
+        let mut slot_ref = ExchangeContainerRef::<i32>::of_pointer(slot.publish());
+        assert_empty(slot_ref.slot())
     }
 
     #[test]
     fn exchange_ref_send() {
-        let mut container = ValueExchangeContainer::<i32>::Empty;
-        let mut container_ref = ExchangeContainerRef::new(&mut container);
-        container_ref.send_value(2);
-        assert_eq!(container.receive_content(), 2)
+        let mut slot = ReceiveSlot::<i32>::default();
+        let mut slot_ref = ExchangeContainerRef::new(&mut slot);
+        assert_eq!(slot_ref.try_send_value(2), Ok(()));
+        assert_eq!(take(&mut slot), 2)
     }
 
     #[test]
     fn exchange_ref_receive() {
-        let mut container = ValueExchangeContainer::<i32>::Empty;
-        let mut container_ref = ExchangeContainerRef::new(&mut container);
-        let alt_container = ValueExchangeContainer::prepare_exchange(3);
-        container_ref.receive_ref(alt_container.make_pointer());
-        assert_eq!(container_ref.0.receive_content(), 3)
+        let mut slot = ReceiveSlot::<i32>::default();
+        let mut slot_ref = ExchangeContainerRef::new(&mut slot);
+        let mut alt_slot = ReceiveSlot::filled_with(3);
+        slot_ref.receive_ref(alt_slot.publish());
+        assert_eq!(take(slot_ref.slot()), 3)
     }
 
 
     static mut STATIC_TEST_STACK: Option<ProtectedFixedSizeStack> = None;
 
+    /// Tests running a context are ignored by Miri, which can't interpret the foreign context switch
     fn create_test_context(test_fn: ContextFn, start_data: usize) -> Transfer {
         unsafe {
             STATIC_TEST_STACK = Some(ProtectedFixedSizeStack::default())
@@ -299,47 +634,52 @@ mod tests {
         panic!("")
     }
 
+    #[cfg_attr(miri, ignore)]
     #[test]
     fn transfer_create_without_send() {
         let test_transfer = create_test_context(init_test, 0);
         let transfer = ExchangingTransfer::<i32, i32>::create_without_send(test_transfer);
         assert_eq!(transfer.pointer_transfer.data, 0);
-        assert!(!transfer.receive_container.has_content());
+        assert_empty(&transfer.receive_slot);
         assert!(transfer.send_ref.is_none())
     }
 
+    #[cfg_attr(miri, ignore)]
     #[test]
     fn transfer_create_with_send() {
-        let test_exchange = ValueExchangeContainer::prepare_exchange(5);
-        let test_transfer = create_test_context(init_test, test_exchange.make_pointer());
+        let mut test_slot = ReceiveSlot::filled_with(5);
+        let test_transfer = create_test_context(init_test, test_slot.publish());
         let transfer = ExchangingTransfer::<i32, i32>::create_with_send(test_transfer);
-        assert_eq!(transfer.pointer_transfer.data, test_exchange.make_pointer());
-        assert!(!transfer.receive_container.has_content());
-        assert_eq!(transfer.send_ref.unwrap().0.receive_content(), 5)
+        assert_eq!(transfer.pointer_transfer.data, test_slot.publish());
+        assert_empty(&transfer.receive_slot);
+        assert_eq!(take(transfer.send_ref.unwrap().slot()), 5)
     }
 
+    #[cfg_attr(miri, ignore)]
     #[test]
     fn transfer_create_receiving() {
-        let test_exchange = ValueExchangeContainer::prepare_exchange("test");
-        let test_transfer = create_test_context(init_test, test_exchange.make_pointer());
-        let (transfer, initial) = ExchangingTransfer::<i32, i32>::create_receiving::<&str>(test_transfer);
-        assert_eq!(transfer.pointer_transfer.data, test_exchange.make_pointer());
-        assert!(!transfer.receive_container.has_content());
+        let mut test_slot = SendSlot::new("test");
+        let test_transfer = create_test_context(init_test, test_slot.publish());
+        let (transfer, initial) = unsafe { ExchangingTransfer::<i32, i32>::create_receiving::<&str>(test_transfer) };
+        assert_eq!(transfer.pointer_transfer.data, test_slot.publish());
+        assert_empty(&transfer.receive_slot);
         assert_eq!(transfer.send_ref.is_none(), true);
         assert_eq!(initial, "test")
     }
 
+    #[cfg_attr(miri, ignore)]
     #[test]
     fn transfer_dispose_with() {
         extern "C" fn dispose_test(t: Transfer) -> ! {
             let mut trans = ExchangingTransfer::<i32, i32>::create_with_send(t);
             trans.dispose_with(3)
         }
-        let mut test_exchange = ValueExchangeContainer::<i32>::Empty;
-        unsafe { create_test_context(dispose_test, 0).context.resume(test_exchange.make_pointer()) };
-        assert_eq!(test_exchange.receive_content(), 3)
+        let mut test_slot = ReceiveSlot::<i32>::default();
+        unsafe { create_test_context(dispose_test, 0).context.resume(test_slot.publish()) };
+        assert_eq!(take(&mut test_slot), 3)
     }
 
+    #[cfg_attr(miri, ignore)]
     #[test]
     fn transfer_yield_with() {
         extern "C" fn dispose_test(t: Transfer) -> ! {
@@ -347,14 +687,15 @@ mod tests {
             trans.yield_with(2);
             trans.dispose_with(0)
         }
-        let mut test_exchange = ValueExchangeContainer::<i32>::Empty;
-        let t = unsafe { create_test_context(dispose_test, 0).context.resume(test_exchange.make_pointer()) };
-        assert_eq!(test_exchange.receive_content(), 2);
-        ExchangeContainerRef::of_pointer(t.data).send_value(1);
-        unsafe { t.context.resume(test_exchange.make_pointer()) };
-        assert_eq!(test_exchange.receive_content(), 0);
+        let mut test_slot = ReceiveSlot::<i32>::default();
+        let t = unsafe { create_test_context(dispose_test, 0).context.resume(test_slot.publish()) };
+        assert_eq!(take(&mut test_slot), 2);
+        assert_eq!(ExchangeContainerRef::of_pointer(t.data).try_send_value(1), Ok(()));
+        unsafe { t.context.resume(test_slot.publish()) };
+        assert_eq!(take(&mut test_slot), 0);
     }
 
+    #[cfg_attr(miri, ignore)]
     #[test]
     #[should_panic]
     fn transfer_dispose_with_does_not_allow_resume() {
@@ -362,8 +703,230 @@ mod tests {
             unsafe { t.context.resume(0) };
             panic!()
         }
-        let test_exchange = ValueExchangeContainer::<i32>::Empty;
-        let mut t = ExchangingTransfer::<i32, i32>::create_with_send(create_test_context(dispose_test, test_exchange.make_pointer()));
+        let mut test_slot = ReceiveSlot::<i32>::default();
+        let mut t = ExchangingTransfer::<i32, i32>::create_with_send(create_test_context(dispose_test, test_slot.publish()));
         t.dispose_with(5);
     }
-}
\ No newline at end of file
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn exchange_ref_try_send_hands_value_back() {
+        let mut slot = ReceiveSlot::<i32>::default();
+        let mut slot_ref = ExchangeContainerRef::new(&mut slot);
+        assert!(slot_ref.try_send_value(1).is_ok());
+        assert_eq!(slot_ref.try_send_value(2), Err(2));
+        assert_eq!(take(&mut slot), 1)
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn transfer_try_send_hands_value_back() {
+        let test_transfer = create_test_context(init_test, 0);
+        let mut transfer = ExchangingTransfer::<String, i32>::create_without_send(test_transfer);
+        let error = transfer.try_send("unknown".to_string()).unwrap_err();
+        assert_eq!(error.value, "unknown");
+        assert_eq!(error.to_string(), "failed to send: no receiving slot known");
+    }
+
+    #[cfg(debug_assertions)]
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn transfer_try_send_hands_value_back_on_full_slot() {
+        let mut test_slot = ReceiveSlot::<String>::default();
+        let test_transfer = create_test_context(init_test, test_slot.publish());
+        let mut transfer = ExchangingTransfer::<String, i32>::create_with_send(test_transfer);
+        assert!(transfer.try_send("first".to_string()).is_ok());
+        let error = transfer.try_send("second".to_string()).unwrap_err();
+        assert_eq!(error.value, "second");
+        assert_eq!(error.to_string(), "failed to send: receiving slot is not empty");
+        assert_eq!(take(&mut test_slot), "first".to_string());
+    }
+
+    #[cfg(debug_assertions)]
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    #[should_panic(expected = "exchange protocol violated on resume: resumed without a value; did the other side dispose? (state: PeerDisposed)")]
+    fn transfer_suspend_on_empty_resume() {
+        extern "C" fn resume_empty(t: Transfer) -> ! {
+            // switches back without sending anything nor offering a slot
+            unsafe { t.context.resume(0) };
+            unreachable!()
+        }
+        let mut transfer = ExchangingTransfer::<i32, i32>::create_without_send(create_test_context(resume_empty, 0));
+        transfer.suspend();
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn transfer_raw_round_trip() {
+        extern "C" fn double_test(t: Transfer) -> ! {
+            let mut trans = ExchangingTransfer::<i32, i32>::create_with_send(t);
+            let received = trans.yield_with(1);
+            trans.dispose_with(received * 2)
+        }
+        let mut transfer = ExchangingTransfer::<i32, i32>::create_without_send(create_test_context(double_test, 0));
+        assert_eq!(transfer.suspend(), 1);
+        let (raw, state) = transfer.into_raw();
+        let mut transfer = unsafe { ExchangingTransfer::<i32, i32>::from_raw(raw, state) };
+        assert_eq!(transfer.yield_with(5), 10);
+        assert!(!transfer.can_send());
+    }
+
+    #[cfg(debug_assertions)]
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    #[should_panic(expected = "exchange protocol violated on yield: the receiving slot of the other context must be known (state: Unconnected)")]
+    fn protocol_violation_yield_without_receiving_slot() {
+        let mut transfer = ExchangingTransfer::<i32, i32>::create_without_send(create_test_context(init_test, 0));
+        transfer.yield_with(1);
+    }
+
+    #[cfg(debug_assertions)]
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    #[should_panic(expected = "exchange protocol violated on dispose: the receiving slot of the other context must be known (state: Unconnected)")]
+    fn protocol_violation_dispose_without_receiving_slot() {
+        let mut transfer = ExchangingTransfer::<i32, i32>::create_without_send(create_test_context(init_test, 0));
+        transfer.dispose_with(1);
+    }
+
+    #[cfg(debug_assertions)]
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    #[should_panic(expected = "exchange protocol violated on suspend: the receive slot must be empty before suspending (state: Unconnected)")]
+    fn protocol_violation_suspend_with_full_receive_slot() {
+        let mut transfer = ExchangingTransfer::<i32, i32>::create_without_send(create_test_context(init_test, 0));
+        transfer.receive_slot = ReceiveSlot::filled_with(1);
+        transfer.suspend();
+    }
+
+    #[cfg(debug_assertions)]
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    #[should_panic(expected = "exchange protocol violated on suspend: the other context has been disposed and must not be resumed (state: PeerDisposed)")]
+    fn protocol_violation_resume_disposed_context() {
+        extern "C" fn dispose_test(t: Transfer) -> ! {
+            let mut trans = ExchangingTransfer::<i32, i32>::create_with_send(t);
+            trans.dispose_with(3)
+        }
+        let mut transfer = ExchangingTransfer::<i32, i32>::create_without_send(create_test_context(dispose_test, 0));
+        assert_eq!(transfer.suspend(), 3);
+        transfer.suspend();
+    }
+
+    #[test]
+    fn ring_exchange_is_first_in_first_out() {
+        let mut ring = RingExchange::<i32, 3>::new();
+        assert!(ring.is_empty());
+        for val in 0..3 {
+            assert_eq!(ring.push(val), Ok(()));
+        }
+        assert!(ring.is_full());
+        assert_eq!(ring.push(3), Err(3));
+        assert_eq!(ring.pop(), Some(0));
+        assert_eq!(ring.push(3), Ok(()));
+        assert_eq!(std::iter::from_fn(|| ring.pop()).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(ring.len(), 0);
+    }
+
+    thread_local! {
+        static CREATED: Cell<usize> = const { Cell::new(0) };
+        static DROPPED: Cell<usize> = const { Cell::new(0) };
+    }
+
+    /// Value counting its creations and drops, counted per thread as the context runs on the thread of the test
+    #[derive(Debug, PartialEq)]
+    struct Counted(usize);
+
+    impl Counted {
+        fn new(val: usize) -> Self {
+            CREATED.with(|created| created.set(created.get() + 1));
+            Self(val)
+        }
+    }
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            DROPPED.with(|dropped| dropped.set(dropped.get() + 1));
+        }
+    }
+
+    /// Produces ten counted values through rings of four and hands over the partially filled last one on dispose
+    extern "C" fn produce_buffered(t: Transfer) -> ! {
+        let mut trans = ExchangingTransfer::<RingExchange<Counted, 4>, ()>::create_with_send(t);
+        let mut ring = RingExchange::new();
+        for val in 0..10 {
+            trans.yield_buffered(&mut ring, Counted::new(val));
+        }
+        trans.dispose_with(ring)
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn ring_exchange_transfers_every_value_once() {
+        let stack = ProtectedFixedSizeStack::default();
+        let context = unsafe { Transfer::new(Context::new(&stack, produce_buffered), 0) };
+        let mut transfer = ExchangingTransfer::<(), RingExchange<Counted, 4>>::create_without_send(context);
+        let mut ring = transfer.suspend();
+        let mut received = Vec::new();
+        while let Some(Counted(val)) = transfer.drain(&mut ring, || ()) {
+            received.push(val);
+        }
+        assert_eq!(received, (0..10).collect::<Vec<_>>());
+        assert_eq!(CREATED.with(Cell::get), 10);
+        assert_eq!(DROPPED.with(Cell::get), 10);
+    }
+
+    /// Hands a ring filled up front to [ExchangingTransfer::yield_buffered], which has to pass it on before buffering the next value
+    extern "C" fn produce_prefilled(t: Transfer) -> ! {
+        let mut trans = ExchangingTransfer::<RingExchange<Counted, 4>, ()>::create_with_send(t);
+        let mut ring = RingExchange::new();
+        for val in 0..4 {
+            assert!(ring.push(Counted::new(val)).is_ok());
+        }
+        for val in 4..7 {
+            trans.yield_buffered(&mut ring, Counted::new(val));
+        }
+        trans.dispose_with(ring)
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn full_ring_is_handed_over_before_buffering() {
+        let stack = ProtectedFixedSizeStack::default();
+        let context = unsafe { Transfer::new(Context::new(&stack, produce_prefilled), 0) };
+        let mut transfer = ExchangingTransfer::<(), RingExchange<Counted, 4>>::create_without_send(context);
+        let mut ring = transfer.suspend();
+        assert_eq!(ring.len(), 4);
+        let mut received = Vec::new();
+        while let Some(Counted(val)) = transfer.drain(&mut ring, || ()) {
+            received.push(val);
+        }
+        assert_eq!(received, (0..7).collect::<Vec<_>>());
+        assert_eq!(CREATED.with(Cell::get), 7);
+        assert_eq!(DROPPED.with(Cell::get), 7);
+    }
+
+    #[test]
+    #[should_panic(expected = "ring exchange must hold at least one value")]
+    fn ring_exchange_of_zero_values_panics() {
+        RingExchange::<i32, 0>::new();
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn ring_exchange_drops_values_left_after_completion() {
+        let stack = ProtectedFixedSizeStack::default();
+        let context = unsafe { Transfer::new(Context::new(&stack, produce_buffered), 0) };
+        let mut transfer = ExchangingTransfer::<(), RingExchange<Counted, 4>>::create_without_send(context);
+        let mut ring = transfer.suspend();
+        for val in 0..9 {
+            assert_eq!(transfer.drain(&mut ring, || ()).map(|counted| counted.0), Some(val));
+        }
+        assert!(!transfer.can_send());
+        assert_eq!(ring.len(), 1);
+        drop(ring);
+        assert_eq!(CREATED.with(Cell::get), 10);
+        assert_eq!(DROPPED.with(Cell::get), 10);
+    }
+}
@@ -1,3 +1,4 @@
+use std::convert::Infallible;
 use std::ops::{Deref, DerefMut};
 
 /// Wraps a value that is about to be replaced by a value generated by a destructive operation on the value itself
@@ -17,7 +18,7 @@ use std::ops::{Deref, DerefMut};
 /// However since the operation is somewhat "semanticly atomic" (i.e. the attribute will be valid before and after method call) as long as Incremented::increment does not know about container and the container is not synced between theads, it is safe to assume that container.0 is always in a valid state
 /// SelfUpdating simulates this by implementing a smartpointer over a value that may be updated by moving the original value out inside a passed closure by v.update():
 ///
-/// ```
+/// ```ignore
 /// pub struct Container(SelfUpdating<Incremented>);
 /// impl Container {
 ///     pub fn increment(&mut self) {
@@ -25,19 +26,49 @@ use std::ops::{Deref, DerefMut};
 ///     }
 /// }
 /// ```
+///
+/// If the operation passed to [update] panics, there is no value left to put back, which poisons the SelfUpdating:
+/// any further access panics naming the earlier panic as cause rather than failing somewhere unrelated
 pub struct SelfUpdating<T>(Option<T>);
 
+const POISONED: &str = "SelfUpdating poisoned by earlier panic";
+
 impl<T> SelfUpdating<T> {
     pub fn of(initial: T) -> Self {
         Self(Some(initial))
     }
 
+    #[allow(dead_code)]
     pub fn update<F: FnOnce(T) -> T>(&mut self, op: F) {
-        self.0 = Some(op(self.0.take().unwrap()))
+        self.0 = Some(op(self.take()))
+    }
+
+    /// Like [update] but [op] returns an additional result alongside the new value, which is handed out
+    pub fn returning_update<R, F: FnOnce(T) -> (T, R)>(&mut self, op: F) -> R {
+        let (updated, result) = op(self.take());
+        self.0 = Some(updated);
+        result
+    }
+
+    /// Hands the value to a terminal operation which never gives it back, e.g. as it leaves the current context for good
+    /// Diverging closures can't be typed as returning ! on stable, so [op] returns [Infallible] which it can't ever construct
+    pub fn consume<F: FnOnce(T) -> Infallible>(&mut self, op: F) -> ! {
+        match op(self.take()) {}
+    }
+
+    /// Queries whether an operation passed to [update] (or [returning_update] or [consume]) has panicked, which left no value behind
+    #[allow(dead_code)]
+    pub fn is_poisoned(&self) -> bool {
+        self.0.is_none()
+    }
+
+    /// Moves the value out for an operation, which poisons the SelfUpdating until a new value is put back
+    fn take(&mut self) -> T {
+        self.0.take().expect(POISONED)
     }
 
     #[allow(dead_code)]
-    pub fn unwrap(mut self) -> T {self.0.take().unwrap()}
+    pub fn unwrap(mut self) -> T {self.take()}
 }
 
 impl<T> From<T> for SelfUpdating<T> {
@@ -49,13 +80,13 @@ impl<T> Deref for SelfUpdating<T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
-        self.0.as_ref().unwrap()
+        self.0.as_ref().expect(POISONED)
     }
 }
 
 impl<T> DerefMut for SelfUpdating<T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        self.0.as_mut().unwrap()
+        self.0.as_mut().expect(POISONED)
     }
 }
 
@@ -96,5 +127,30 @@ mod tests {
         assert_eq!(self_updating.unwrap(),"testtest");
     }
 
+    #[test]
+    fn self_updating_perform_returning_update() {
+        let mut self_updating=SelfUpdating::of(String::from("test"));
+        let len=self_updating.returning_update(|s| { let len=s.len(); (s.repeat(2), len) });
+        assert_eq!(len,4);
+        assert_eq!(self_updating.unwrap(),"testtest");
+    }
 
-}
\ No newline at end of file
+    #[test]
+    #[should_panic(expected = "consumed test")]
+    fn self_updating_consume() {
+        let mut self_updating=SelfUpdating::of(String::from("test"));
+        self_updating.consume(|s| panic!("consumed {}", s));
+    }
+
+    #[test]
+    fn self_updating_poisoned_by_panicking_update() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let mut self_updating=SelfUpdating::of(String::from("test"));
+        assert!(!self_updating.is_poisoned());
+        assert!(catch_unwind(AssertUnwindSafe(|| self_updating.update(|_| panic!("update failed")))).is_err());
+        assert!(self_updating.is_poisoned());
+        let payload=catch_unwind(AssertUnwindSafe(|| self_updating.len())).unwrap_err();
+        assert_eq!(payload.downcast_ref::<String>().unwrap(),"SelfUpdating poisoned by earlier panic");
+    }
+}
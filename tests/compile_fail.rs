@@ -0,0 +1,8 @@
+//! Checks that borrows of generators and coroutines the lifetimes are meant to rule out are rejected by the compiler
+//! The expected errors are kept next to the cases in tests/ui, run with TRYBUILD=overwrite to update them after a compiler upgrade
+#[cfg_attr(miri, ignore)]
+#[test]
+fn borrowing_misuse_does_not_compile() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,44 @@
+#![cfg(feature = "metrics")]
+// kept as the only test of this binary, since the counter is shared by all threads of the process
+
+use rusterators::generators::{BoostedGenerator, BoringGenerator, GeneratorChannel, IterGenerator};
+use rusterators::metrics;
+
+#[test]
+fn counts_context_switches_of_generator() {
+    metrics::reset();
+    let generator = BoringGenerator::new(|chan| {
+        for i in 0..5 {
+            chan.yield_val(i);
+        }
+    });
+    assert_eq!(metrics::context_switches(), 0);
+    assert_eq!(generator.collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+    // starting the context and its initial suspend, then switching in and out for each of the 5 yields and the return
+    assert_eq!(metrics::context_switches(), 2 + 2 * 6);
+    metrics::reset();
+    assert_eq!(metrics::context_switches(), 0);
+
+    // wrapping an iterator doesn't need a context at all
+    assert_eq!(IterGenerator::new(0..5).collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+    assert_eq!(metrics::context_switches(), 0);
+
+    // values of nested generators are resumed right from the outermost one, so each costs two context switches whatever the depth
+    let switches = |values: u32| {
+        metrics::reset();
+        assert_eq!(nested(10, values).count(), values as usize);
+        metrics::context_switches()
+    };
+    assert_eq!(switches(10) - switches(5), 2 * 5);
+}
+
+/// Yields [values] numbers from within [depth] generators yielding from each other
+fn nested(depth: u32, values: u32) -> BoostedGenerator<'static, u32, (), ()> {
+    BoostedGenerator::new(move |chan| {
+        if depth == 0 {
+            chan.yield_all(0..values);
+        } else {
+            chan.yield_from(nested(depth - 1, values)).unwrap();
+        }
+    })
+}
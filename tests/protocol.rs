@@ -0,0 +1,283 @@
+//! Property based tests running random interaction scripts against generators and coroutines
+//! A script yields payloads of random sizes, returns early or panics at random points and nests generators by yield_from,
+//! while the driver may drop the generator after a random number of resumes
+use std::cell::Cell;
+use std::ops::ControlFlow;
+use std::panic;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Once};
+
+use proptest::prelude::*;
+
+use rusterators::coroutines::{Coroutine, CoroutineError, DefaultStackAllocator, OwnedStack, ResumeResult, StackAllocator, StackError, StackFactory};
+use rusterators::generators::{BoostedGenerator, Generator, GeneratorChannel, ResultingGenerator};
+
+const STACK_SIZE: usize = 64 * 1024;
+const SCRIPT_PANIC: &str = "script panic";
+
+#[derive(Debug, Clone)]
+enum Step {
+    /// Yields a payload of the given size
+    Yield(usize),
+    /// Yields from a generator running the nested steps, which returns 0 unless it returns early
+    Nested(Vec<Step>),
+    /// Returns from the current generator
+    Return(u32),
+    Panic,
+}
+
+#[derive(Debug, Clone)]
+struct Script {
+    steps: Vec<Step>,
+    /// Returned once all steps have been run
+    result: u32,
+    /// Number of resumes after which the driver drops the generator
+    drop_after: Option<usize>,
+}
+
+/// The way a script completes according to [expect]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Returned(u32),
+    Panicked,
+}
+
+/// Yielded value carrying its position within the script, filled with a pattern derived from it
+/// Counts the payloads alive in [Env::payloads], so duplicated or leaked values are noticed
+struct Payload {
+    seq: usize,
+    data: Vec<u8>,
+    alive: Rc<Cell<usize>>,
+}
+
+impl Drop for Payload {
+    fn drop(&mut self) {
+        self.alive.set(self.alive.get() - 1);
+    }
+}
+
+/// Stack allocator counting the stacks which have not been handed back yet
+#[derive(Default)]
+struct CountingAllocator(AtomicUsize);
+
+impl StackAllocator for CountingAllocator {
+    fn allocate(&self, size: usize) -> Result<OwnedStack, StackError> {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        DefaultStackAllocator.allocate(size)
+    }
+
+    fn deallocate(&self, stack: OwnedStack) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+        DefaultStackAllocator.deallocate(stack)
+    }
+}
+
+/// State shared by all generators of a script run
+#[derive(Clone)]
+struct Env {
+    stacks: Arc<CountingAllocator>,
+    payloads: Rc<Cell<usize>>,
+    next_seq: Rc<Cell<usize>>,
+}
+
+impl Env {
+    fn new() -> Self {
+        Self { stacks: Arc::default(), payloads: Rc::default(), next_seq: Rc::default() }
+    }
+
+    fn factory(&self) -> StackFactory {
+        StackFactory::with_allocator(self.stacks.clone(), STACK_SIZE)
+    }
+
+    fn payload(&self, size: usize) -> Payload {
+        let seq = self.next_seq.get();
+        self.next_seq.set(seq + 1);
+        self.payloads.set(self.payloads.get() + 1);
+        Payload { seq, data: vec![seq as u8; size], alive: self.payloads.clone() }
+    }
+
+    /// Asserts that neither stacks nor payloads are left once everything has been dropped
+    fn assert_released(&self) {
+        assert_eq!(self.stacks.0.load(Ordering::SeqCst), 0, "leaked stacks");
+        assert_eq!(self.payloads.get(), 0, "leaked payloads");
+    }
+}
+
+/// Runs [steps] yielding through [chan], breaks with the returned value on an early return
+fn interpret<'a, C>(chan: &mut C, steps: &[Step], env: &Env) -> ControlFlow<u32>
+    where C: GeneratorChannel<'a, Yield=Payload, Receive=()> {
+    for step in steps {
+        match step {
+            Step::Yield(size) => chan.yield_val(env.payload(*size)),
+            Step::Nested(nested) => {
+                // a panic of the nested generator is propagated like generators nested by hand do
+                if let Err(e) = chan.yield_from(nested_generator(nested.clone(), 0, env.clone())) {
+                    panic!("{}", e);
+                }
+            }
+            Step::Return(result) => return ControlFlow::Break(*result),
+            Step::Panic => panic!("{}", SCRIPT_PANIC),
+        }
+    }
+    ControlFlow::Continue(())
+}
+
+fn nested_generator<'a>(steps: Vec<Step>, result: u32, env: Env) -> BoostedGenerator<'a, Payload, u32, ()> {
+    BoostedGenerator::new_with_factory(env.factory(), move |chan| {
+        match interpret(chan, &steps, &env) {
+            ControlFlow::Break(early) => early,
+            ControlFlow::Continue(()) => result,
+        }
+    })
+}
+
+/// Determines the sizes of the payloads [steps] yield in order and how they complete
+fn expect(steps: &[Step], sizes: &mut Vec<usize>) -> ControlFlow<Outcome> {
+    for step in steps {
+        match step {
+            Step::Yield(size) => sizes.push(*size),
+            Step::Nested(nested) => {
+                if let ControlFlow::Break(Outcome::Panicked) = expect(nested, sizes) {
+                    return ControlFlow::Break(Outcome::Panicked);
+                }
+            }
+            Step::Return(result) => return ControlFlow::Break(Outcome::Returned(*result)),
+            Step::Panic => return ControlFlow::Break(Outcome::Panicked),
+        }
+    }
+    ControlFlow::Continue(())
+}
+
+/// Checks the payloads observed by a driver of [script] and its completion, which is None if the driver dropped the generator before
+fn check(script: &Script, observed: &[Payload], completion: Option<Outcome>) {
+    let mut sizes = Vec::new();
+    let outcome = match expect(&script.steps, &mut sizes) {
+        ControlFlow::Break(outcome) => outcome,
+        ControlFlow::Continue(()) => Outcome::Returned(script.result),
+    };
+    let resumes = script.drop_after.unwrap_or(usize::MAX);
+    let expected_yields = sizes.len().min(resumes);
+    assert_eq!(observed.len(), expected_yields, "number of yielded values");
+    for (seq, (payload, size)) in observed.iter().zip(&sizes).enumerate() {
+        assert_eq!(payload.seq, seq, "order of yielded values");
+        assert_eq!(payload.data.len(), *size);
+        assert!(payload.data.iter().all(|b| *b == seq as u8), "corrupted payload");
+    }
+    // the completing resume follows the last yield
+    let expected_completion = if resumes > sizes.len() { Some(outcome) } else { None };
+    assert_eq!(completion, expected_completion, "completion");
+}
+
+/// Silences the panic messages of the scripts, including those rethrown by the generators they are nested in
+fn silence_script_panics() {
+    static SILENCE: Once = Once::new();
+    SILENCE.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let message = info.payload().downcast_ref::<String>().map(String::as_str)
+                .or_else(|| info.payload().downcast_ref::<&str>().copied());
+            if !message.is_some_and(|message| message.contains(SCRIPT_PANIC)) {
+                previous(info)
+            }
+        }));
+    });
+}
+
+fn step() -> impl Strategy<Value=Step> {
+    let leaf = prop_oneof![
+        8 => (0..512usize).prop_map(Step::Yield),
+        1 => any::<u32>().prop_map(Step::Return),
+        1 => Just(Step::Panic),
+    ];
+    leaf.prop_recursive(3, 48, 6, |step| prop::collection::vec(step, 0..6).prop_map(Step::Nested))
+}
+
+fn script() -> impl Strategy<Value=Script> {
+    (prop::collection::vec(step(), 0..12), any::<u32>(), prop::option::of(0..16usize))
+        .prop_map(|(steps, result, drop_after)| Script { steps, result, drop_after })
+}
+
+proptest! {
+    #[test]
+    fn generator_follows_script(script in script()) {
+        silence_script_panics();
+        let env = Env::new();
+        let mut observed = Vec::new();
+        let mut completion = None;
+        {
+            let mut gen = nested_generator(script.steps.clone(), script.result, env.clone());
+            for _ in 0..script.drop_after.unwrap_or(usize::MAX) {
+                match gen.resume_checked(()) {
+                    Ok(Some(payload)) => observed.push(payload),
+                    Ok(None) => {
+                        completion = Some(Outcome::Returned(gen.result().expect("generator returned")));
+                        break;
+                    }
+                    Err(CoroutineError::Panicked(_)) => {
+                        assert!(gen.has_completed());
+                        assert!(gen.result().is_err());
+                        completion = Some(Outcome::Panicked);
+                        break;
+                    }
+                    Err(e) => panic!("unexpected failure {:?}", e),
+                }
+            }
+        }
+        check(&script, &observed, completion);
+        drop(observed);
+        env.assert_released();
+    }
+
+    #[test]
+    fn coroutine_follows_script(script in script()) {
+        silence_script_panics();
+        let env = Env::new();
+        let mut observed = Vec::new();
+        let mut completion = None;
+        {
+            let routine_env = env.clone();
+            let steps = script.steps.clone();
+            let result = script.result;
+            let mut co = Coroutine::<Payload, u32, ()>::new_with_factory(env.factory(), move |chan, _| {
+                let mut chan = CoroutineYield(chan);
+                match interpret(&mut chan, &steps, &routine_env) {
+                    ControlFlow::Break(early) => early,
+                    ControlFlow::Continue(()) => result,
+                }
+            });
+            for _ in 0..script.drop_after.unwrap_or(usize::MAX) {
+                match co.resume_checked(()) {
+                    Ok(ResumeResult::Yield(payload)) => observed.push(payload),
+                    Ok(ResumeResult::Return(result)) => {
+                        completion = Some(Outcome::Returned(result));
+                        break;
+                    }
+                    Err(CoroutineError::Panicked(_)) => {
+                        completion = Some(Outcome::Panicked);
+                        break;
+                    }
+                    Err(e) => panic!("unexpected failure {:?}", e),
+                }
+            }
+            if completion.is_some() {
+                assert!(co.is_completed());
+            }
+        }
+        check(&script, &observed, completion);
+        drop(observed);
+        env.assert_released();
+    }
+}
+
+/// Lets scripts yield through the channel of a plain coroutine
+struct CoroutineYield<'c, 'a>(&'c mut rusterators::coroutines::CoroutineChannel<'a, Payload, u32, ()>);
+
+impl<'c, 'a> GeneratorChannel<'c> for CoroutineYield<'c, 'a> {
+    type Yield = Payload;
+    type Receive = ();
+
+    fn yield_val(&mut self, val: Payload) {
+        self.0.suspend(val)
+    }
+}
@@ -0,0 +1,47 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use rusterators::coroutines::{ResumeResult, SendCoroutine};
+
+/// Worker threads take turns resuming a single coroutine guarded by a mutex
+#[test]
+fn workers_take_turns_resuming_shared_coroutine() {
+    const WORKERS: usize = 4;
+    const ROUNDS: usize = 25;
+
+    let co = unsafe {
+        SendCoroutine::new(|chan, mut worker: usize| {
+            let mut visits = vec![0; WORKERS];
+            let mut buffer = Vec::new();
+            for _ in 0..WORKERS * ROUNDS {
+                visits[worker] += 1;
+                buffer.push(worker);
+                worker = chan.suspend(buffer.len());
+            }
+            visits
+        })
+    };
+    let co = Arc::new(Mutex::new(co));
+
+    let workers: Vec<_> = (0..WORKERS).map(|worker| {
+        let co = co.clone();
+        thread::spawn(move || {
+            for _ in 0..ROUNDS {
+                match co.lock().unwrap().resume(worker) {
+                    ResumeResult::Yield(_) => {}
+                    ResumeResult::Return(_) => panic!("coroutine returned too early"),
+                }
+            }
+        })
+    }).collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+
+    let mut co = co.lock().unwrap();
+    match co.resume(0) {
+        ResumeResult::Return(visits) => assert_eq!(visits, vec![ROUNDS; WORKERS]),
+        ResumeResult::Yield(_) => panic!("coroutine should have returned"),
+    }
+    assert!(co.is_completed());
+}
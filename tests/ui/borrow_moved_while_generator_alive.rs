@@ -0,0 +1,9 @@
+use rusterators::generators::{BoostedGenerator, GeneratorChannel};
+
+// borrowed data can't be moved away while a generator borrowing it is alive
+fn main() {
+    let text = String::from("a bc def");
+    let lengths = BoostedGenerator::new(|chan| chan.yield_all(text.split(' ').map(str::len)));
+    drop(text);
+    lengths.count();
+}
@@ -0,0 +1,11 @@
+use rusterators::generators::{BoostedGenerator, GeneratorChannel};
+
+// a generator borrowing a local can't be returned from the function owning it
+fn words() -> BoostedGenerator<'static, usize, (), ()> {
+    let text = String::from("a bc def");
+    BoostedGenerator::new(|chan| chan.yield_all(text.split(' ').map(str::len)))
+}
+
+fn main() {
+    drop(words());
+}
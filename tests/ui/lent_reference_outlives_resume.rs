@@ -0,0 +1,10 @@
+use rusterators::coroutines::LendingCoroutine;
+
+// the reference lent by a resume is only valid until the routine suspends again
+fn main() {
+    let _co = LendingCoroutine::<(), (), Vec<i32>>::new(|chan| {
+        let mut first = chan.current();
+        chan.suspend(());
+        first.push(1);
+    });
+}
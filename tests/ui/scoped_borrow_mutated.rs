@@ -0,0 +1,12 @@
+use rusterators::coroutines::scope;
+use rusterators::generators::GeneratorChannel;
+
+// borrowed data can't be changed while a scoped generator is alive
+fn main() {
+    let mut text = String::from("a bc def");
+    scope(|s| {
+        let lengths = s.generator(|chan| chan.yield_all(text.split(' ').map(str::len)));
+        text.clear();
+        lengths.count()
+    });
+}
@@ -0,0 +1,9 @@
+use rusterators::coroutines::scope;
+use rusterators::generators::GeneratorChannel;
+
+// a generator created by a scope is bound to it and can't be handed out
+fn main() {
+    let text = String::from("a bc def");
+    let lengths = scope(|s| s.generator(|chan| chan.yield_all(text.split(' ').map(str::len))));
+    drop(lengths);
+}
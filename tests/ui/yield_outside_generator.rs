@@ -0,0 +1,12 @@
+use rusterators::generator;
+use rusterators::generators::BoostedGenerator;
+
+// yield_! only exists within the block of generator!, not in the functions it calls
+fn helper() {
+    yield_!(1);
+}
+
+fn main() {
+    let gen: BoostedGenerator<i32, (), ()> = generator! { helper() };
+    drop(gen);
+}